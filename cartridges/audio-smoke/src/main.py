@@ -5,15 +5,24 @@ class AudioSmoke(vcon.Game):
     def __init__(self):
         self.ticks = 0
         self.last_voice_id = None
+        self.finished_voices = 0
 
     def on_boot(self):
+        vcon.audio.set_voice_limit(2, steal_policy="oldest")
+        vcon.audio.set_bus_volume("sfx", 0.9)
+        vcon.audio.set_ducking(gain=0.5, fade_seconds=0.05)
         vcon.audio.play_music("bgm_smoke", volume=0.35, looped=True)
 
     def on_update(self, dt_fixed):
         self.ticks += 1
 
         if self.ticks % 8 == 0:
-            vcon.audio.play_sfx(f"ping_{self.ticks}", volume=0.8)
+            vcon.audio.play_sfx("ping", volume=0.8)
+
+        if self.ticks == 12:
+            # Burst past the voice limit; music outranks SFX so pings steal each other.
+            for _ in range(3):
+                vcon.audio.play_sfx("ping", volume=0.5, priority=10)
 
         voices = vcon.audio.active_voices()
         if voices:
@@ -22,19 +31,23 @@ class AudioSmoke(vcon.Game):
         if self.ticks == 24 and self.last_voice_id is not None:
             vcon.audio.stop_voice(self.last_voice_id)
 
+    def on_event(self, event):
+        if event.get("type") == "audio.finished":
+            self.finished_voices += 1
+
     def on_render(self, alpha):
         health = vcon.audio.health()
         vcon.graphics.clear((10, 14, 20, 255))
         vcon.graphics.text("Audio Smoke", 24, 24, size=26, color=(255, 255, 255, 255))
         vcon.graphics.text(
-            f"voices: {len(vcon.audio.active_voices())}",
+            f"voices: {len(vcon.audio.active_voices())} finished: {self.finished_voices}",
             24,
             62,
             size=18,
             color=(220, 240, 255, 255),
         )
         vcon.graphics.text(
-            f"q={health.get('queued_buffers', 0)} u={health.get('underruns', 0)} o={health.get('overruns', 0)} d={health.get('dropped_buffers', 0)}",
+            f"q={health.get('queued_buffers', 0)} u={health.get('underruns', 0)} o={health.get('overruns', 0)} d={health.get('dropped_buffers', 0)} s={health.get('voices_stolen', 0)} duck={health['buses']['music']['duck_gain']:.2f}",
             24,
             90,
             size=16,
@@ -1,8 +1,302 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const MIX_SAMPLE_RATE: u32 = 48_000;
+pub const MIX_CHANNELS: usize = 2;
+pub const DEFAULT_MAX_VOICES: usize = 32;
+pub const DEFAULT_SFX_PRIORITY: u8 = 64;
+pub const DEFAULT_MUSIC_PRIORITY: u8 = 192;
+pub const MAX_SYNTH_FREQUENCY: f32 = 20_000.0;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct PlayRequest {
     pub clip_id: String,
     pub volume: f32,
     pub looped: bool,
+    /// Higher values win; a request may only steal voices of equal or lower priority.
+    pub priority: u8,
+    pub bus: AudioBus,
+    /// Seconds to ramp from silence up to `volume`; 0 starts at full volume.
+    pub fade_in_seconds: f32,
+    /// Plays a generated tone instead of resolving `clip_id` against the clip store.
+    pub synth: Option<SynthTone>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SynthWaveform {
+    Square,
+    Triangle,
+    Saw,
+    Noise,
+}
+
+impl SynthWaveform {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "square" => Some(Self::Square),
+            "triangle" => Some(Self::Triangle),
+            "saw" => Some(Self::Saw),
+            "noise" => Some(Self::Noise),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Square => "square",
+            Self::Triangle => "triangle",
+            Self::Saw => "saw",
+            Self::Noise => "noise",
+        }
+    }
+}
+
+/// Attack/decay/release are in seconds; `sustain` is the held level in [0, 1].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdsrEnvelope {
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub release: f32,
+}
+
+impl Default for AdsrEnvelope {
+    fn default() -> Self {
+        Self {
+            attack: 0.005,
+            decay: 0.05,
+            sustain: 0.7,
+            release: 0.05,
+        }
+    }
+}
+
+impl AdsrEnvelope {
+    /// Envelope level `t` seconds after note-on for a note held for `gate` seconds.
+    /// Release starts from whatever level the note had reached when the gate closed.
+    pub fn level(&self, t: f32, gate: f32) -> f32 {
+        if t < gate {
+            return self.held_level(t);
+        }
+        if self.release <= 0.0 {
+            return 0.0;
+        }
+        let released = (t - gate) / self.release;
+        self.held_level(gate) * (1.0 - released).max(0.0)
+    }
+
+    fn held_level(&self, t: f32) -> f32 {
+        if t < self.attack {
+            t / self.attack
+        } else if t < self.attack + self.decay {
+            1.0 - (1.0 - self.sustain) * (t - self.attack) / self.decay
+        } else {
+            self.sustain
+        }
+    }
+}
+
+/// Oscillator note: `duration_seconds` is the gate time, so the voice lasts
+/// `duration_seconds + envelope.release`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SynthTone {
+    pub wave: SynthWaveform,
+    pub frequency: f32,
+    pub duration_seconds: f32,
+    pub envelope: AdsrEnvelope,
+}
+
+impl SynthTone {
+    /// Clip-style id reported for synth voices (`synth:<wave>`).
+    pub fn clip_id(&self) -> String {
+        format!("synth:{}", self.wave.as_str())
+    }
+
+    fn total_frames(&self, sample_rate: u32) -> usize {
+        let seconds = self.duration_seconds + self.envelope.release;
+        (seconds as f64 * sample_rate as f64).ceil() as usize
+    }
+
+    fn sanitized(self) -> Self {
+        let seconds = |value: f32| {
+            if value.is_finite() {
+                value.max(0.0)
+            } else {
+                0.0
+            }
+        };
+        Self {
+            wave: self.wave,
+            frequency: if self.frequency.is_finite() {
+                self.frequency.clamp(1.0, MAX_SYNTH_FREQUENCY)
+            } else {
+                1.0
+            },
+            duration_seconds: seconds(self.duration_seconds),
+            envelope: AdsrEnvelope {
+                attack: seconds(self.envelope.attack),
+                decay: seconds(self.envelope.decay),
+                sustain: unit_volume(self.envelope.sustain),
+                release: seconds(self.envelope.release),
+            },
+        }
+    }
+}
+
+/// Oscillator state of a playing synth voice. Noise uses a 15-bit LFSR clocked at the
+/// tone frequency, so output is identical on every run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SynthVoice {
+    pub tone: SynthTone,
+    phase: f64,
+    noise: u16,
+}
+
+impl SynthVoice {
+    fn new(tone: SynthTone) -> Self {
+        Self {
+            tone,
+            phase: 0.0,
+            noise: 1,
+        }
+    }
+
+    fn next_sample(&mut self, elapsed_seconds: f32, out_rate: f64, pitch: f32) -> f32 {
+        let value = match self.tone.wave {
+            SynthWaveform::Square => {
+                if self.phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            SynthWaveform::Triangle => 1.0 - 4.0 * (self.phase as f32 - 0.5).abs(),
+            SynthWaveform::Saw => 2.0 * self.phase as f32 - 1.0,
+            SynthWaveform::Noise => {
+                if self.noise & 1 == 1 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+        };
+
+        self.phase += self.tone.frequency as f64 * pitch as f64 / out_rate;
+        while self.phase >= 1.0 {
+            self.phase -= 1.0;
+            let feedback = (self.noise ^ (self.noise >> 1)) & 1;
+            self.noise = (self.noise >> 1) | (feedback << 14);
+        }
+
+        let level = self
+            .tone
+            .envelope
+            .level(elapsed_seconds, self.tone.duration_seconds);
+        value * level
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioBus {
+    Music,
+    Sfx,
+    Ui,
+}
+
+impl AudioBus {
+    pub const ALL: [AudioBus; 3] = [AudioBus::Music, AudioBus::Sfx, AudioBus::Ui];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "music" => Some(Self::Music),
+            "sfx" => Some(Self::Sfx),
+            "ui" => Some(Self::Ui),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Music => "music",
+            Self::Sfx => "sfx",
+            Self::Ui => "ui",
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Self::Music => 0,
+            Self::Sfx => 1,
+            Self::Ui => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioBusState {
+    pub bus: AudioBus,
+    pub volume: f32,
+    pub muted: bool,
+    /// Current ducking gain applied on top of `volume` (1.0 when not ducked).
+    pub duck_gain: f32,
+}
+
+impl AudioBusState {
+    fn new(bus: AudioBus) -> Self {
+        Self {
+            bus,
+            volume: 1.0,
+            muted: false,
+            duck_gain: 1.0,
+        }
+    }
+
+    fn gain(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume
+        }
+    }
+}
+
+/// Music ducking: while any voice on another bus is active the music bus ramps linearly
+/// to `gain`, and back to unity once those voices end, over `fade_seconds`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DuckingConfig {
+    pub gain: f32,
+    pub fade_seconds: f32,
+}
+
+/// Victim selection once the mixer is at its polyphony limit. Every policy only
+/// considers voices whose priority does not exceed the incoming request's, and breaks
+/// ties by voice id so stealing is deterministic.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VoiceStealPolicy {
+    #[default]
+    Oldest,
+    Quietest,
+    LowestPriority,
+}
+
+impl VoiceStealPolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "oldest" => Some(Self::Oldest),
+            "quietest" => Some(Self::Quietest),
+            "lowest_priority" => Some(Self::LowestPriority),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Oldest => "oldest",
+            Self::Quietest => "quietest",
+            Self::LowestPriority => "lowest_priority",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -11,46 +305,390 @@ pub struct ActiveVoice {
     pub clip_id: String,
     pub volume: f32,
     pub looped: bool,
+    pub priority: u8,
+    pub bus: AudioBus,
+    /// Stereo balance in [-1, 1]; the far channel is attenuated linearly, centre is unity.
+    pub pan: f32,
+    /// Playback rate multiplier applied on top of resampling to the mix rate.
+    pub pitch: f32,
+    pub fade: Option<VoiceFade>,
+    pub synth: Option<SynthVoice>,
+    /// Playback position in clip frames (fractional when the clip is resampled); synth
+    /// voices count elapsed output frames.
+    pub cursor: f64,
+    /// Clip length in frames; resolved against the clip store on the first mixed step.
+    /// Synth voices know their length (gate plus release) when they start.
+    pub length_frames: usize,
+}
+
+/// Linear volume ramp advanced once per mixed output frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VoiceFade {
+    pub target: f32,
+    pub frames_remaining: u64,
+    /// Removes the voice once the ramp completes (fade-out stop).
+    pub stop_when_done: bool,
+}
+
+/// Partial update for a playing voice; `None` fields are left unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VoiceParams {
+    pub volume: Option<f32>,
+    pub pan: Option<f32>,
+    pub pitch: Option<f32>,
+}
+
+pub const MIN_VOICE_PITCH: f32 = 0.125;
+pub const MAX_VOICE_PITCH: f32 = 8.0;
+
+/// Clamps a gain to `0.0..=1.0`; NaN and infinities play silent rather than poisoning the mix.
+fn unit_volume(volume: f32) -> f32 {
+    if volume.is_finite() {
+        volume.clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+impl ActiveVoice {
+    fn advance_fade(&mut self) {
+        let Some(fade) = &mut self.fade else {
+            return;
+        };
+        if fade.frames_remaining == 0 {
+            self.volume = fade.target;
+        } else {
+            self.volume += (fade.target - self.volume) / fade.frames_remaining as f32;
+            fade.frames_remaining -= 1;
+        }
+        if fade.frames_remaining == 0 {
+            self.volume = fade.target;
+            if !fade.stop_when_done {
+                self.fade = None;
+            }
+        }
+    }
+
+    fn fade_stopped(&self) -> bool {
+        matches!(self.fade, Some(f) if f.stop_when_done && f.frames_remaining == 0)
+    }
+}
+
+/// Decoded clip stored as interleaved stereo f32 samples at the clip's native rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioClip {
+    pub sample_rate: u32,
+    pub samples: Vec<f32>,
+}
+
+impl AudioClip {
+    pub fn frame_count(&self) -> usize {
+        self.samples.len() / MIX_CHANNELS
+    }
+
+    fn frame(&self, index: usize) -> [f32; 2] {
+        let i = index * MIX_CHANNELS;
+        [self.samples[i], self.samples[i + 1]]
+    }
+
+    /// Linearly interpolated frame at a fractional `cursor`; looped clips blend the last
+    /// frame into the first, one-shots into silence.
+    fn sample_linear(&self, cursor: f64, looped: bool) -> [f32; 2] {
+        let len = self.frame_count();
+        let base = cursor.floor();
+        let t = (cursor - base) as f32;
+        let i0 = base as usize;
+        let i1 = if i0 + 1 < len {
+            Some(i0 + 1)
+        } else if looped {
+            Some(0)
+        } else {
+            None
+        };
+        let a = self.frame(i0);
+        let b = i1.map(|i| self.frame(i)).unwrap_or([0.0, 0.0]);
+        [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t]
+    }
 }
 
 #[derive(Debug, Default, Clone)]
+pub struct AudioClipStore {
+    clips: HashMap<String, AudioClip>,
+}
+
+impl AudioClipStore {
+    pub fn insert(&mut self, id: impl Into<String>, clip: AudioClip) {
+        self.clips.insert(id.into(), clip);
+    }
+
+    pub fn clip(&self, id: &str) -> Option<&AudioClip> {
+        self.clips.get(id)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.clips.contains_key(id)
+    }
+
+    /// Clip ids in sorted order.
+    pub fn ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.clips.keys().map(String::as_str).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    pub fn len(&self) -> usize {
+        self.clips.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clips.is_empty()
+    }
+}
+
+const CLIP_CALLS: &[&str] = &["play_sfx(", "play_music(", "crossfade_music("];
+
+/// Clip ids passed as string literals to `play_sfx`/`play_music`/`crossfade_music` in
+/// cartridge source, sorted and deduplicated. Ids built at runtime are not visible here.
+pub fn literal_clip_references(source: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    for line in source.lines() {
+        let content = strip_line_comment(line);
+        for call in CLIP_CALLS {
+            let mut rest = content;
+            while let Some(pos) = rest.find(call) {
+                let preceded_by_ident = rest[..pos]
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c.is_alphanumeric() || c == '_');
+                rest = &rest[pos + call.len()..];
+                if preceded_by_ident {
+                    continue;
+                }
+                if let Some(id) = leading_string_literal(rest) {
+                    out.push(id.to_owned());
+                }
+            }
+        }
+    }
+    out.sort();
+    out.dedup();
+    out
+}
+
+/// Cuts a `#` comment off `line`, ignoring `#` inside string literals.
+fn strip_line_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &line[..i],
+            None => {}
+        }
+    }
+    line
+}
+
+fn leading_string_literal(input: &str) -> Option<&str> {
+    let trimmed = input.trim_start();
+    let quote = trimmed.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let body = &trimmed[1..];
+    let end = body.find(quote)?;
+    let literal = &body[..end];
+    (!literal.is_empty() && !literal.contains('\\')).then_some(literal)
+}
+
+#[derive(Debug, Clone)]
 pub struct AudioMixer {
     next_voice_id: u64,
     queue: Vec<PlayRequest>,
     active: Vec<ActiveVoice>,
+    finished: Vec<ActiveVoice>,
+    sample_rate: u32,
+    frame_remainder: f64,
+    max_voices: usize,
+    steal_policy: VoiceStealPolicy,
+    voices_stolen: u64,
+    buses: [AudioBusState; 3],
+    ducking: Option<DuckingConfig>,
+}
+
+impl Default for AudioMixer {
+    fn default() -> Self {
+        Self::with_sample_rate(MIX_SAMPLE_RATE)
+    }
 }
 
 impl AudioMixer {
+    pub fn with_sample_rate(sample_rate: u32) -> Self {
+        Self {
+            next_voice_id: 0,
+            queue: Vec::new(),
+            active: Vec::new(),
+            finished: Vec::new(),
+            sample_rate: sample_rate.max(1),
+            frame_remainder: 0.0,
+            max_voices: DEFAULT_MAX_VOICES,
+            steal_policy: VoiceStealPolicy::default(),
+            voices_stolen: 0,
+            buses: AudioBus::ALL.map(AudioBusState::new),
+            ducking: None,
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn set_voice_limit(&mut self, max_voices: usize, policy: VoiceStealPolicy) {
+        self.max_voices = max_voices.max(1);
+        self.steal_policy = policy;
+    }
+
+    pub fn max_voices(&self) -> usize {
+        self.max_voices
+    }
+
+    pub fn steal_policy(&self) -> VoiceStealPolicy {
+        self.steal_policy
+    }
+
+    pub fn voices_stolen(&self) -> u64 {
+        self.voices_stolen
+    }
+
+    pub fn set_bus_volume(&mut self, bus: AudioBus, volume: f32) {
+        self.buses[bus.index()].volume = unit_volume(volume);
+    }
+
+    pub fn set_bus_muted(&mut self, bus: AudioBus, muted: bool) {
+        self.buses[bus.index()].muted = muted;
+    }
+
+    pub fn set_ducking(&mut self, ducking: Option<DuckingConfig>) {
+        self.ducking = ducking.map(|cfg| DuckingConfig {
+            gain: unit_volume(cfg.gain),
+            fade_seconds: cfg.fade_seconds.max(0.0),
+        });
+    }
+
+    pub fn bus_state(&self, bus: AudioBus) -> &AudioBusState {
+        &self.buses[bus.index()]
+    }
+
+    pub fn bus_states(&self) -> &[AudioBusState] {
+        &self.buses
+    }
+
+    pub fn queue(&mut self, mut request: PlayRequest) {
+        request.volume = unit_volume(request.volume);
+        request.synth = request.synth.map(SynthTone::sanitized);
+        self.queue.push(request);
+    }
+
+    pub fn queue_tone(&mut self, tone: SynthTone, volume: f32) {
+        self.queue(PlayRequest {
+            clip_id: tone.clip_id(),
+            volume,
+            looped: false,
+            priority: DEFAULT_SFX_PRIORITY,
+            bus: AudioBus::Sfx,
+            fade_in_seconds: 0.0,
+            synth: Some(tone),
+        });
+    }
+
     pub fn queue_sfx(&mut self, clip_id: impl Into<String>, volume: f32) {
-        self.queue.push(PlayRequest {
+        self.queue(PlayRequest {
             clip_id: clip_id.into(),
-            volume: volume.clamp(0.0, 1.0),
+            volume,
             looped: false,
+            priority: DEFAULT_SFX_PRIORITY,
+            bus: AudioBus::Sfx,
+            fade_in_seconds: 0.0,
+            synth: None,
         });
     }
 
     pub fn queue_music(&mut self, clip_id: impl Into<String>, volume: f32, looped: bool) {
-        self.queue.push(PlayRequest {
+        self.queue(PlayRequest {
             clip_id: clip_id.into(),
-            volume: volume.clamp(0.0, 1.0),
+            volume,
             looped,
+            priority: DEFAULT_MUSIC_PRIORITY,
+            bus: AudioBus::Music,
+            fade_in_seconds: 0.0,
+            synth: None,
         });
     }
 
+    /// Starts queued requests in submission order. At the polyphony limit victims are
+    /// stolen per `steal_policy` until the request fits (several if the limit was lowered);
+    /// if every remaining voice outranks the request it is dropped.
     pub fn flush_queue(&mut self) -> &[ActiveVoice] {
-        for req in self.queue.drain(..) {
+        let queued = std::mem::take(&mut self.queue);
+        'requests: for req in queued {
+            while self.active.len() >= self.max_voices {
+                let Some(victim) = self.steal_candidate(req.priority) else {
+                    continue 'requests;
+                };
+                self.active.remove(victim);
+                self.voices_stolen += 1;
+            }
+
             self.next_voice_id += 1;
+            let fade_frames = self.seconds_to_frames(req.fade_in_seconds);
             self.active.push(ActiveVoice {
                 voice_id: self.next_voice_id,
                 clip_id: req.clip_id,
-                volume: req.volume,
-                looped: req.looped,
+                volume: if fade_frames > 0 { 0.0 } else { req.volume },
+                // Synth notes are one-shots; their envelope defines the whole voice.
+                looped: req.looped && req.synth.is_none(),
+                priority: req.priority,
+                bus: req.bus,
+                pan: 0.0,
+                pitch: 1.0,
+                fade: (fade_frames > 0).then_some(VoiceFade {
+                    target: req.volume,
+                    frames_remaining: fade_frames,
+                    stop_when_done: false,
+                }),
+                synth: req.synth.map(SynthVoice::new),
+                cursor: 0.0,
+                length_frames: req
+                    .synth
+                    .map_or(0, |tone| tone.total_frames(self.sample_rate)),
             });
         }
 
         &self.active
     }
 
+    fn steal_candidate(&self, priority: u8) -> Option<usize> {
+        let candidates = self
+            .active
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.priority <= priority);
+
+        match self.steal_policy {
+            VoiceStealPolicy::Oldest => candidates.min_by_key(|(_, v)| v.voice_id),
+            VoiceStealPolicy::Quietest => candidates.min_by(|(_, a), (_, b)| {
+                a.volume
+                    .total_cmp(&b.volume)
+                    .then(a.voice_id.cmp(&b.voice_id))
+            }),
+            VoiceStealPolicy::LowestPriority => {
+                candidates.min_by_key(|(_, v)| (v.priority, v.voice_id))
+            }
+        }
+        .map(|(index, _)| index)
+    }
+
     pub fn active_voices(&self) -> &[ActiveVoice] {
         &self.active
     }
@@ -59,24 +697,544 @@ impl AudioMixer {
         self.active.retain(|v| v.voice_id != voice_id);
     }
 
+    /// Applies the given parameters to a playing voice. Setting `volume` cancels an
+    /// in-progress `fade_voice` ramp but not a fade-out stop. Returns false for unknown ids.
+    pub fn set_voice_params(&mut self, voice_id: u64, params: VoiceParams) -> bool {
+        let Some(voice) = self.active.iter_mut().find(|v| v.voice_id == voice_id) else {
+            return false;
+        };
+        if let Some(volume) = params.volume {
+            match &mut voice.fade {
+                Some(fade) if fade.stop_when_done => {}
+                _ => {
+                    voice.volume = unit_volume(volume);
+                    voice.fade = None;
+                }
+            }
+        }
+        if let Some(pan) = params.pan.filter(|pan| pan.is_finite()) {
+            voice.pan = pan.clamp(-1.0, 1.0);
+        }
+        if let Some(pitch) = params.pitch.filter(|pitch| pitch.is_finite()) {
+            voice.pitch = pitch.clamp(MIN_VOICE_PITCH, MAX_VOICE_PITCH);
+        }
+        true
+    }
+
+    /// Ramps a voice's volume linearly to `volume` over `seconds` of mixed output,
+    /// replacing any ramp in progress. Returns false for unknown ids.
+    pub fn fade_voice(&mut self, voice_id: u64, volume: f32, seconds: f32) -> bool {
+        self.start_fade(voice_id, unit_volume(volume), seconds, false)
+    }
+
+    /// Fades a voice to silence over `seconds`, then removes it without reporting it
+    /// through `drain_finished`. Non-positive durations stop immediately.
+    pub fn stop_voice_with_fade(&mut self, voice_id: u64, seconds: f32) -> bool {
+        if self.seconds_to_frames(seconds) == 0 {
+            let known = self.active.iter().any(|v| v.voice_id == voice_id);
+            self.stop_voice(voice_id);
+            return known;
+        }
+        self.start_fade(voice_id, 0.0, seconds, true)
+    }
+
+    fn start_fade(&mut self, voice_id: u64, target: f32, seconds: f32, stop: bool) -> bool {
+        let frames = self.seconds_to_frames(seconds);
+        let Some(voice) = self.active.iter_mut().find(|v| v.voice_id == voice_id) else {
+            return false;
+        };
+        if matches!(voice.fade, Some(f) if f.stop_when_done) && !stop {
+            return true;
+        }
+        if frames == 0 {
+            voice.volume = target;
+            voice.fade = None;
+        } else {
+            voice.fade = Some(VoiceFade {
+                target,
+                frames_remaining: frames,
+                stop_when_done: stop,
+            });
+        }
+        true
+    }
+
+    fn seconds_to_frames(&self, seconds: f32) -> u64 {
+        if seconds.is_finite() && seconds > 0.0 {
+            (seconds as f64 * self.sample_rate as f64).round() as u64
+        } else {
+            0
+        }
+    }
+
     pub fn stop_all(&mut self) {
         self.active.clear();
     }
+
+    /// Returns one-shot voices retired since the last call, in retirement order.
+    /// Voices removed via `stop_voice`/`stop_all` are not reported.
+    pub fn drain_finished(&mut self) -> Vec<ActiveVoice> {
+        std::mem::take(&mut self.finished)
+    }
+
+    /// Renders the interleaved stereo output for one fixed step. Fractional frame counts
+    /// are carried over so that long runs stay sample-accurate for any `dt_fixed`.
+    pub fn mix_step(&mut self, dt_fixed: f64, clips: &AudioClipStore) -> Vec<f32> {
+        let exact = dt_fixed.max(0.0) * self.sample_rate as f64 + self.frame_remainder;
+        let frames = exact.floor();
+        self.frame_remainder = exact - frames;
+        self.render_frames(frames as usize, clips)
+    }
+
+    pub fn render_frames(&mut self, frames: usize, clips: &AudioClipStore) -> Vec<f32> {
+        let mut out = vec![0.0_f32; frames * MIX_CHANNELS];
+        let out_rate = self.sample_rate as f64;
+        let duck_curve = self.advance_ducking(frames);
+        let bus_gains = self.buses.map(|b| b.gain());
+
+        for voice in &mut self.active {
+            let clip = if voice.synth.is_some() {
+                None
+            } else {
+                let clip = clips.clip(&voice.clip_id);
+                voice.length_frames = clip.map_or(0, AudioClip::frame_count);
+                let Some(clip) = clip.filter(|_| voice.length_frames > 0) else {
+                    // Keep ramps moving so fade-out stops on silent voices still complete.
+                    for _ in 0..frames {
+                        voice.advance_fade();
+                    }
+                    continue;
+                };
+                Some(clip)
+            };
+            let len = voice.length_frames;
+            // Synth voices apply pitch to the oscillator and advance one frame at a time.
+            let step = clip.map_or(1.0, |clip| {
+                clip.sample_rate as f64 / out_rate * voice.pitch as f64
+            });
+            let bus_gain = bus_gains[voice.bus.index()];
+            let ducked = voice.bus == AudioBus::Music;
+            let pan_left = (1.0 - voice.pan).min(1.0);
+            let pan_right = (1.0 + voice.pan).min(1.0);
+
+            for (i, frame) in out.chunks_exact_mut(MIX_CHANNELS).enumerate() {
+                if voice.cursor >= len as f64 {
+                    if !voice.looped {
+                        break;
+                    }
+                    voice.cursor %= len as f64;
+                }
+
+                let [left, right] = match (clip, &mut voice.synth) {
+                    (Some(clip), _) => clip.sample_linear(voice.cursor, voice.looped),
+                    (None, Some(synth)) => {
+                        let elapsed = (voice.cursor / out_rate) as f32;
+                        let value = synth.next_sample(elapsed, out_rate, voice.pitch);
+                        [value, value]
+                    }
+                    (None, None) => break,
+                };
+
+                let gain = voice.volume * bus_gain * if ducked { duck_curve[i] } else { 1.0 };
+                frame[0] += left * gain * pan_left;
+                frame[1] += right * gain * pan_right;
+                voice.cursor += step;
+                voice.advance_fade();
+                if voice.fade_stopped() {
+                    break;
+                }
+            }
+        }
+        self.active.retain(|v| !v.fade_stopped());
+
+        // One-shots and synth notes end once the cursor passes their length; unresolved
+        // clips end immediately, looped or not, so that missing assets cannot pin voices forever.
+        // Voices already fading out to a stop finish their ramp and leave without an event.
+        let (done, playing): (Vec<_>, Vec<_>) = self.active.drain(..).partition(|v| {
+            let stopping = matches!(v.fade, Some(fade) if fade.stop_when_done);
+            let unresolved = v.synth.is_none() && v.length_frames == 0 && !stopping;
+            unresolved || (!v.looped && v.cursor >= v.length_frames as f64)
+        });
+        self.active = playing;
+        self.finished.extend(done);
+
+        for sample in &mut out {
+            *sample = sample.clamp(-1.0, 1.0);
+        }
+        out
+    }
+
+    /// Produces the per-frame music ducking gain for the next `frames` frames and stores
+    /// the final value on the music bus so the ramp continues across steps.
+    fn advance_ducking(&mut self, frames: usize) -> Vec<f32> {
+        let music = AudioBus::Music.index();
+        let mut gain = self.buses[music].duck_gain;
+        let (target, rate) = match self.ducking {
+            Some(cfg) => {
+                let triggered = self.active.iter().any(|v| v.bus != AudioBus::Music);
+                let target = if triggered { cfg.gain } else { 1.0 };
+                let rate = if cfg.fade_seconds > 0.0 {
+                    1.0 / (cfg.fade_seconds * self.sample_rate as f32)
+                } else {
+                    1.0
+                };
+                (target, rate)
+            }
+            None => (1.0, 1.0),
+        };
+
+        let curve = (0..frames)
+            .map(|_| {
+                gain = if gain < target {
+                    (gain + rate).min(target)
+                } else {
+                    (gain - rate).max(target)
+                };
+                gain
+            })
+            .collect();
+        self.buses[music].duck_gain = gain;
+        curve
+    }
+}
+
+/// Headless output sink that collects mixed frames and writes them as 16-bit PCM WAV.
+#[derive(Debug, Clone)]
+pub struct WavFileSink {
+    sample_rate: u32,
+    samples: Vec<f32>,
+}
+
+impl WavFileSink {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            samples: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, interleaved: &[f32]) {
+        self.samples.extend_from_slice(interleaved);
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.samples.len() / MIX_CHANNELS
+    }
+
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    pub fn write_wav(&self, path: &Path) -> Result<(), AudioIoError> {
+        let mut file = std::fs::File::create(path)
+            .map_err(|source| AudioIoError::Write(path.to_path_buf(), source))?;
+        file.write_all(&encode_wav_pcm16(&self.samples, self.sample_rate))
+            .map_err(|source| AudioIoError::Write(path.to_path_buf(), source))?;
+        Ok(())
+    }
+}
+
+pub fn encode_wav_pcm16(interleaved: &[f32], sample_rate: u32) -> Vec<u8> {
+    let channels = MIX_CHANNELS as u16;
+    let block_align = channels * 2;
+    let data_len = (interleaved.len() * 2) as u32;
+
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVE");
+    bytes.extend_from_slice(b"fmt ");
+    bytes.extend_from_slice(&16_u32.to_le_bytes());
+    bytes.extend_from_slice(&1_u16.to_le_bytes());
+    bytes.extend_from_slice(&channels.to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    bytes.extend_from_slice(&block_align.to_le_bytes());
+    bytes.extend_from_slice(&16_u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for sample in interleaved {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
+
+pub fn parse_wav_pcm(bytes: &[u8]) -> Result<AudioClip, String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("missing RIFF/WAVE header".to_owned());
+    }
+
+    let mut format: Option<(u16, u16, u32, u16)> = None;
+    let mut data: Option<&[u8]> = None;
+    let mut i = 12usize;
+    while i + 8 <= bytes.len() {
+        let id = &bytes[i..i + 4];
+        let len =
+            u32::from_le_bytes([bytes[i + 4], bytes[i + 5], bytes[i + 6], bytes[i + 7]]) as usize;
+        let body_start = i + 8;
+        let body_end = body_start
+            .checked_add(len)
+            .filter(|end| *end <= bytes.len())
+            .ok_or_else(|| format!("chunk `{}` is truncated", String::from_utf8_lossy(id)))?;
+        let body = &bytes[body_start..body_end];
+
+        match id {
+            b"fmt " => {
+                if body.len() < 16 {
+                    return Err("fmt chunk is too short".to_owned());
+                }
+                format = Some((
+                    u16::from_le_bytes([body[0], body[1]]),
+                    u16::from_le_bytes([body[2], body[3]]),
+                    u32::from_le_bytes([body[4], body[5], body[6], body[7]]),
+                    u16::from_le_bytes([body[14], body[15]]),
+                ));
+            }
+            b"data" => data = Some(body),
+            _ => {}
+        }
+
+        // Chunks are word aligned; odd-sized bodies carry one pad byte.
+        i = body_end + (len & 1);
+    }
+
+    let (audio_format, channels, sample_rate, bits) = format.ok_or("missing fmt chunk")?;
+    let data = data.ok_or("missing data chunk")?;
+    if audio_format != 1 {
+        return Err(format!(
+            "unsupported wav encoding {audio_format} (expected PCM)"
+        ));
+    }
+    if channels != 1 && channels != 2 {
+        return Err(format!(
+            "unsupported channel count {channels} (expected 1 or 2)"
+        ));
+    }
+    if sample_rate == 0 {
+        return Err("sample rate must be > 0".to_owned());
+    }
+
+    let decoded: Vec<f32> = match bits {
+        8 => data.iter().map(|b| (*b as f32 - 128.0) / 128.0).collect(),
+        16 => data
+            .chunks_exact(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]) as f32 / 32768.0)
+            .collect(),
+        _ => return Err(format!("unsupported bit depth {bits} (expected 8 or 16)")),
+    };
+
+    let samples = if channels == 1 {
+        decoded.iter().flat_map(|s| [*s, *s]).collect()
+    } else {
+        let frames = decoded.len() / MIX_CHANNELS;
+        decoded[..frames * MIX_CHANNELS].to_vec()
+    };
+
+    Ok(AudioClip {
+        sample_rate,
+        samples,
+    })
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AudioIoError {
+    #[error("failed writing audio mix to {0}: {1}")]
+    Write(PathBuf, std::io::Error),
 }
 
 #[cfg(test)]
 mod tests {
-    use super::AudioMixer;
+    use super::{
+        encode_wav_pcm16, literal_clip_references, parse_wav_pcm, AdsrEnvelope, AudioBus,
+        AudioClip, AudioClipStore, AudioMixer, DuckingConfig, PlayRequest, SynthTone,
+        SynthWaveform, VoiceParams, VoiceStealPolicy, WavFileSink,
+    };
+
+    fn request(clip_id: &str, volume: f32, priority: u8) -> PlayRequest {
+        PlayRequest {
+            clip_id: clip_id.to_owned(),
+            volume,
+            looped: true,
+            priority,
+            bus: AudioBus::Sfx,
+            fade_in_seconds: 0.0,
+            synth: None,
+        }
+    }
+
+    fn constant_clip(value: f32, frames: usize, sample_rate: u32) -> AudioClip {
+        AudioClip {
+            sample_rate,
+            samples: vec![value; frames * 2],
+        }
+    }
+
+    fn wav_bytes(bits: u16, channels: u16, sample_rate: u32, data: &[u8]) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16_u32.to_le_bytes());
+        bytes.extend_from_slice(&1_u16.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&bits.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
 
     #[test]
-    fn queues_and_activates_unlimited_voices() {
+    fn queues_many_voices_and_retires_unresolved_one_shots() {
         let mut mixer = AudioMixer::default();
+        mixer.set_voice_limit(128, VoiceStealPolicy::Oldest);
 
         for i in 0..128 {
             mixer.queue_sfx(format!("sfx_{i}"), 0.8);
         }
         let active = mixer.flush_queue();
         assert_eq!(active.len(), 128);
+
+        mixer.mix_step(1.0 / 60.0, &AudioClipStore::default());
+        assert!(mixer.active_voices().is_empty());
+        let finished = mixer.drain_finished();
+        assert_eq!(finished.len(), 128);
+        assert_eq!(finished[0].voice_id, 1);
+        assert!(mixer.drain_finished().is_empty());
+    }
+
+    #[test]
+    fn retires_looped_voices_whose_clip_is_missing() {
+        let mut clips = AudioClipStore::default();
+        clips.insert("bgm", constant_clip(0.5, 480, 48_000));
+
+        let mut mixer = AudioMixer::default();
+        mixer.set_voice_limit(1, VoiceStealPolicy::Oldest);
+        mixer.queue_music("missing_bgm", 1.0, true);
+        mixer.flush_queue();
+        mixer.mix_step(1.0 / 60.0, &clips);
+        assert!(mixer.active_voices().is_empty());
+        let finished = mixer.drain_finished();
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].clip_id, "missing_bgm");
+
+        // The freed slot goes to the next request without stealing anything.
+        mixer.queue_music("bgm", 1.0, true);
+        mixer.flush_queue();
+        mixer.mix_step(1.0 / 60.0, &clips);
+        assert_eq!(mixer.active_voices().len(), 1);
+        assert_eq!(mixer.voices_stolen(), 0);
+    }
+
+    #[test]
+    fn caps_polyphony_by_stealing_oldest_voice() {
+        let mut mixer = AudioMixer::default();
+        mixer.set_voice_limit(2, VoiceStealPolicy::Oldest);
+        for clip in ["a", "b", "c"] {
+            mixer.queue(request(clip, 1.0, 10));
+        }
+
+        let active = mixer.flush_queue();
+        let ids = active
+            .iter()
+            .map(|v| v.clip_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["b", "c"]);
+        assert_eq!(mixer.voices_stolen(), 1);
+    }
+
+    #[test]
+    fn lowering_the_limit_on_a_full_mixer_steals_down_to_the_cap() {
+        let mut mixer = AudioMixer::default();
+        mixer.set_voice_limit(4, VoiceStealPolicy::Oldest);
+        for clip in ["a", "b", "c", "d"] {
+            mixer.queue(request(clip, 1.0, 10));
+        }
+        mixer.flush_queue();
+
+        mixer.set_voice_limit(2, VoiceStealPolicy::Oldest);
+        mixer.queue(request("e", 1.0, 10));
+        let ids = mixer
+            .flush_queue()
+            .iter()
+            .map(|v| v.clip_id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["d", "e"]);
+        assert_eq!(mixer.voices_stolen(), 3);
+    }
+
+    #[test]
+    fn steals_quietest_and_lowest_priority_deterministically() {
+        let mut quiet = AudioMixer::default();
+        quiet.set_voice_limit(3, VoiceStealPolicy::Quietest);
+        quiet.queue(request("loud", 0.9, 10));
+        quiet.queue(request("soft_a", 0.2, 10));
+        quiet.queue(request("soft_b", 0.2, 10));
+        quiet.queue(request("new", 0.5, 10));
+        let ids = quiet
+            .flush_queue()
+            .iter()
+            .map(|v| v.clip_id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["loud", "soft_b", "new"]);
+
+        let mut ranked = AudioMixer::default();
+        ranked.set_voice_limit(2, VoiceStealPolicy::LowestPriority);
+        ranked.queue(request("high", 0.5, 200));
+        ranked.queue(request("low", 0.5, 5));
+        ranked.queue(request("mid", 0.5, 50));
+        let ids = ranked
+            .flush_queue()
+            .iter()
+            .map(|v| v.clip_id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["high", "mid"]);
+    }
+
+    #[test]
+    fn drops_request_when_all_voices_outrank_it() {
+        let mut mixer = AudioMixer::default();
+        mixer.set_voice_limit(1, VoiceStealPolicy::Oldest);
+        mixer.queue_music("bgm", 0.5, true);
+        mixer.queue_sfx("spam", 1.0);
+
+        let active = mixer.flush_queue();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].clip_id, "bgm");
+        assert_eq!(mixer.voices_stolen(), 0);
+    }
+
+    #[test]
+    fn retires_one_shot_when_clip_ends_and_keeps_loops() {
+        let mut clips = AudioClipStore::default();
+        clips.insert("blip", constant_clip(0.5, 1_200, 48_000));
+
+        let mut mixer = AudioMixer::default();
+        mixer.queue_sfx("blip", 1.0);
+        mixer.queue_music("blip", 1.0, true);
+        mixer.flush_queue();
+
+        mixer.mix_step(1.0 / 60.0, &clips);
+        assert_eq!(mixer.active_voices().len(), 2);
+        assert_eq!(mixer.active_voices()[0].length_frames, 1_200);
+        assert!(mixer.drain_finished().is_empty());
+
+        mixer.mix_step(1.0 / 60.0, &clips);
+        let finished = mixer.drain_finished();
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].voice_id, 1);
+        assert_eq!(finished[0].cursor, 1_200.0);
+        assert_eq!(mixer.active_voices().len(), 1);
+        assert!(mixer.active_voices()[0].looped);
+
+        mixer.stop_all();
+        mixer.mix_step(1.0 / 60.0, &clips);
+        assert!(mixer.drain_finished().is_empty());
     }
 
     #[test]
@@ -89,4 +1247,381 @@ mod tests {
         mixer.stop_voice(first.voice_id);
         assert!(mixer.active_voices().is_empty());
     }
+
+    #[test]
+    fn decodes_8_and_16_bit_pcm_wav() {
+        let mono8 = parse_wav_pcm(&wav_bytes(8, 1, 22_050, &[128, 255, 0]))
+            .expect("8-bit mono wav should decode");
+        assert_eq!(mono8.sample_rate, 22_050);
+        assert_eq!(mono8.frame_count(), 3);
+        assert_eq!(&mono8.samples[0..2], &[0.0, 0.0]);
+        assert_eq!(mono8.samples[4], -1.0);
+
+        let stereo16 = parse_wav_pcm(&wav_bytes(16, 2, 48_000, &[0x00, 0x40, 0x00, 0xC0]))
+            .expect("16-bit stereo wav should decode");
+        assert_eq!(stereo16.frame_count(), 1);
+        assert_eq!(stereo16.samples, vec![0.5, -0.5]);
+
+        let err = parse_wav_pcm(&wav_bytes(24, 1, 48_000, &[0, 0, 0]))
+            .expect_err("24-bit wav should be rejected");
+        assert!(err.contains("bit depth"));
+    }
+
+    #[test]
+    fn mixes_voices_with_volume_and_clamps() {
+        let mut clips = AudioClipStore::default();
+        clips.insert("a", constant_clip(0.5, 4_800, 48_000));
+        clips.insert("b", constant_clip(0.8, 4_800, 48_000));
+
+        let mut mixer = AudioMixer::default();
+        mixer.queue_sfx("a", 0.5);
+        mixer.flush_queue();
+        let quiet = mixer.mix_step(1.0 / 60.0, &clips);
+        assert_eq!(quiet.len(), 800 * 2);
+        assert!(quiet.iter().all(|s| (*s - 0.25).abs() < 1e-6));
+
+        mixer.queue_sfx("b", 1.0);
+        mixer.queue_sfx("missing", 1.0);
+        mixer.flush_queue();
+        let loud = mixer.mix_step(1.0 / 60.0, &clips);
+        assert!(loud.iter().all(|s| *s == 1.0));
+    }
+
+    #[test]
+    fn non_finite_volumes_play_silent_instead_of_poisoning_the_mix() {
+        let mut clips = AudioClipStore::default();
+        clips.insert("a", constant_clip(0.5, 4_800, 48_000));
+        clips.insert("b", constant_clip(0.8, 4_800, 48_000));
+
+        let mut mixer = AudioMixer::default();
+        mixer.queue_sfx("a", f32::NAN);
+        mixer.queue_sfx("b", 0.5);
+        mixer.flush_queue();
+        let nan_voice = mixer.active_voices()[0].voice_id;
+        assert_eq!(mixer.active_voices()[0].volume, 0.0);
+        let out = mixer.render_frames(4, &clips);
+        assert!(out.iter().all(|s| (*s - 0.4).abs() < 1e-6), "{out:?}");
+
+        let params = VoiceParams {
+            volume: Some(f32::INFINITY),
+            pan: Some(f32::NAN),
+            pitch: Some(f32::NAN),
+        };
+        assert!(mixer.set_voice_params(nan_voice, params));
+        assert!(mixer.fade_voice(nan_voice, f32::NAN, 0.0));
+        mixer.set_bus_volume(AudioBus::Music, f32::NAN);
+        let out = mixer.render_frames(4, &clips);
+        assert!(out.iter().all(|s| (*s - 0.4).abs() < 1e-6), "{out:?}");
+
+        mixer.set_bus_volume(AudioBus::Sfx, f32::NAN);
+        let out = mixer.render_frames(4, &clips);
+        assert!(out.iter().all(|s| *s == 0.0), "{out:?}");
+    }
+
+    #[test]
+    fn applies_bus_volume_and_mute() {
+        let mut clips = AudioClipStore::default();
+        clips.insert("tone", constant_clip(0.5, 4_800, 48_000));
+
+        let mut mixer = AudioMixer::default();
+        mixer.set_bus_volume(AudioBus::Music, 0.5);
+        mixer.set_bus_muted(AudioBus::Ui, true);
+        mixer.queue_music("tone", 1.0, true);
+        mixer.queue(PlayRequest {
+            bus: AudioBus::Ui,
+            ..request("tone", 1.0, 10)
+        });
+        mixer.flush_queue();
+
+        let out = mixer.render_frames(4, &clips);
+        assert!(out.iter().all(|s| (*s - 0.25).abs() < 1e-6));
+        assert!(mixer.bus_state(AudioBus::Ui).muted);
+        assert_eq!(mixer.bus_states().len(), 3);
+    }
+
+    #[test]
+    fn ducks_music_while_other_bus_is_active_and_recovers() {
+        let mut clips = AudioClipStore::default();
+        clips.insert("bgm", constant_clip(1.0, 48_000, 48_000));
+        clips.insert("blip", constant_clip(0.0, 480, 48_000));
+
+        let mut mixer = AudioMixer::default();
+        mixer.set_ducking(Some(DuckingConfig {
+            gain: 0.25,
+            fade_seconds: 0.01,
+        }));
+        mixer.queue_music("bgm", 1.0, true);
+        mixer.queue_sfx("blip", 1.0);
+        mixer.flush_queue();
+
+        let ducking = mixer.render_frames(960, &clips);
+        assert!(ducking[0] < 1.0 && ducking[0] > 0.99);
+        assert!((ducking[2 * 959] - 0.25).abs() < 1e-6);
+        assert!((mixer.bus_state(AudioBus::Music).duck_gain - 0.25).abs() < 1e-6);
+
+        // The blip has ended, so the music bus ramps back to unity over 480 frames.
+        let recovering = mixer.render_frames(960, &clips);
+        assert!(recovering[0] < 0.26);
+        assert!((recovering[2 * 959] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn looped_voice_wraps_and_one_shot_goes_silent() {
+        let mut clips = AudioClipStore::default();
+        clips.insert("short", constant_clip(0.5, 100, 48_000));
+
+        let mut mixer = AudioMixer::default();
+        mixer.queue_music("short", 1.0, true);
+        mixer.queue_sfx("short", 1.0);
+        mixer.flush_queue();
+
+        let out = mixer.render_frames(250, &clips);
+        assert_eq!(out[2 * 50], 1.0);
+        assert_eq!(out[2 * 150], 0.5);
+        assert_eq!(out[2 * 249], 0.5);
+        assert_eq!(mixer.active_voices()[0].cursor, 50.0);
+    }
+
+    #[test]
+    fn mix_step_carries_fractional_frames() {
+        let clips = AudioClipStore::default();
+        let mut mixer = AudioMixer::with_sample_rate(44_100);
+
+        let total: usize = (0..60)
+            .map(|_| mixer.mix_step(1.0 / 60.0, &clips).len() / 2)
+            .sum();
+        assert_eq!(total, 44_100);
+    }
+
+    #[test]
+    fn wav_sink_round_trips_through_decoder() {
+        let mut sink = WavFileSink::new(48_000);
+        sink.push(&[0.5, -0.5, 0.25, 0.0]);
+        assert_eq!(sink.frame_count(), 2);
+
+        let decoded = parse_wav_pcm(&encode_wav_pcm16(sink.samples(), 48_000))
+            .expect("encoded mix should decode");
+        assert_eq!(decoded.frame_count(), 2);
+        assert!((decoded.samples[0] - 0.5).abs() < 1e-3);
+        assert!((decoded.samples[1] + 0.5).abs() < 1e-3);
+
+        let path = std::env::temp_dir().join("vcon-audio-sink-test.wav");
+        let _ = std::fs::remove_file(&path);
+        sink.write_wav(&path).expect("wav should be written");
+        let bytes = std::fs::read(&path).expect("wav should exist");
+        assert!(bytes.starts_with(b"RIFF"));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn fades_voice_volume_linearly_per_frame() {
+        let mut clips = AudioClipStore::default();
+        clips.insert("tone", constant_clip(1.0, 48_000, 48_000));
+
+        let mut mixer = AudioMixer::default();
+        mixer.queue(request("tone", 1.0, 10));
+        let voice_id = mixer.flush_queue()[0].voice_id;
+        assert!(mixer.fade_voice(voice_id, 0.0, 0.01));
+        assert!(!mixer.fade_voice(999, 0.0, 0.01));
+
+        let out = mixer.render_frames(960, &clips);
+        assert_eq!(out[0], 1.0);
+        assert!((out[2 * 240] - 0.5).abs() < 1e-3);
+        assert_eq!(out[2 * 480], 0.0);
+        assert_eq!(mixer.active_voices()[0].volume, 0.0);
+        assert!(mixer.active_voices()[0].fade.is_none());
+    }
+
+    #[test]
+    fn fade_out_stop_removes_voice_without_finished_event() {
+        let mut clips = AudioClipStore::default();
+        clips.insert("tone", constant_clip(1.0, 48_000, 48_000));
+
+        let mut mixer = AudioMixer::default();
+        mixer.queue(request("tone", 1.0, 10));
+        mixer.queue(request("missing", 1.0, 10));
+        let ids: Vec<u64> = mixer.flush_queue().iter().map(|v| v.voice_id).collect();
+        for id in &ids {
+            assert!(mixer.stop_voice_with_fade(*id, 0.005));
+        }
+        // A later volume change must not cancel the fade-out.
+        mixer.set_voice_params(
+            ids[0],
+            VoiceParams {
+                volume: Some(1.0),
+                ..VoiceParams::default()
+            },
+        );
+
+        mixer.render_frames(100, &clips);
+        assert_eq!(mixer.active_voices().len(), 2);
+        let out = mixer.render_frames(200, &clips);
+        assert!(mixer.active_voices().is_empty());
+        assert!(mixer.drain_finished().is_empty());
+        assert_eq!(out[2 * 199], 0.0);
+    }
+
+    #[test]
+    fn applies_pan_and_pitch() {
+        let mut clips = AudioClipStore::default();
+        clips.insert(
+            "ramp",
+            AudioClip {
+                sample_rate: 48_000,
+                samples: (0..100).flat_map(|i| [i as f32 / 100.0; 2]).collect(),
+            },
+        );
+
+        let mut mixer = AudioMixer::default();
+        mixer.queue(request("ramp", 1.0, 10));
+        let voice_id = mixer.flush_queue()[0].voice_id;
+        mixer.set_voice_params(
+            voice_id,
+            VoiceParams {
+                volume: None,
+                pan: Some(-0.5),
+                pitch: Some(2.0),
+            },
+        );
+
+        let out = mixer.render_frames(10, &clips);
+        assert!((out[2 * 5] - 0.10).abs() < 1e-6);
+        assert!((out[2 * 5 + 1] - 0.05).abs() < 1e-6);
+        assert_eq!(mixer.active_voices()[0].cursor, 20.0);
+
+        mixer.set_voice_params(
+            voice_id,
+            VoiceParams {
+                pitch: Some(100.0),
+                ..VoiceParams::default()
+            },
+        );
+        assert_eq!(mixer.active_voices()[0].pitch, super::MAX_VOICE_PITCH);
+    }
+
+    #[test]
+    fn fade_in_request_crossfades_with_fade_out_stop() {
+        let mut clips = AudioClipStore::default();
+        clips.insert("title", constant_clip(0.5, 48_000, 48_000));
+        clips.insert("game", constant_clip(0.5, 48_000, 48_000));
+
+        let mut mixer = AudioMixer::default();
+        mixer.queue_music("title", 1.0, true);
+        let title = mixer.flush_queue()[0].voice_id;
+        mixer.stop_voice_with_fade(title, 0.01);
+        mixer.queue(PlayRequest {
+            bus: AudioBus::Music,
+            fade_in_seconds: 0.01,
+            ..request("game", 1.0, 192)
+        });
+        mixer.flush_queue();
+
+        let out = mixer.render_frames(480, &clips);
+        assert!(out.iter().all(|s| (*s - 0.5).abs() < 1e-3));
+        assert_eq!(mixer.active_voices().len(), 1);
+        assert_eq!(mixer.active_voices()[0].clip_id, "game");
+        assert_eq!(mixer.active_voices()[0].volume, 1.0);
+    }
+
+    #[test]
+    fn renders_synth_waveforms_with_adsr_envelope() {
+        let clips = AudioClipStore::default();
+        let flat = AdsrEnvelope {
+            attack: 0.0,
+            decay: 0.0,
+            sustain: 1.0,
+            release: 0.0,
+        };
+        let mut mixer = AudioMixer::default();
+        mixer.queue_tone(
+            SynthTone {
+                wave: SynthWaveform::Square,
+                frequency: 1_000.0,
+                duration_seconds: 0.01,
+                envelope: flat,
+            },
+            0.5,
+        );
+        let voice = mixer.flush_queue()[0].clone();
+        assert_eq!(voice.clip_id, "synth:square");
+        assert_eq!(voice.length_frames, 480);
+        assert!(!voice.looped);
+
+        // 48 frames per period: 24 high, 24 low.
+        let out = mixer.render_frames(480, &clips);
+        assert_eq!(out[0], 0.5);
+        assert_eq!(out[2 * 20 + 1], 0.5);
+        assert_eq!(out[2 * 30], -0.5);
+        assert_eq!(out[2 * 50], 0.5);
+        assert!(mixer.active_voices().is_empty());
+        assert_eq!(mixer.drain_finished()[0].clip_id, "synth:square");
+
+        let envelope = AdsrEnvelope {
+            attack: 0.1,
+            decay: 0.1,
+            sustain: 0.5,
+            release: 0.2,
+        };
+        assert!((envelope.level(0.05, 1.0) - 0.5).abs() < 1e-6);
+        assert!((envelope.level(0.15, 1.0) - 0.75).abs() < 1e-6);
+        assert!((envelope.level(0.5, 1.0) - 0.5).abs() < 1e-6);
+        assert!((envelope.level(1.1, 1.0) - 0.25).abs() < 1e-6);
+        assert!(envelope.level(1.3, 1.0).abs() < 1e-6);
+        // Releasing mid-attack starts from the level reached so far.
+        assert!((envelope.level(0.15, 0.05) - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn synth_noise_and_pitch_are_deterministic() {
+        let clips = AudioClipStore::default();
+        let tone = |wave| SynthTone {
+            wave,
+            frequency: 4_000.0,
+            duration_seconds: 0.05,
+            envelope: AdsrEnvelope::default(),
+        };
+        let render = |pitch: f32| {
+            let mut mixer = AudioMixer::default();
+            mixer.queue_tone(tone(SynthWaveform::Noise), 1.0);
+            mixer.queue_tone(tone(SynthWaveform::Triangle), 1.0);
+            let ids: Vec<u64> = mixer.flush_queue().iter().map(|v| v.voice_id).collect();
+            for id in ids {
+                mixer.set_voice_params(
+                    id,
+                    VoiceParams {
+                        pitch: Some(pitch),
+                        ..VoiceParams::default()
+                    },
+                );
+            }
+            mixer.render_frames(2_400, &clips)
+        };
+
+        let first = render(1.0);
+        assert_eq!(first, render(1.0));
+        assert_ne!(first, render(2.0));
+        assert!(first.iter().any(|s| *s > 0.1) && first.iter().any(|s| *s < -0.1));
+    }
+
+    #[test]
+    fn finds_literal_clip_references() {
+        let source = r#"
+import vcon
+
+def on_update(self, dt):
+    vcon.audio.play_sfx("jump", volume=0.5)
+    vcon.audio.play_music( 'title' )
+    vcon.audio.crossfade_music("stage1", 1.0); vcon.audio.play_sfx("jump")
+    vcon.audio.play_sfx(self.clip_id)
+    my_play_sfx("not_a_clip")
+    # vcon.audio.play_sfx("commented")
+    vcon.audio.tone("square", 440, 0.1)
+    vcon.audio.play_sfx("hit#2")  # vcon.audio.play_sfx("trailing")
+    vcon.audio.play_sfx('it\'s') ; vcon.audio.play_sfx("after#escape")
+"#;
+        assert_eq!(
+            literal_clip_references(source),
+            vec!["after#escape", "hit#2", "jump", "stage1", "title"]
+        );
+    }
 }
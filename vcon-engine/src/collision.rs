@@ -0,0 +1,703 @@
+use crate::physics::PhysicsVec2;
+
+pub const MAX_POLYGON_VERTICES: usize = 8;
+
+/// Overlap below this between core features counts as touching cores.
+const CORE_OVERLAP_EPSILON: f64 = 1e-9;
+/// Keeps the reference face on the first shape unless the second is clearly better, so nearly
+/// symmetric contacts do not flip between frames.
+const REFERENCE_FACE_BIAS: f64 = 1e-3;
+
+/// Collision geometry in body space, centred on the body origin and turned by its rotation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CollisionShape {
+    Circle {
+        radius: f64,
+    },
+    Box {
+        half_width: f64,
+        half_height: f64,
+    },
+    /// Convex polygon of 3..=`MAX_POLYGON_VERTICES` points in either winding.
+    Polygon {
+        points: Vec<PhysicsVec2>,
+    },
+    /// Upright capsule: cap centres sit `half_length` above and below the origin.
+    Capsule {
+        half_length: f64,
+        radius: f64,
+    },
+}
+
+impl CollisionShape {
+    pub fn validate(&self) -> Result<(), String> {
+        let positive = |value: f64| value.is_finite() && value > 0.0;
+        match self {
+            CollisionShape::Circle { radius } => {
+                if !positive(*radius) {
+                    return Err("circle radius must be greater than 0".to_owned());
+                }
+            }
+            CollisionShape::Box {
+                half_width,
+                half_height,
+            } => {
+                if !positive(*half_width) || !positive(*half_height) {
+                    return Err("box width and height must be greater than 0".to_owned());
+                }
+            }
+            CollisionShape::Polygon { points } => {
+                if !(3..=MAX_POLYGON_VERTICES).contains(&points.len()) {
+                    return Err(format!(
+                        "polygon must have 3..={MAX_POLYGON_VERTICES} points"
+                    ));
+                }
+                if points.iter().any(|p| !(p.x.is_finite() && p.y.is_finite())) {
+                    return Err("polygon points must be finite".to_owned());
+                }
+                let edges = (0..points.len())
+                    .map(|i| sub(points[(i + 1) % points.len()], points[i]))
+                    .collect::<Vec<_>>();
+                let turns = (0..edges.len())
+                    .map(|i| {
+                        let (a, b) = (edges[i], edges[(i + 1) % edges.len()]);
+                        (cross(a, b), dot(a, b))
+                    })
+                    .collect::<Vec<_>>();
+                if !(turns.iter().all(|t| t.0 > 0.0) || turns.iter().all(|t| t.0 < 0.0)) {
+                    return Err("polygon must be strictly convex".to_owned());
+                }
+                // Consistent turns alone still admit star polygons that wind more than once.
+                let winding: f64 = turns.iter().map(|(sin, cos)| sin.atan2(*cos)).sum();
+                if winding.abs() > std::f64::consts::TAU + 1e-6 {
+                    return Err("polygon must be strictly convex".to_owned());
+                }
+            }
+            CollisionShape::Capsule {
+                half_length,
+                radius,
+            } => {
+                if !(positive(*radius) && half_length.is_finite() && *half_length >= 0.0) {
+                    return Err(
+                        "capsule radius must be greater than 0 and its length at least 0"
+                            .to_owned(),
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn area(&self) -> f64 {
+        match self {
+            CollisionShape::Circle { radius } => std::f64::consts::PI * radius * radius,
+            CollisionShape::Box {
+                half_width,
+                half_height,
+            } => 4.0 * half_width * half_height,
+            CollisionShape::Polygon { points } => {
+                polygon_fans(points).map(|(area, _)| area).sum::<f64>()
+            }
+            CollisionShape::Capsule {
+                half_length,
+                radius,
+            } => 4.0 * half_length * radius + std::f64::consts::PI * radius * radius,
+        }
+    }
+
+    /// Moment of inertia about the body origin for a body of unit mass. Bodies turn about
+    /// their origin, so off-centre polygons behave as if pinned there.
+    pub fn unit_inertia(&self) -> f64 {
+        match self {
+            CollisionShape::Circle { radius } => radius * radius * 0.5,
+            CollisionShape::Box {
+                half_width,
+                half_height,
+            } => (half_width * half_width + half_height * half_height) / 3.0,
+            CollisionShape::Polygon { points } => {
+                let (area, inertia) = polygon_fans(points)
+                    .fold((0.0, 0.0), |(area, inertia), (a, i)| {
+                        (area + a, inertia + i)
+                    });
+                inertia / area
+            }
+            CollisionShape::Capsule {
+                half_length,
+                radius,
+            } => {
+                let (h, r) = (*half_length, *radius);
+                let rect_area = 4.0 * h * r;
+                let cap_area = std::f64::consts::PI * r * r;
+                // Each half-disc's centroid sits this far beyond its cap centre.
+                let cap_offset = 4.0 * r / (3.0 * std::f64::consts::PI);
+                let rect = rect_area * (r * r + h * h) / 3.0;
+                let caps = cap_area * (r * r * 0.5 + h * h + 2.0 * h * cap_offset);
+                (rect + caps) / (rect_area + cap_area)
+            }
+        }
+    }
+
+    /// Distance from the body origin to the farthest point of the shape, whatever the rotation.
+    pub fn bounding_radius(&self) -> f64 {
+        match self {
+            CollisionShape::Circle { radius } => *radius,
+            CollisionShape::Box {
+                half_width,
+                half_height,
+            } => half_width.hypot(*half_height),
+            CollisionShape::Polygon { points } => {
+                points.iter().map(|p| p.x.hypot(p.y)).fold(0.0, f64::max)
+            }
+            CollisionShape::Capsule {
+                half_length,
+                radius,
+            } => half_length + radius,
+        }
+    }
+
+    /// World-space bounds `(min, max)` of the shape placed at `position` and turned by
+    /// `rotation`.
+    pub fn aabb(&self, position: PhysicsVec2, rotation: f64) -> (PhysicsVec2, PhysicsVec2) {
+        let hull = Hull::new(self, position, rotation);
+        let (mut min, mut max) = (hull.points[0], hull.points[0]);
+        for p in hull.vertices() {
+            min = PhysicsVec2::new(min.x.min(p.x), min.y.min(p.y));
+            max = PhysicsVec2::new(max.x.max(p.x), max.y.max(p.y));
+        }
+        let inflate = PhysicsVec2::new(hull.radius, hull.radius);
+        (sub(min, inflate), add(max, inflate))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactPoint {
+    /// World point halfway between the two surfaces.
+    pub point: PhysicsVec2,
+    /// Overlap along the manifold normal; 0 when the shapes just touch.
+    pub depth: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContactManifold {
+    /// Unit normal pointing from the first shape towards the second.
+    pub normal: PhysicsVec2,
+    /// One point, or two when edges rest against each other.
+    pub points: Vec<ContactPoint>,
+}
+
+impl ContactManifold {
+    pub fn depth(&self) -> f64 {
+        self.points.iter().map(|p| p.depth).fold(0.0, f64::max)
+    }
+}
+
+/// Contact between two placed shapes, or `None` when they are apart.
+pub fn collide(
+    a: &CollisionShape,
+    a_position: PhysicsVec2,
+    a_rotation: f64,
+    b: &CollisionShape,
+    b_position: PhysicsVec2,
+    b_rotation: f64,
+) -> Option<ContactManifold> {
+    let offset = sub(b_position, a_position);
+    let reach = a.bounding_radius() + b.bounding_radius();
+    if dot(offset, offset) > reach * reach {
+        return None;
+    }
+
+    let a = Hull::new(a, a_position, a_rotation);
+    let b = Hull::new(b, b_position, b_rotation);
+    let radius = a.radius + b.radius;
+    match core_separation(&a, &b) {
+        Some((on_a, on_b, distance)) => {
+            if distance > radius {
+                return None;
+            }
+            // Only the radii overlap: a single contact between the closest core points.
+            let normal = scale(sub(on_b, on_a), 1.0 / distance);
+            let surface_a = add(on_a, scale(normal, a.radius));
+            let surface_b = sub(on_b, scale(normal, b.radius));
+            Some(ContactManifold {
+                normal,
+                points: vec![ContactPoint {
+                    point: scale(add(surface_a, surface_b), 0.5),
+                    depth: radius - distance,
+                }],
+            })
+        }
+        None => Some(clip_overlapping_cores(&a, &b)),
+    }
+}
+
+/// A shape in world space as a convex core (one point, a segment or a polygon wound so edge
+/// normals face outward) inflated by `radius`.
+#[derive(Debug, Clone, Copy)]
+struct Hull {
+    points: [PhysicsVec2; MAX_POLYGON_VERTICES],
+    count: usize,
+    radius: f64,
+}
+
+impl Hull {
+    fn new(shape: &CollisionShape, position: PhysicsVec2, rotation: f64) -> Self {
+        let (sin, cos) = rotation.sin_cos();
+        let place = |p: PhysicsVec2| {
+            PhysicsVec2::new(
+                position.x + p.x * cos - p.y * sin,
+                position.y + p.x * sin + p.y * cos,
+            )
+        };
+        let mut hull = Self {
+            points: [PhysicsVec2::new(0.0, 0.0); MAX_POLYGON_VERTICES],
+            count: 0,
+            radius: 0.0,
+        };
+        let push = |hull: &mut Self, p: PhysicsVec2| {
+            hull.points[hull.count] = place(p);
+            hull.count += 1;
+        };
+        match shape {
+            CollisionShape::Circle { radius } => {
+                push(&mut hull, PhysicsVec2::new(0.0, 0.0));
+                hull.radius = *radius;
+            }
+            CollisionShape::Box {
+                half_width: w,
+                half_height: h,
+            } => {
+                for (x, y) in [(-w, -h), (*w, -h), (*w, *h), (-w, *h)] {
+                    push(&mut hull, PhysicsVec2::new(x, y));
+                }
+            }
+            CollisionShape::Polygon { points } => {
+                for p in points.iter().take(MAX_POLYGON_VERTICES) {
+                    push(&mut hull, *p);
+                }
+                let turn = cross(
+                    sub(hull.points[1], hull.points[0]),
+                    sub(hull.points[2], hull.points[1]),
+                );
+                if turn < 0.0 {
+                    hull.points[..hull.count].reverse();
+                }
+            }
+            CollisionShape::Capsule {
+                half_length,
+                radius,
+            } => {
+                push(&mut hull, PhysicsVec2::new(0.0, -half_length));
+                if *half_length > 0.0 {
+                    push(&mut hull, PhysicsVec2::new(0.0, *half_length));
+                }
+                hull.radius = *radius;
+            }
+        }
+        hull
+    }
+
+    fn vertices(&self) -> &[PhysicsVec2] {
+        &self.points[..self.count]
+    }
+
+    /// Edge `i` runs from vertex `i` to the next; a segment has one face per side.
+    fn edge(&self, i: usize) -> (PhysicsVec2, PhysicsVec2) {
+        (self.points[i], self.points[(i + 1) % self.count])
+    }
+
+    fn face_normal(&self, i: usize) -> PhysicsVec2 {
+        let (from, to) = self.edge(i);
+        let e = sub(to, from);
+        scale(PhysicsVec2::new(e.y, -e.x), 1.0 / e.x.hypot(e.y))
+    }
+
+    /// Faces usable as separating axes: none for a point.
+    fn face_count(&self) -> usize {
+        if self.count < 2 {
+            0
+        } else {
+            self.count
+        }
+    }
+
+    fn contains(&self, p: PhysicsVec2) -> bool {
+        self.count >= 3
+            && (0..self.count).all(|i| dot(self.face_normal(i), sub(p, self.points[i])) <= 0.0)
+    }
+}
+
+/// Closest points between two cores and their distance, or `None` when the cores overlap.
+fn core_separation(a: &Hull, b: &Hull) -> Option<(PhysicsVec2, PhysicsVec2, f64)> {
+    if a.contains(b.points[0]) || b.contains(a.points[0]) {
+        return None;
+    }
+    let segments = |hull: &Hull| {
+        let count = if hull.count <= 2 { 1 } else { hull.count };
+        (0..count).map(|i| hull.edge(i)).collect::<Vec<_>>()
+    };
+    let mut best: Option<(PhysicsVec2, PhysicsVec2, f64)> = None;
+    for sa in segments(a) {
+        for sb in segments(b) {
+            let (pa, pb) = closest_points_on_segments(sa, sb);
+            let d = sub(pb, pa);
+            let distance = dot(d, d).sqrt();
+            if best.is_none_or(|(_, _, best)| distance < best) {
+                best = Some((pa, pb, distance));
+            }
+        }
+    }
+    best.filter(|(_, _, distance)| *distance > CORE_OVERLAP_EPSILON)
+}
+
+/// Closest pair of points between segments `p1-q1` and `p2-q2`; either may be a single point.
+fn closest_points_on_segments(
+    (p1, q1): (PhysicsVec2, PhysicsVec2),
+    (p2, q2): (PhysicsVec2, PhysicsVec2),
+) -> (PhysicsVec2, PhysicsVec2) {
+    let d1 = sub(q1, p1);
+    let d2 = sub(q2, p2);
+    let r = sub(p1, p2);
+    let a = dot(d1, d1);
+    let e = dot(d2, d2);
+    let f = dot(d2, r);
+    let (s, t) = if a <= f64::EPSILON && e <= f64::EPSILON {
+        (0.0, 0.0)
+    } else if a <= f64::EPSILON {
+        (0.0, (f / e).clamp(0.0, 1.0))
+    } else {
+        let c = dot(d1, r);
+        if e <= f64::EPSILON {
+            ((-c / a).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = dot(d1, d2);
+            let denom = a * e - b * b;
+            let s = if denom > f64::EPSILON {
+                ((b * f - c * e) / denom).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let t = (b * s + f) / e;
+            if t < 0.0 {
+                ((-c / a).clamp(0.0, 1.0), 0.0)
+            } else if t > 1.0 {
+                (((b - c) / a).clamp(0.0, 1.0), 1.0)
+            } else {
+                (s, t)
+            }
+        }
+    };
+    (add(p1, scale(d1, s)), add(p2, scale(d2, t)))
+}
+
+/// Face of `reference` along which `other` penetrates least, with that separation.
+fn max_separation(reference: &Hull, other: &Hull) -> Option<(usize, f64)> {
+    (0..reference.face_count())
+        .map(|i| {
+            let normal = reference.face_normal(i);
+            let separation = other
+                .vertices()
+                .iter()
+                .map(|v| dot(normal, sub(*v, reference.points[i])))
+                .fold(f64::INFINITY, f64::min);
+            (i, separation)
+        })
+        .fold(
+            None,
+            |best: Option<(usize, f64)>, (i, separation)| match best {
+                Some((_, best_separation)) if best_separation >= separation => best,
+                _ => Some((i, separation)),
+            },
+        )
+}
+
+/// SAT for overlapping cores: the least-penetrated face becomes the reference face and the
+/// opposing edge is clipped to its side planes, giving up to two contact points.
+fn clip_overlapping_cores(a: &Hull, b: &Hull) -> ContactManifold {
+    let reference = match (max_separation(a, b), max_separation(b, a)) {
+        (Some((face, sep_a)), Some((face_b, sep_b))) => {
+            if sep_b > sep_a + REFERENCE_FACE_BIAS {
+                Some((face_b, true))
+            } else {
+                Some((face, false))
+            }
+        }
+        (Some((face, _)), None) => Some((face, false)),
+        (None, Some((face, _))) => Some((face, true)),
+        (None, None) => None,
+    };
+    let Some((face, flip)) = reference else {
+        // Two circles on the same centre: any direction separates them.
+        return ContactManifold {
+            normal: PhysicsVec2::new(1.0, 0.0),
+            points: vec![ContactPoint {
+                point: a.points[0],
+                depth: a.radius + b.radius,
+            }],
+        };
+    };
+    let (reference, incident) = if flip { (b, a) } else { (a, b) };
+
+    let (v1, v2) = reference.edge(face);
+    let normal = reference.face_normal(face);
+    let (w1, w2) = if incident.count == 1 {
+        (incident.points[0], incident.points[0])
+    } else {
+        let edge = (0..incident.count)
+            .min_by(|i, j| {
+                dot(normal, incident.face_normal(*i))
+                    .total_cmp(&dot(normal, incident.face_normal(*j)))
+            })
+            .unwrap_or(0);
+        incident.edge(edge)
+    };
+
+    // Keep the part of the incident edge that lies beside the reference face.
+    let tangent_length = sub(v2, v1);
+    let face_length = dot(tangent_length, tangent_length).sqrt();
+    let tangent = scale(tangent_length, 1.0 / face_length);
+    let (s1, s2) = (dot(tangent, sub(w1, v1)), dot(tangent, sub(w2, v1)));
+    let mut clipped = [w1, w2];
+    if (s2 - s1).abs() > f64::EPSILON {
+        let at = |s: f64| add(w1, scale(sub(w2, w1), (s - s1) / (s2 - s1)));
+        let (low, high) = if s1 < s2 { (0, 1) } else { (1, 0) };
+        let (s_low, s_high) = (s1.min(s2), s1.max(s2));
+        if s_low < 0.0 && s_high > 0.0 {
+            clipped[low] = at(0.0);
+        }
+        if s_high > face_length && s_low < face_length {
+            clipped[high] = at(face_length);
+        }
+    }
+
+    let radius = reference.radius + incident.radius;
+    let candidates = if w1 == w2 {
+        &clipped[..1]
+    } else {
+        &clipped[..]
+    };
+    let contact = |p: PhysicsVec2| {
+        let separation = dot(normal, sub(p, v1));
+        ContactPoint {
+            point: sub(
+                p,
+                scale(
+                    normal,
+                    0.5 * (incident.radius + separation - reference.radius),
+                ),
+            ),
+            depth: radius - separation,
+        }
+    };
+    let mut points = candidates
+        .iter()
+        .map(|p| contact(*p))
+        .filter(|c| c.depth >= 0.0)
+        .collect::<Vec<_>>();
+    if points.is_empty() {
+        let deepest = candidates
+            .iter()
+            .map(|p| contact(*p))
+            .max_by(|x, y| x.depth.total_cmp(&y.depth))
+            .expect("at least one incident point");
+        points.push(deepest);
+    }
+
+    ContactManifold {
+        normal: if flip { scale(normal, -1.0) } else { normal },
+        points,
+    }
+}
+
+/// Area and origin-centred polar moment (per unit density) of each triangle fanned from the
+/// body origin across a polygon edge, signed so that either winding comes out positive.
+fn polygon_fans(points: &[PhysicsVec2]) -> impl Iterator<Item = (f64, f64)> + '_ {
+    let winding = polygon_winding(points);
+    (0..points.len()).map(move |i| {
+        let (p, q) = (points[i], points[(i + 1) % points.len()]);
+        let area = cross(p, q) * 0.5 * winding;
+        (area, area * (dot(p, p) + dot(p, q) + dot(q, q)) / 6.0)
+    })
+}
+
+fn polygon_winding(points: &[PhysicsVec2]) -> f64 {
+    let twice_area = (0..points.len())
+        .map(|i| cross(points[i], points[(i + 1) % points.len()]))
+        .sum::<f64>();
+    if twice_area < 0.0 {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
+fn add(a: PhysicsVec2, b: PhysicsVec2) -> PhysicsVec2 {
+    PhysicsVec2::new(a.x + b.x, a.y + b.y)
+}
+
+fn sub(a: PhysicsVec2, b: PhysicsVec2) -> PhysicsVec2 {
+    PhysicsVec2::new(a.x - b.x, a.y - b.y)
+}
+
+fn scale(a: PhysicsVec2, s: f64) -> PhysicsVec2 {
+    PhysicsVec2::new(a.x * s, a.y * s)
+}
+
+fn dot(a: PhysicsVec2, b: PhysicsVec2) -> f64 {
+    a.x * b.x + a.y * b.y
+}
+
+fn cross(a: PhysicsVec2, b: PhysicsVec2) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collide, CollisionShape};
+    use crate::physics::PhysicsVec2;
+
+    fn v(x: f64, y: f64) -> PhysicsVec2 {
+        PhysicsVec2::new(x, y)
+    }
+
+    fn close(a: PhysicsVec2, b: PhysicsVec2) -> bool {
+        (a.x - b.x).abs() < 1e-9 && (a.y - b.y).abs() < 1e-9
+    }
+
+    #[test]
+    fn stacked_boxes_get_a_two_point_manifold() {
+        let crate_box = CollisionShape::Box {
+            half_width: 10.0,
+            half_height: 10.0,
+        };
+        let ground = CollisionShape::Box {
+            half_width: 50.0,
+            half_height: 5.0,
+        };
+        // The crate sinks 1 unit into the ground below it (y grows downward).
+        let m = collide(&crate_box, v(0.0, 0.0), 0.0, &ground, v(0.0, 14.0), 0.0)
+            .expect("boxes overlap");
+        assert!(close(m.normal, v(0.0, 1.0)), "{m:?}");
+        assert_eq!(m.points.len(), 2);
+        for point in &m.points {
+            assert!((point.depth - 1.0).abs() < 1e-9);
+            assert!((point.point.y - 9.5).abs() < 1e-9);
+        }
+        let xs = (
+            m.points[0].point.x.min(m.points[1].point.x),
+            m.points[0].point.x.max(m.points[1].point.x),
+        );
+        assert!(close(v(xs.0, xs.1), v(-10.0, 10.0)), "{m:?}");
+
+        // Turned 45 degrees, the crate rests on a corner.
+        let m = collide(
+            &crate_box,
+            v(0.0, 0.0),
+            std::f64::consts::FRAC_PI_4,
+            &ground,
+            v(0.0, 18.0),
+            0.0,
+        )
+        .expect("corner overlaps");
+        assert_eq!(m.points.len(), 1);
+        assert!((m.depth() - (200.0_f64.sqrt() - 13.0)).abs() < 1e-9);
+        assert!(collide(&crate_box, v(0.0, 0.0), 0.0, &ground, v(0.0, 15.5), 0.0).is_none());
+    }
+
+    #[test]
+    fn rounded_shapes_collide_with_polygons_and_each_other() {
+        let triangle = CollisionShape::Polygon {
+            points: vec![v(0.0, -10.0), v(-10.0, 10.0), v(10.0, 10.0)],
+        };
+        let ball = CollisionShape::Circle { radius: 5.0 };
+        let m = collide(&triangle, v(0.0, 0.0), 0.0, &ball, v(0.0, 14.0), 0.0)
+            .expect("ball touches the flat side");
+        assert!(close(m.normal, v(0.0, 1.0)), "{m:?}");
+        assert!((m.depth() - 1.0).abs() < 1e-9);
+
+        // A capsule standing on a box touches it with its lower cap.
+        let capsule = CollisionShape::Capsule {
+            half_length: 10.0,
+            radius: 4.0,
+        };
+        let floor = CollisionShape::Box {
+            half_width: 20.0,
+            half_height: 2.0,
+        };
+        let m = collide(&capsule, v(0.0, 0.0), 0.0, &floor, v(0.0, 15.5), 0.0)
+            .expect("capsule rests on the floor");
+        assert!(close(m.normal, v(0.0, 1.0)), "{m:?}");
+        assert!((m.depth() - 0.5).abs() < 1e-9);
+
+        // Lying down, two capsules overlap along their sides.
+        let m = collide(
+            &capsule,
+            v(0.0, 0.0),
+            std::f64::consts::FRAC_PI_2,
+            &capsule,
+            v(5.0, 7.0),
+            std::f64::consts::FRAC_PI_2,
+        )
+        .expect("capsules overlap");
+        assert!(close(m.normal, v(0.0, 1.0)), "{m:?}");
+        assert!((m.depth() - 1.0).abs() < 1e-9);
+
+        // Circle pairs keep their centre-to-centre normal.
+        let m = collide(&ball, v(0.0, 0.0), 0.0, &ball, v(6.0, 8.0), 0.0).expect("overlap");
+        assert!(close(m.normal, v(0.6, 0.8)));
+        assert!(collide(&ball, v(0.0, 0.0), 0.0, &ball, v(8.0, 8.0), 0.0).is_none());
+    }
+
+    #[test]
+    fn rejects_concave_and_oversized_polygons() {
+        let concave = CollisionShape::Polygon {
+            points: vec![v(0.0, 0.0), v(10.0, 0.0), v(2.0, 2.0), v(0.0, 10.0)],
+        };
+        assert!(concave.validate().is_err());
+        let too_many = CollisionShape::Polygon {
+            points: (0..9)
+                .map(|i| {
+                    let angle = i as f64 * std::f64::consts::TAU / 9.0;
+                    v(angle.cos(), angle.sin())
+                })
+                .collect(),
+        };
+        assert!(too_many.validate().is_err());
+        let clockwise = CollisionShape::Polygon {
+            points: vec![v(0.0, 0.0), v(0.0, 10.0), v(10.0, 0.0)],
+        };
+        assert!(clockwise.validate().is_ok());
+        // Every corner of a pentagram turns the same way, but it winds around twice.
+        let pentagram = CollisionShape::Polygon {
+            points: (0..5)
+                .map(|i| {
+                    let angle = i as f64 * 2.0 * std::f64::consts::TAU / 5.0;
+                    v(angle.cos(), angle.sin())
+                })
+                .collect(),
+        };
+        assert!(pentagram.validate().is_err());
+    }
+
+    #[test]
+    fn mass_properties_agree_across_equivalent_shapes() {
+        let boxed = CollisionShape::Box {
+            half_width: 3.0,
+            half_height: 2.0,
+        };
+        // Clockwise on purpose: winding must not change the sign.
+        let square = CollisionShape::Polygon {
+            points: vec![v(-3.0, -2.0), v(-3.0, 2.0), v(3.0, 2.0), v(3.0, -2.0)],
+        };
+        assert!((boxed.area() - 24.0).abs() < 1e-9);
+        assert!((square.area() - boxed.area()).abs() < 1e-9);
+        assert!((square.unit_inertia() - boxed.unit_inertia()).abs() < 1e-9);
+
+        let disc = CollisionShape::Circle { radius: 4.0 };
+        let stub = CollisionShape::Capsule {
+            half_length: 0.0,
+            radius: 4.0,
+        };
+        assert!((stub.area() - disc.area()).abs() < 1e-9);
+        assert!((stub.unit_inertia() - disc.unit_inertia()).abs() < 1e-9);
+    }
+}
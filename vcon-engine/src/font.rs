@@ -0,0 +1,676 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use font8x8::{UnicodeFonts, BASIC_FONTS};
+
+use crate::render::Texture;
+use crate::ttf::TtfFace;
+
+/// Pixel size TTF fonts are rasterized at when no `<stem>.font.toml` sets one.
+pub const DEFAULT_TTF_SIZE: u32 = 32;
+const MAX_TTF_SIZE: u32 = 256;
+const TTF_PAGE_WIDTH: u32 = 1024;
+
+#[derive(Debug, Clone, Copy)]
+struct Glyph {
+    page: usize,
+    u: u32,
+    v: u32,
+    w: u32,
+    h: u32,
+    x_offset: f64,
+    y_offset: f64,
+    advance: f64,
+}
+
+#[derive(Debug, Clone)]
+struct GlyphPage {
+    width: u32,
+    alpha: Vec<u8>,
+}
+
+/// A glyph coverage atlas plus the metrics needed to lay text out. Metrics are in pixels at the
+/// font's native `size`; `Text` draws scale them by `size / font.size()`.
+#[derive(Debug, Clone)]
+pub struct Font {
+    size: f64,
+    line_height: f64,
+    min_scale: f64,
+    missing_advance: f64,
+    pages: Vec<GlyphPage>,
+    glyphs: HashMap<char, Glyph>,
+    kerning: HashMap<(char, char), f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl TextAlign {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "left" => Some(Self::Left),
+            "center" => Some(Self::Center),
+            "right" => Some(Self::Right),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Left => "left",
+            Self::Center => "center",
+            Self::Right => "right",
+        }
+    }
+
+    fn factor(self) -> f64 {
+        match self {
+            Self::Left => 0.0,
+            Self::Center => 0.5,
+            Self::Right => 1.0,
+        }
+    }
+}
+
+/// Line breaking and placement options for a block of text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextLayout {
+    /// Wrap width in drawn pixels; `None` only breaks at newlines.
+    pub max_width: Option<f64>,
+    pub align: TextAlign,
+    /// Multiplier on the font's line height between consecutive baselines.
+    pub line_spacing: f64,
+}
+
+impl Default for TextLayout {
+    fn default() -> Self {
+        Self {
+            max_width: None,
+            align: TextAlign::Left,
+            line_spacing: 1.0,
+        }
+    }
+}
+
+/// A glyph pen position produced by [`Font::layout`], already scaled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PlacedGlyph {
+    pub ch: char,
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Font {
+    /// The 8x8 `font8x8` face, covering printable ASCII. It never draws below its native size.
+    pub fn builtin() -> &'static Font {
+        static BUILTIN: OnceLock<Font> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            let cell = 8u32;
+            let cols = 16u32;
+            let width = cols * cell;
+            let height = 8 * cell;
+            let mut alpha = vec![0u8; (width * height) as usize];
+            let mut glyphs = HashMap::new();
+
+            for code in 32u8..=127u8 {
+                let ch = code as char;
+                let idx = (code - 32) as u32;
+                let gx = (idx % cols) * cell;
+                let gy = (idx / cols) * cell;
+
+                if let Some(bitmap) = BASIC_FONTS.get(ch) {
+                    for (row, bits) in bitmap.iter().enumerate() {
+                        for col in 0..8u32 {
+                            if (bits >> col) & 1 == 1 {
+                                let off = (gy + row as u32) * width + gx + col;
+                                alpha[off as usize] = 255;
+                            }
+                        }
+                    }
+                }
+
+                glyphs.insert(
+                    ch,
+                    Glyph {
+                        page: 0,
+                        u: gx,
+                        v: gy,
+                        w: cell,
+                        h: cell,
+                        x_offset: 0.0,
+                        y_offset: 0.0,
+                        advance: 9.0,
+                    },
+                );
+            }
+
+            Font {
+                size: 8.0,
+                line_height: 8.0,
+                min_scale: 1.0,
+                missing_advance: 9.0,
+                pages: vec![GlyphPage { width, alpha }],
+                glyphs,
+                kerning: HashMap::new(),
+            }
+        })
+    }
+
+    /// Parses an AngelCode BMFont text descriptor (`.fnt`). `page` resolves each `page file=`
+    /// entry to its image; glyph coverage is alpha times brightness, so both white-on-transparent
+    /// and white-on-black sheets work.
+    pub fn from_bmfont<'a>(
+        descriptor: &str,
+        mut page: impl FnMut(&str) -> Option<&'a Texture>,
+    ) -> Result<Self, String> {
+        let mut size = None;
+        let mut line_height = None;
+        let mut pages = Vec::new();
+        let mut glyphs = HashMap::new();
+        let mut kerning = HashMap::new();
+
+        for (line_no, line) in descriptor.lines().enumerate() {
+            let line = line.trim_start();
+            let (tag, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            if tag.is_empty() {
+                continue;
+            }
+            let attrs = parse_bmfont_attrs(rest);
+            let int = |key: &str| -> Result<i64, String> {
+                attrs
+                    .get(key)
+                    .ok_or_else(|| format!("line {}: `{tag}` missing `{key}`", line_no + 1))?
+                    .parse::<i64>()
+                    .map_err(|_| format!("line {}: `{key}` must be an integer", line_no + 1))
+            };
+            match tag {
+                "info" => size = Some(int("size")?.unsigned_abs() as f64),
+                "common" => line_height = Some(int("lineHeight")? as f64),
+                "page" => {
+                    let id = int("id")?;
+                    if id != pages.len() as i64 {
+                        return Err(format!(
+                            "line {}: pages must be listed in order",
+                            line_no + 1
+                        ));
+                    }
+                    let file = attrs
+                        .get("file")
+                        .ok_or_else(|| format!("line {}: `page` missing `file`", line_no + 1))?;
+                    let texture =
+                        page(file).ok_or_else(|| format!("page image `{file}` not found"))?;
+                    pages.push(GlyphPage {
+                        width: texture.width,
+                        alpha: texture
+                            .pixels
+                            .chunks_exact(4)
+                            .map(|px| {
+                                let bright = px[0].max(px[1]).max(px[2]) as u16;
+                                ((px[3] as u16 * bright) / 255) as u8
+                            })
+                            .collect(),
+                    });
+                }
+                "char" => {
+                    let id = int("id")?;
+                    let Some(ch) = u32::try_from(id).ok().and_then(char::from_u32) else {
+                        continue;
+                    };
+                    let glyph = Glyph {
+                        page: int("page")? as usize,
+                        u: int("x")? as u32,
+                        v: int("y")? as u32,
+                        w: int("width")? as u32,
+                        h: int("height")? as u32,
+                        x_offset: int("xoffset")? as f64,
+                        y_offset: int("yoffset")? as f64,
+                        advance: int("xadvance")? as f64,
+                    };
+                    glyphs.insert(ch, glyph);
+                }
+                "kerning" => {
+                    let first = u32::try_from(int("first")?).ok().and_then(char::from_u32);
+                    let second = u32::try_from(int("second")?).ok().and_then(char::from_u32);
+                    if let (Some(first), Some(second)) = (first, second) {
+                        kerning.insert((first, second), int("amount")? as f64);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let line_height = line_height.ok_or("missing `common lineHeight`")?;
+        let size = size.filter(|size| *size > 0.0).unwrap_or(line_height);
+        if size <= 0.0 {
+            return Err("font size must be > 0".to_owned());
+        }
+        for (ch, glyph) in &glyphs {
+            let page = pages
+                .get(glyph.page)
+                .ok_or_else(|| format!("glyph {ch:?} uses missing page {}", glyph.page))?;
+            let page_height = page.alpha.len() as u64 / page.width.max(1) as u64;
+            if glyph.u as u64 + glyph.w as u64 > page.width as u64
+                || glyph.v as u64 + glyph.h as u64 > page_height
+            {
+                return Err(format!("glyph {ch:?} is outside its page image"));
+            }
+        }
+
+        Ok(Self::assemble(
+            size,
+            line_height,
+            0.0,
+            pages,
+            glyphs,
+            kerning,
+        ))
+    }
+
+    /// Rasterizes a TrueType-outline font at `size` pixels for printable ASCII plus `extra`.
+    pub fn from_ttf(bytes: &[u8], size: u32, extra: &str) -> Result<Self, String> {
+        if size == 0 || size > MAX_TTF_SIZE {
+            return Err(format!("size must be 1..={MAX_TTF_SIZE}"));
+        }
+        let px = size as f64;
+        let face = TtfFace::parse(bytes)?;
+        let ascent = face.ascent(px);
+
+        let mut chars: Vec<char> = (' '..='~').chain(extra.chars()).collect();
+        chars.sort_unstable();
+        chars.dedup();
+
+        // Shelf-pack glyphs into one page, left to right.
+        let mut alpha = Vec::new();
+        let mut glyphs = HashMap::new();
+        let (mut pen_x, mut shelf_y, mut shelf_h) = (0u32, 0u32, 0u32);
+        for &ch in &chars {
+            let index = face.glyph_index(ch);
+            if index == 0 && ch != ' ' {
+                continue;
+            }
+            let bitmap = face.rasterize(index, px)?;
+            let (w, h) = (bitmap.width, bitmap.height);
+            if pen_x + w > TTF_PAGE_WIDTH {
+                pen_x = 0;
+                shelf_y += shelf_h + 1;
+                shelf_h = 0;
+            }
+            alpha.resize(((shelf_y + h.max(shelf_h)) * TTF_PAGE_WIDTH) as usize, 0);
+            for row in 0..h {
+                let src = (row * w) as usize;
+                let dst = ((shelf_y + row) * TTF_PAGE_WIDTH + pen_x) as usize;
+                alpha[dst..dst + w as usize]
+                    .copy_from_slice(&bitmap.coverage[src..src + w as usize]);
+            }
+            glyphs.insert(
+                ch,
+                Glyph {
+                    page: 0,
+                    u: pen_x,
+                    v: shelf_y,
+                    w,
+                    h,
+                    x_offset: bitmap.left as f64,
+                    y_offset: ascent - bitmap.top as f64,
+                    advance: bitmap.advance,
+                },
+            );
+            pen_x += w + 1;
+            shelf_h = shelf_h.max(h);
+        }
+
+        let mut kerning = HashMap::new();
+        for &first in &chars {
+            for &second in &chars {
+                let amount = face.kerning(face.glyph_index(first), face.glyph_index(second), px);
+                if amount != 0.0 {
+                    kerning.insert((first, second), amount);
+                }
+            }
+        }
+
+        let pages = vec![GlyphPage {
+            width: TTF_PAGE_WIDTH,
+            alpha,
+        }];
+        Ok(Self::assemble(
+            size as f64,
+            face.line_height(px).ceil(),
+            0.0,
+            pages,
+            glyphs,
+            kerning,
+        ))
+    }
+
+    fn assemble(
+        size: f64,
+        line_height: f64,
+        min_scale: f64,
+        pages: Vec<GlyphPage>,
+        glyphs: HashMap<char, Glyph>,
+        kerning: HashMap<(char, char), f64>,
+    ) -> Self {
+        let missing_advance = glyphs.get(&' ').map_or(size * 0.5, |glyph| glyph.advance);
+        Self {
+            size,
+            line_height,
+            min_scale,
+            missing_advance,
+            pages,
+            glyphs,
+            kerning,
+        }
+    }
+
+    /// Native pixel size the metrics are expressed in.
+    pub fn size(&self) -> f64 {
+        self.size
+    }
+
+    pub fn line_height(&self) -> f64 {
+        self.line_height
+    }
+
+    /// Smallest scale draws use; the builtin face never shrinks below its 8x8 cells.
+    pub fn min_scale(&self) -> f64 {
+        self.min_scale
+    }
+
+    /// Advance used for characters the font has no glyph for.
+    pub fn missing_advance(&self) -> f64 {
+        self.missing_advance
+    }
+
+    /// Scale factor a `Text` draw of `size` pixels applies to this font.
+    pub fn scale_for(&self, size: f64) -> f64 {
+        (size / self.size).max(self.min_scale)
+    }
+
+    /// Horizontal pen advance of `ch`, in native pixels.
+    pub fn advance(&self, ch: char) -> f64 {
+        self.glyphs
+            .get(&ch)
+            .map_or(self.missing_advance, |glyph| glyph.advance)
+    }
+
+    pub fn kerning(&self, first: char, second: char) -> f64 {
+        self.kerning.get(&(first, second)).copied().unwrap_or(0.0)
+    }
+
+    /// Characters the font has glyphs for.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.glyphs.keys().copied()
+    }
+
+    /// `(width, height)` of `text` drawn at `size` pixels: the widest laid-out line and the
+    /// distance from the first line's top to the last line's bottom.
+    pub fn measure(&self, text: &str, size: f64, layout: &TextLayout) -> (f64, f64) {
+        let scale = self.scale_for(size);
+        let lines = self.wrap(text, scale, layout.max_width);
+        let widest = lines.iter().map(|line| line.1).fold(0.0, f64::max);
+        (
+            widest * scale,
+            self.block_height(lines.len(), scale, layout),
+        )
+    }
+
+    /// Splits `text` into lines with their widths in native pixels. Hard newlines always break;
+    /// with `max_width` (in drawn pixels) lines also break after the last space that fits, or
+    /// mid-word when a single word is too wide, and spaces at a break are dropped.
+    pub fn wrap<'t>(
+        &self,
+        text: &'t str,
+        scale: f64,
+        max_width: Option<f64>,
+    ) -> Vec<(&'t str, f64)> {
+        let mut lines = Vec::new();
+        for line in text.split('\n') {
+            let Some(max_width) = max_width else {
+                lines.push(line);
+                continue;
+            };
+            let max = max_width / scale;
+            let mut start = 0;
+            let mut break_at = None;
+            for (i, ch) in line.char_indices() {
+                if ch == ' ' {
+                    break_at = Some(i);
+                    continue;
+                }
+                let end = i + ch.len_utf8();
+                while i > start && self.line_width(&line[start..end]) > max {
+                    let cut = break_at.take().filter(|at| *at > start).unwrap_or(i);
+                    lines.push(line[start..cut].trim_end_matches(' '));
+                    start = cut + (line[cut..].len() - line[cut..].trim_start_matches(' ').len());
+                }
+            }
+            lines.push(line[start..].trim_end_matches(' '));
+        }
+        lines
+            .into_iter()
+            .map(|line| (line, self.line_width(line)))
+            .collect()
+    }
+
+    fn line_width(&self, line: &str) -> f64 {
+        let mut width = 0.0;
+        let mut prev = None;
+        for ch in line.chars() {
+            if let Some(prev) = prev {
+                width += self.kerning(prev, ch);
+            }
+            width += self.advance(ch);
+            prev = Some(ch);
+        }
+        width
+    }
+
+    fn block_height(&self, lines: usize, scale: f64, layout: &TextLayout) -> f64 {
+        let pitch = self.line_height * layout.line_spacing;
+        ((lines.max(1) - 1) as f64 * pitch + self.line_height) * scale
+    }
+
+    /// Pen positions for each drawable glyph of `text`, laid out as a block whose top-left is
+    /// `(x, y)`. Lines align within `max_width` when set, otherwise within the widest line.
+    pub(crate) fn layout(
+        &self,
+        text: &str,
+        x: f64,
+        y: f64,
+        scale: f64,
+        layout: &TextLayout,
+    ) -> Vec<PlacedGlyph> {
+        let lines = self.wrap(text, scale, layout.max_width);
+        let block_width = layout
+            .max_width
+            .unwrap_or_else(|| lines.iter().map(|line| line.1 * scale).fold(0.0, f64::max));
+        let mut placed = Vec::new();
+        for (row, (line, width)) in lines.into_iter().enumerate() {
+            let mut pen_x = x + (block_width - width * scale) * layout.align.factor();
+            let pen_y = y + row as f64 * self.line_height * layout.line_spacing * scale;
+            let mut prev = None;
+            for ch in line.chars() {
+                if let Some(prev) = prev {
+                    pen_x += self.kerning(prev, ch) * scale;
+                }
+                if self.glyphs.contains_key(&ch) {
+                    placed.push(PlacedGlyph {
+                        ch,
+                        x: pen_x,
+                        y: pen_y,
+                    });
+                }
+                pen_x += self.advance(ch) * scale;
+                prev = Some(ch);
+            }
+        }
+        placed
+    }
+
+    /// Output rectangle `(x, y, w, h)` in pixels and a coverage sampler for `ch` at a pen position.
+    pub(crate) fn glyph_quad(&self, ch: char, x: f64, y: f64, scale: f64) -> Option<GlyphQuad<'_>> {
+        let glyph = self.glyphs.get(&ch)?;
+        if glyph.w == 0 || glyph.h == 0 {
+            return None;
+        }
+        Some(GlyphQuad {
+            x0: (x + glyph.x_offset * scale).round() as i32,
+            y0: (y + glyph.y_offset * scale).round() as i32,
+            out_w: (glyph.w as f64 * scale).round().max(1.0) as u32,
+            out_h: (glyph.h as f64 * scale).round().max(1.0) as u32,
+            glyph: *glyph,
+            page: &self.pages[glyph.page],
+        })
+    }
+}
+
+/// A glyph resolved to screen pixels; `coverage` samples it nearest-neighbour.
+pub(crate) struct GlyphQuad<'a> {
+    pub x0: i32,
+    pub y0: i32,
+    pub out_w: u32,
+    pub out_h: u32,
+    glyph: Glyph,
+    page: &'a GlyphPage,
+}
+
+impl GlyphQuad<'_> {
+    pub fn coverage(&self, ox: u32, oy: u32) -> u8 {
+        let glyph = &self.glyph;
+        let gx = ((ox as f64 / self.out_w as f64) * glyph.w as f64)
+            .floor()
+            .min((glyph.w - 1) as f64) as u32;
+        let gy = ((oy as f64 / self.out_h as f64) * glyph.h as f64)
+            .floor()
+            .min((glyph.h - 1) as f64) as u32;
+        self.page.alpha[((glyph.v + gy) * self.page.width + glyph.u + gx) as usize]
+    }
+}
+
+/// `key=value` pairs following a BMFont line's tag; quoted values may be empty or contain
+/// spaces.
+fn parse_bmfont_attrs(line: &str) -> HashMap<String, String> {
+    let mut attrs = HashMap::new();
+    let mut rest = line.trim_start();
+    while !rest.is_empty() {
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let key = &rest[..key_end];
+        let Some(value) = rest[key_end..].strip_prefix('=') else {
+            rest = rest[key_end..].trim_start();
+            continue;
+        };
+        let (value, tail) = match value.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => value.split_once(char::is_whitespace).unwrap_or((value, "")),
+        };
+        attrs.insert(key.to_owned(), value.to_owned());
+        rest = tail.trim_start();
+    }
+    attrs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Font, TextAlign, TextLayout};
+    use crate::render::Texture;
+
+    #[test]
+    fn builtin_font_keeps_legacy_metrics() {
+        let font = Font::builtin();
+        let layout = TextLayout::default();
+        assert_eq!(font.measure("abc", 16.0, &layout), (54.0, 16.0));
+        // Sizes below 8 px still draw the 8x8 cells unscaled.
+        assert_eq!(font.measure("ab\nabc", 4.0, &layout), (27.0, 16.0));
+    }
+
+    #[test]
+    fn wraps_at_spaces_and_splits_long_words() {
+        let font = Font::builtin();
+        let wrapped = |text, max_width| -> Vec<&str> {
+            font.wrap(text, 1.0, Some(max_width))
+                .into_iter()
+                .map(|line| line.0)
+                .collect()
+        };
+        // Each builtin glyph advances 9 px.
+        assert_eq!(wrapped("aa bb cc", 54.0), ["aa bb", "cc"]);
+        assert_eq!(wrapped("aa   bb\n\ncc", 30.0), ["aa", "bb", "", "cc"]);
+        assert_eq!(wrapped("abcdefg", 27.0), ["abc", "def", "g"]);
+        assert_eq!(wrapped("a bcdefg", 27.0), ["a", "bcd", "efg"]);
+
+        let layout = TextLayout {
+            max_width: Some(54.0),
+            align: TextAlign::Right,
+            line_spacing: 1.5,
+        };
+        assert_eq!(font.measure("aa bb cc", 8.0, &layout), (45.0, 20.0));
+        let placed = font.layout("aa bb cc", 0.0, 0.0, 1.0, &layout);
+        let last = placed.last().expect("glyphs should be placed");
+        assert_eq!((last.ch, last.x, last.y), ('c', 45.0, 12.0));
+        let centered = TextLayout {
+            align: TextAlign::Center,
+            ..TextLayout::default()
+        };
+        let placed = font.layout("abc\na", 0.0, 0.0, 1.0, &centered);
+        assert_eq!((placed[3].x, placed[3].y), (9.0, 8.0));
+    }
+
+    #[test]
+    fn parses_bmfont_descriptor_with_kerning() {
+        let page = Texture {
+            width: 8,
+            height: 4,
+            pixels: [[255, 255, 255, 255]; 32].concat(),
+        };
+        let descriptor = r#"info face="Tiny Font" size=4 bold=0
+common lineHeight=5 base=4 scaleW=8 scaleH=4 pages=1
+page id=0 file="tiny_0.png"
+chars count=2
+char id=65 x=0 y=0 width=3 height=4 xoffset=0 yoffset=1 xadvance=4 page=0 chnl=15
+char id=66 x=4 y=0 width=3 height=4 xoffset=0 yoffset=1 xadvance=4 page=0 chnl=15
+kerning first=65 second=66 amount=-1
+"#;
+        let font = Font::from_bmfont(descriptor, |file| (file == "tiny_0.png").then_some(&page))
+            .expect("descriptor should parse");
+        assert_eq!(font.line_height(), 5.0);
+        assert_eq!(
+            font.measure("AB", 8.0, &TextLayout::default()),
+            (14.0, 10.0)
+        );
+        let quad = font.glyph_quad('B', 10.0, 0.0, 2.0).expect("glyph B");
+        assert_eq!((quad.x0, quad.y0, quad.out_w, quad.out_h), (10, 2, 6, 8));
+
+        let err = Font::from_bmfont(descriptor, |_| None).expect_err("page is missing");
+        assert_eq!(err, "page image `tiny_0.png` not found");
+    }
+
+    #[test]
+    fn parses_empty_and_spaced_quoted_bmfont_values() {
+        let attrs = super::parse_bmfont_attrs(r#"face="" file="my  page.png" id=3 lone"#);
+        assert_eq!(attrs.get("face").map(String::as_str), Some(""));
+        assert_eq!(attrs.get("file").map(String::as_str), Some("my  page.png"));
+        assert_eq!(attrs.get("id").map(String::as_str), Some("3"));
+        assert!(!attrs.contains_key("lone"));
+    }
+
+    #[test]
+    fn rasterizes_system_ttf_when_available() {
+        let Ok(bytes) = std::fs::read("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf") else {
+            return;
+        };
+        let font = Font::from_ttf(&bytes, 16, "é").expect("ttf should rasterize");
+        assert!(font.chars().any(|ch| ch == 'é'));
+        let (w, h) = font.measure("Hello", 16.0, &TextLayout::default());
+        assert!(w > 20.0 && w < 60.0, "{w}");
+        assert_eq!(h, font.line_height());
+        assert!(Font::from_ttf(b"not a font", 16, "").is_err());
+    }
+}
@@ -1,24 +1,40 @@
 pub mod audio;
+pub mod collision;
+pub mod font;
 pub mod host;
 pub mod input;
 pub mod input_mapping;
 pub mod manifest;
 pub mod physics;
+pub mod png;
 pub mod render;
 pub mod sandbox;
 pub mod scene;
 pub mod storage;
+pub mod tilemap;
+pub mod ttf;
 
-pub use audio::{ActiveVoice, AudioMixer, PlayRequest};
+pub use audio::{
+    literal_clip_references, ActiveVoice, AdsrEnvelope, AudioBus, AudioBusState, AudioClip,
+    AudioClipStore, AudioIoError, AudioMixer, DuckingConfig, PlayRequest, SynthTone, SynthVoice,
+    SynthWaveform, VoiceFade, VoiceParams, VoiceStealPolicy, WavFileSink,
+};
+pub use collision::{collide, CollisionShape, ContactManifold, ContactPoint, MAX_POLYGON_VERTICES};
+pub use font::{Font, TextAlign, TextLayout, DEFAULT_TTF_SIZE};
 pub use host::{boot_cartridge, BootReport, EngineError};
 pub use input::{scripted_input_frame, scripted_input_frame_seeded, InputFrame, InputSource};
 pub use input_mapping::{map_gamepad_state, InputProfile, RawGamepadState};
-pub use manifest::Manifest;
-pub use physics::{upsert_scene_body, CollisionEvent, PhysicsBackend, PhysicsVec2, PhysicsWorld};
+pub use manifest::{DisplayConfig, DisplayFilter, Manifest};
+pub use physics::{
+    upsert_scene_body, CollisionEvent, ContactPhase, PhysicsBackend, PhysicsVec2, PhysicsWorld,
+};
+pub use png::{decode_png, encode_png_rgba};
 pub use render::{
-    AssetLoadError, AssetStore, DrawCommand, FrameCommandBuffer, RenderIoError, RenderStats,
-    RenderValidationError, SoftwareFrame,
+    AssetLoadError, AssetStore, BlendMode, DrawCommand, FrameCommandBuffer, RenderIoError,
+    RenderStats, RenderValidationError, SoftwareFrame, MAX_PALETTE_COLORS, MAX_RENDER_TARGET_BYTES,
+    RENDER_TARGET_PREFIX,
 };
 pub use scene::{
     NodeId, PhysicsBody2D, PhysicsBodyKind, SceneError, SceneGraph, SceneNode, Transform2D,
 };
+pub use tilemap::{Tilemap, TilemapError, TilemapStore, TILEMAP_CHUNK};
@@ -1,6 +1,7 @@
 use serde::Deserialize;
 
 pub const SUPPORTED_SDK_VERSION: &str = "2";
+pub const MAX_DISPLAY_SIZE: u32 = 4096;
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 pub struct Manifest {
@@ -13,6 +14,39 @@ pub struct Manifest {
     pub save_quota_mb: u32,
     #[serde(default)]
     pub permissions: Vec<String>,
+    #[serde(default)]
+    pub display: Option<DisplayConfig>,
+}
+
+/// The `[display]` table: the logical resolution the cartridge renders at, which windowed runs
+/// upscale by whole multiples.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct DisplayConfig {
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub filter: DisplayFilter,
+}
+
+/// Post effect applied while upscaling to the window.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayFilter {
+    #[default]
+    None,
+    Scanlines,
+    Crt,
+}
+
+impl DisplayFilter {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DisplayFilter::None => "none",
+            DisplayFilter::Scanlines => "scanlines",
+            DisplayFilter::Crt => "crt",
+        }
+    }
 }
 
 impl Manifest {
@@ -64,10 +98,24 @@ impl Manifest {
                 "manifest key `save_quota_mb` must be greater than 0".to_owned(),
             ));
         }
+        if let Some(display) = self.display {
+            let valid = 1..=MAX_DISPLAY_SIZE;
+            if !valid.contains(&display.width) || !valid.contains(&display.height) {
+                return Err(ManifestError::Validation(format!(
+                    "manifest keys `display.width` and `display.height` must be 1..={MAX_DISPLAY_SIZE}"
+                )));
+            }
+        }
 
         Ok(())
     }
 
+    /// The cartridge's `[display]` resolution, or `fallback` when it does not declare one.
+    pub fn logical_size(&self, fallback: (u32, u32)) -> (u32, u32) {
+        self.display
+            .map_or(fallback, |display| (display.width, display.height))
+    }
+
     pub fn validate_sdk_version_compatibility(&self) -> Result<(), ManifestError> {
         if self.sdk_version == SUPPORTED_SDK_VERSION {
             return Ok(());
@@ -90,7 +138,7 @@ pub enum ManifestError {
 
 #[cfg(test)]
 mod tests {
-    use super::Manifest;
+    use super::{DisplayFilter, Manifest};
 
     #[test]
     fn parses_valid_manifest() {
@@ -162,4 +210,35 @@ permissions = ["storage"]
             .expect_err("sdk version should be gated");
         assert!(err.to_string().contains("must be `2`"));
     }
+
+    #[test]
+    fn parses_display_table_and_rejects_bad_sizes() {
+        let base = r#"
+id = "com.example.demo"
+name = "Demo"
+version = "0.1.0"
+entrypoint = "src/main.py"
+sdk_version = "2"
+assets_path = "assets"
+save_quota_mb = 8
+"#;
+
+        let manifest = Manifest::parse(base).expect("display is optional");
+        assert_eq!(manifest.logical_size((1280, 800)), (1280, 800));
+
+        let manifest = Manifest::parse(&format!(
+            "{base}\n[display]\nwidth = 320\nheight = 200\nfilter = \"crt\"\n"
+        ))
+        .expect("display table should parse");
+        assert_eq!(manifest.logical_size((1280, 800)), (320, 200));
+        assert_eq!(manifest.display.map(|d| d.filter), Some(DisplayFilter::Crt));
+
+        let err = Manifest::parse(&format!("{base}\n[display]\nwidth = 0\nheight = 200\n"))
+            .expect_err("zero width must fail");
+        assert!(err.to_string().contains("display.width"));
+        assert!(Manifest::parse(&format!(
+            "{base}\n[display]\nwidth = 320\nheight = 200\nfilter = \"blur\"\n"
+        ))
+        .is_err());
+    }
 }
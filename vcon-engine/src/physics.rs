@@ -1,8 +1,9 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::collision::{collide, CollisionShape, ContactManifold};
 use crate::scene::{NodeId, PhysicsBody2D, PhysicsBodyKind, SceneGraph};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct PhysicsVec2 {
     pub x: f64,
     pub y: f64,
@@ -29,10 +30,64 @@ impl PhysicsBackend {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContactPhase {
+    Begin,
+    End,
+}
+
+impl ContactPhase {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ContactPhase::Begin => "begin",
+            ContactPhase::End => "end",
+        }
+    }
+}
+
+/// A pair starting or stopping contact. Contact details are filled for `Begin` and zero for
+/// `End`, since the bodies no longer touch.
+#[derive(Debug, Clone, PartialEq)]
 pub struct CollisionEvent {
     pub a: NodeId,
     pub b: NodeId,
+    pub phase: ContactPhase,
+    /// Either body is a sensor, so the overlap was reported but not resolved.
+    pub sensor: bool,
+    /// Unit contact normal pointing from `a` towards `b`.
+    pub normal: PhysicsVec2,
+    /// World point centred between the contact points.
+    pub point: PhysicsVec2,
+    /// Deepest overlap along `normal`.
+    pub depth: f64,
+    /// Total normal impulse the solver applied to separate the pair this step; zero when the
+    /// pair only came into contact while moving.
+    pub impulse: f64,
+}
+
+/// What a step learned about one touching pair, kept for its `Begin` event.
+#[derive(Debug, Clone, Copy, Default)]
+struct PairContact {
+    normal: PhysicsVec2,
+    point: PhysicsVec2,
+    depth: f64,
+    impulse: f64,
+}
+
+impl PairContact {
+    fn from_manifold(manifold: &ContactManifold) -> Self {
+        let count = manifold.points.len().max(1) as f64;
+        let (sum_x, sum_y) = manifold
+            .points
+            .iter()
+            .fold((0.0, 0.0), |(x, y), p| (x + p.point.x, y + p.point.y));
+        Self {
+            normal: manifold.normal,
+            point: PhysicsVec2::new(sum_x / count, sum_y / count),
+            depth: manifold.depth(),
+            impulse: 0.0,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -40,18 +95,93 @@ struct BodyState {
     kind: PhysicsBodyKind,
     position: PhysicsVec2,
     velocity: PhysicsVec2,
-    radius: f64,
+    rotation: f64,
+    angular_velocity: f64,
+    shape: CollisionShape,
     restitution: f64,
+    friction: f64,
+    linear_damping: f64,
+    angular_damping: f64,
+    /// Zero for static and kinematic bodies, which contacts never move.
+    inverse_mass: f64,
+    inverse_inertia: f64,
+    category: u32,
+    mask: u32,
+    sensor: bool,
 }
 
 impl BodyState {
-    fn inverse_mass(&self) -> f64 {
+    fn new(body: &PhysicsBody2D, position: PhysicsVec2, rotation: f64) -> Self {
+        let mut state = Self {
+            kind: body.kind,
+            position,
+            velocity: PhysicsVec2::new(body.velocity_x, body.velocity_y),
+            rotation,
+            angular_velocity: body.angular_velocity,
+            shape: body.shape.clone(),
+            restitution: body.restitution,
+            friction: body.friction,
+            linear_damping: body.linear_damping,
+            angular_damping: body.angular_damping,
+            inverse_mass: 0.0,
+            inverse_inertia: 0.0,
+            category: body.category,
+            mask: body.mask,
+            sensor: body.sensor,
+        };
+        state.update_mass(body);
+        state
+    }
+
+    fn update_mass(&mut self, body: &PhysicsBody2D) {
         if matches!(self.kind, PhysicsBodyKind::Dynamic) {
-            1.0
+            let mass = body.mass.unwrap_or(body.density * self.shape.area());
+            let inertia = mass * self.shape.unit_inertia();
+            self.inverse_mass = 1.0 / mass;
+            self.inverse_inertia = if inertia > 0.0 { 1.0 / inertia } else { 0.0 };
         } else {
-            0.0
+            self.inverse_mass = 0.0;
+            self.inverse_inertia = 0.0;
         }
     }
+
+    /// Velocity of the body's material at `offset` from its origin.
+    fn point_velocity(&self, offset: PhysicsVec2) -> PhysicsVec2 {
+        PhysicsVec2::new(
+            self.velocity.x - self.angular_velocity * offset.y,
+            self.velocity.y + self.angular_velocity * offset.x,
+        )
+    }
+
+    /// Whether the two bodies' category and mask bits accept each other.
+    fn filters_with(&self, other: &BodyState) -> bool {
+        self.category & other.mask != 0 && other.category & self.mask != 0
+    }
+
+    fn inverse_mass(&self) -> f64 {
+        self.inverse_mass
+    }
+}
+
+/// One touching pair as the velocity solver sees it; impulses accumulate per contact point.
+#[derive(Debug, Clone)]
+struct SolverContact {
+    a: NodeId,
+    b: NodeId,
+    solid: bool,
+    manifold: ContactManifold,
+    normal_impulses: [f64; 2],
+    tangent_impulses: [f64; 2],
+}
+
+/// Bounding box of one body along both axes, used by the sweep-and-prune broad phase.
+#[derive(Debug, Clone, Copy)]
+struct BroadPhaseSpan {
+    id: NodeId,
+    min_x: f64,
+    max_x: f64,
+    min_y: f64,
+    max_y: f64,
 }
 
 #[derive(Debug, Clone)]
@@ -60,6 +190,8 @@ pub struct PhysicsWorld {
     gravity: PhysicsVec2,
     bodies: HashMap<NodeId, BodyState>,
     active_collisions: HashSet<(NodeId, NodeId)>,
+    /// End events for pairs whose body left the scene, delivered with the next step.
+    removed_contacts: Vec<CollisionEvent>,
 }
 
 impl Default for PhysicsWorld {
@@ -75,6 +207,7 @@ impl PhysicsWorld {
             gravity: PhysicsVec2::new(0.0, 0.0),
             bodies: HashMap::new(),
             active_collisions: HashSet::new(),
+            removed_contacts: Vec::new(),
         }
     }
 
@@ -109,24 +242,52 @@ impl PhysicsWorld {
             };
 
             seen.insert(node.id);
-            let entry = self.bodies.entry(node.id).or_insert_with(|| BodyState {
-                kind: body.kind,
-                position: PhysicsVec2::new(node.transform.x, node.transform.y),
-                velocity: PhysicsVec2::new(body.velocity_x, body.velocity_y),
-                radius: body.radius,
-                restitution: body.restitution,
-            });
+            let position = PhysicsVec2::new(node.transform.x, node.transform.y);
+            let entry = self
+                .bodies
+                .entry(node.id)
+                .or_insert_with(|| BodyState::new(body, position, node.transform.rotation));
 
             entry.kind = body.kind;
-            entry.position = PhysicsVec2::new(node.transform.x, node.transform.y);
+            entry.position = position;
             entry.velocity = PhysicsVec2::new(body.velocity_x, body.velocity_y);
-            entry.radius = body.radius;
+            entry.rotation = node.transform.rotation;
+            entry.angular_velocity = body.angular_velocity;
+            if entry.shape != body.shape {
+                entry.shape = body.shape.clone();
+            }
             entry.restitution = body.restitution;
+            entry.friction = body.friction;
+            entry.linear_damping = body.linear_damping;
+            entry.angular_damping = body.angular_damping;
+            entry.update_mass(body);
+            entry.category = body.category;
+            entry.mask = body.mask;
+            entry.sensor = body.sensor;
+        }
+
+        let mut dropped = self
+            .active_collisions
+            .iter()
+            .filter(|(a, b)| !seen.contains(a) || !seen.contains(b))
+            .copied()
+            .collect::<Vec<_>>();
+        dropped.sort_by_key(|(a, b)| (a.0, b.0));
+        for (a, b) in dropped {
+            self.active_collisions.remove(&(a, b));
+            self.removed_contacts.push(CollisionEvent {
+                a,
+                b,
+                phase: ContactPhase::End,
+                sensor: !self.is_solid_pair(a, b),
+                normal: PhysicsVec2::default(),
+                point: PhysicsVec2::default(),
+                depth: 0.0,
+                impulse: 0.0,
+            });
         }
 
         self.bodies.retain(|id, _| seen.contains(id));
-        self.active_collisions
-            .retain(|(a, b)| seen.contains(a) && seen.contains(b));
     }
 
     pub fn step(&mut self, dt_fixed: f64) -> Vec<CollisionEvent> {
@@ -145,24 +306,50 @@ impl PhysicsWorld {
                 state.velocity.x,
                 state.velocity.y,
             );
+            let _ = scene.update_physics_spin(*id, state.rotation, state.angular_velocity);
         }
     }
 
-    fn step_legacy(&mut self, dt_fixed: f64) -> Vec<CollisionEvent> {
+    /// Applies gravity and damping to dynamic bodies.
+    fn integrate_velocities(&mut self, dt_fixed: f64) {
         for body in self.bodies.values_mut() {
             if matches!(body.kind, PhysicsBodyKind::Dynamic) {
                 body.velocity.x += self.gravity.x * dt_fixed;
                 body.velocity.y += self.gravity.y * dt_fixed;
+                let linear = 1.0 / (1.0 + dt_fixed * body.linear_damping);
+                body.velocity.x *= linear;
+                body.velocity.y *= linear;
+                body.angular_velocity /= 1.0 + dt_fixed * body.angular_damping;
+            }
+        }
+    }
+
+    /// Moves dynamic and kinematic bodies by their velocities.
+    fn integrate_positions(&mut self, dt_fixed: f64) {
+        for body in self.bodies.values_mut() {
+            if !matches!(body.kind, PhysicsBodyKind::Static) {
                 body.position.x += body.velocity.x * dt_fixed;
                 body.position.y += body.velocity.y * dt_fixed;
+                body.rotation += body.angular_velocity * dt_fixed;
             }
         }
+    }
 
-        let mut collisions_now = HashSet::new();
-        for (a_id, b_id) in self.ordered_pairs() {
-            if self.resolve_collision_legacy(a_id, b_id) {
-                collisions_now.insert((a_id, b_id));
+    /// Resolves each contact once at the body centres; ignores friction and contact torque.
+    fn step_legacy(&mut self, dt_fixed: f64) -> Vec<CollisionEvent> {
+        self.integrate_velocities(dt_fixed);
+        self.integrate_positions(dt_fixed);
+
+        let mut collisions_now = HashMap::new();
+        for (a_id, b_id) in self.candidate_pairs() {
+            let Some(manifold) = self.contact(a_id, b_id) else {
+                continue;
+            };
+            let mut contact = PairContact::from_manifold(&manifold);
+            if self.is_solid_pair(a_id, b_id) {
+                contact.impulse = self.resolve_collision_legacy(a_id, b_id, &contact);
             }
+            collisions_now.insert((a_id, b_id), contact);
         }
 
         self.build_collision_events(collisions_now)
@@ -172,163 +359,264 @@ impl PhysicsWorld {
         const VELOCITY_ITERATIONS: usize = 8;
         const POSITION_ITERATIONS: usize = 3;
 
-        for body in self.bodies.values_mut() {
-            if matches!(body.kind, PhysicsBodyKind::Dynamic) {
-                body.velocity.x += self.gravity.x * dt_fixed;
-                body.velocity.y += self.gravity.y * dt_fixed;
-            }
-        }
+        self.integrate_velocities(dt_fixed);
 
-        let pairs = self.ordered_pairs();
+        // Contacts are gathered once before solving, as Box2D does: positions hold still while
+        // velocities are solved, and each pair sums the impulses it receives for its event.
+        let mut contacts = self
+            .candidate_pairs()
+            .into_iter()
+            .filter_map(|(a, b)| {
+                Some(SolverContact {
+                    a,
+                    b,
+                    solid: self.is_solid_pair(a, b),
+                    manifold: self.contact(a, b)?,
+                    normal_impulses: [0.0; 2],
+                    tangent_impulses: [0.0; 2],
+                })
+            })
+            .collect::<Vec<_>>();
 
         for _ in 0..VELOCITY_ITERATIONS {
-            for (a_id, b_id) in &pairs {
-                let _ = self.solve_velocity_contact(*a_id, *b_id);
+            for contact in contacts.iter_mut().filter(|contact| contact.solid) {
+                self.solve_velocity_contact(contact);
             }
         }
 
-        for body in self.bodies.values_mut() {
-            if matches!(body.kind, PhysicsBodyKind::Dynamic) {
-                body.position.x += body.velocity.x * dt_fixed;
-                body.position.y += body.velocity.y * dt_fixed;
-            }
-        }
+        self.integrate_positions(dt_fixed);
 
+        // Bodies have moved, so gather pairs again for positional correction.
+        let pairs = self.candidate_pairs();
         for _ in 0..POSITION_ITERATIONS {
             for (a_id, b_id) in &pairs {
-                let _ = self.solve_position_contact(*a_id, *b_id);
+                if self.is_solid_pair(*a_id, *b_id) {
+                    let _ = self.solve_position_contact(*a_id, *b_id);
+                }
             }
         }
 
-        let mut collisions_now = HashSet::new();
+        // Events describe where bodies ended up, so a pair begins on the step it first overlaps;
+        // impulses come from the solver when the pair was already touching before it moved.
+        let impulses = contacts
+            .into_iter()
+            .map(|contact| ((contact.a, contact.b), contact.normal_impulses.iter().sum()))
+            .collect::<HashMap<_, f64>>();
+        let mut collisions_now = HashMap::new();
         for (a_id, b_id) in pairs {
-            if self.is_overlapping(a_id, b_id) {
-                collisions_now.insert((a_id, b_id));
-            }
+            let Some(manifold) = self.contact(a_id, b_id) else {
+                continue;
+            };
+            let mut report = PairContact::from_manifold(&manifold);
+            report.impulse = impulses.get(&(a_id, b_id)).copied().unwrap_or(0.0);
+            collisions_now.insert((a_id, b_id), report);
         }
-
         self.build_collision_events(collisions_now)
     }
 
-    fn ordered_pairs(&self) -> Vec<(NodeId, NodeId)> {
-        let mut ids = self.bodies.keys().copied().collect::<Vec<_>>();
-        ids.sort_by_key(|id| id.0);
+    /// Sweep-and-prune along x over each body's axis-aligned box, taken around its shape at
+    /// its current rotation. Returns the pairs whose boxes overlap on both axes and whose
+    /// layers accept each other, ordered by `(a, b)` node id so solver order stays
+    /// deterministic.
+    fn candidate_pairs(&self) -> Vec<(NodeId, NodeId)> {
+        let mut spans = self
+            .bodies
+            .iter()
+            .map(|(id, body)| {
+                let (min, max) = body.shape.aabb(body.position, body.rotation);
+                BroadPhaseSpan {
+                    id: *id,
+                    min_x: min.x,
+                    max_x: max.x,
+                    min_y: min.y,
+                    max_y: max.y,
+                }
+            })
+            .collect::<Vec<_>>();
+        spans.sort_by(|a, b| a.min_x.total_cmp(&b.min_x).then(a.id.0.cmp(&b.id.0)));
 
         let mut pairs = Vec::new();
-        for i in 0..ids.len() {
-            for j in (i + 1)..ids.len() {
-                pairs.push((ids[i], ids[j]));
+        for (i, a) in spans.iter().enumerate() {
+            for b in &spans[i + 1..] {
+                if b.min_x > a.max_x {
+                    break;
+                }
+                if b.min_y <= a.max_y
+                    && a.min_y <= b.max_y
+                    && self.bodies[&a.id].filters_with(&self.bodies[&b.id])
+                {
+                    pairs.push(if a.id.0 < b.id.0 {
+                        (a.id, b.id)
+                    } else {
+                        (b.id, a.id)
+                    });
+                }
             }
         }
+        pairs.sort_unstable_by_key(|(a, b)| (a.0, b.0));
         pairs
     }
 
+    /// Diffs this step's touching pairs against the previous step: pairs that stopped
+    /// touching end first, then new pairs begin, each in `(a, b)` node id order.
     fn build_collision_events(
         &mut self,
-        collisions_now: HashSet<(NodeId, NodeId)>,
+        collisions_now: HashMap<(NodeId, NodeId), PairContact>,
     ) -> Vec<CollisionEvent> {
-        let mut events = Vec::new();
-        for pair in &collisions_now {
-            if !self.active_collisions.contains(pair) {
-                events.push(CollisionEvent {
-                    a: pair.0,
-                    b: pair.1,
-                });
-            }
-        }
-        events.sort_by_key(|event| (event.a.0, event.b.0));
-
-        self.active_collisions = collisions_now;
-        events
+        let touching = collisions_now.keys().copied().collect::<HashSet<_>>();
+        let mut ended = self
+            .active_collisions
+            .difference(&touching)
+            .copied()
+            .collect::<Vec<_>>();
+        let mut begun = touching
+            .difference(&self.active_collisions)
+            .copied()
+            .collect::<Vec<_>>();
+        ended.sort_by_key(|(a, b)| (a.0, b.0));
+        begun.sort_by_key(|(a, b)| (a.0, b.0));
+
+        let events = ended
+            .into_iter()
+            .map(|pair| (pair, ContactPhase::End, PairContact::default()))
+            .chain(
+                begun
+                    .into_iter()
+                    .map(|pair| (pair, ContactPhase::Begin, collisions_now[&pair])),
+            )
+            .map(|((a, b), phase, contact)| CollisionEvent {
+                a,
+                b,
+                phase,
+                sensor: !self.is_solid_pair(a, b),
+                normal: contact.normal,
+                point: contact.point,
+                depth: contact.depth,
+                impulse: contact.impulse,
+            })
+            .collect::<Vec<_>>();
+
+        self.active_collisions = touching;
+        let mut removed = std::mem::take(&mut self.removed_contacts);
+        removed.extend(events);
+        removed
     }
 
-    fn is_overlapping(&self, a_id: NodeId, b_id: NodeId) -> bool {
-        let Some(a_state) = self.bodies.get(&a_id) else {
-            return false;
-        };
-        let Some(b_state) = self.bodies.get(&b_id) else {
-            return false;
-        };
-
-        let dx = b_state.position.x - a_state.position.x;
-        let dy = b_state.position.y - a_state.position.y;
-        let radius_sum = a_state.radius + b_state.radius;
-        let dist_sq = dx * dx + dy * dy;
-
-        dist_sq <= radius_sum * radius_sum
+    fn is_solid_pair(&self, a_id: NodeId, b_id: NodeId) -> bool {
+        match (self.bodies.get(&a_id), self.bodies.get(&b_id)) {
+            (Some(a), Some(b)) => !a.sensor && !b.sensor,
+            _ => false,
+        }
     }
 
-    fn collision_normal_penetration(&self, a_id: NodeId, b_id: NodeId) -> Option<(f64, f64, f64)> {
+    fn contact(&self, a_id: NodeId, b_id: NodeId) -> Option<ContactManifold> {
         let a_state = self.bodies.get(&a_id)?;
         let b_state = self.bodies.get(&b_id)?;
-
-        let dx = b_state.position.x - a_state.position.x;
-        let dy = b_state.position.y - a_state.position.y;
-        let radius_sum = a_state.radius + b_state.radius;
-        let dist_sq = dx * dx + dy * dy;
-
-        if dist_sq > radius_sum * radius_sum {
-            return None;
-        }
-
-        let dist = dist_sq.sqrt();
-        let (nx, ny) = if dist > 1e-9 {
-            (dx / dist, dy / dist)
-        } else {
-            (1.0, 0.0)
-        };
-        let penetration = (radius_sum - dist).max(0.0);
-
-        Some((nx, ny, penetration))
+        collide(
+            &a_state.shape,
+            a_state.position,
+            a_state.rotation,
+            &b_state.shape,
+            b_state.position,
+            b_state.rotation,
+        )
     }
 
-    fn solve_velocity_contact(&mut self, a_id: NodeId, b_id: NodeId) -> bool {
-        let Some((nx, ny, _)) = self.collision_normal_penetration(a_id, b_id) else {
-            return false;
-        };
+    fn collision_normal_penetration(&self, a_id: NodeId, b_id: NodeId) -> Option<(f64, f64, f64)> {
+        let manifold = self.contact(a_id, b_id)?;
+        Some((manifold.normal.x, manifold.normal.y, manifold.depth()))
+    }
 
-        let Some(a_state) = self.bodies.get(&a_id).cloned() else {
-            return false;
+    /// Sequential impulses at each manifold point: a restitution impulse along the normal
+    /// while the point approaches, then friction bounded by the normal impulse so far.
+    fn solve_velocity_contact(&mut self, contact: &mut SolverContact) {
+        let (Some(a_state), Some(b_state)) =
+            (self.bodies.get(&contact.a), self.bodies.get(&contact.b))
+        else {
+            return;
         };
-        let Some(b_state) = self.bodies.get(&b_id).cloned() else {
-            return false;
-        };
-
-        let rvx = b_state.velocity.x - a_state.velocity.x;
-        let rvy = b_state.velocity.y - a_state.velocity.y;
-        let vel_along_normal = rvx * nx + rvy * ny;
-
-        if vel_along_normal >= 0.0 {
-            return true;
-        }
+        let e = ((a_state.restitution + b_state.restitution) * 0.5).clamp(0.0, 1.0);
+        let friction = (a_state.friction * b_state.friction).sqrt();
+        let normal = contact.manifold.normal;
+        let tangent = PhysicsVec2::new(-normal.y, normal.x);
+
+        for (index, point) in contact.manifold.points.iter().enumerate().take(2) {
+            let (Some(a_state), Some(b_state)) =
+                (self.bodies.get(&contact.a), self.bodies.get(&contact.b))
+            else {
+                return;
+            };
+            let ra = offset(point.point, a_state.position);
+            let rb = offset(point.point, b_state.position);
+
+            let vel_along_normal = self.relative_velocity(contact, ra, rb, normal);
+            let k_normal = self.effective_inverse_mass(contact, ra, rb, normal);
+            if vel_along_normal < 0.0 && k_normal > 0.0 {
+                let j = -(1.0 + e) * vel_along_normal / k_normal;
+                contact.normal_impulses[index] += j;
+                self.apply_contact_impulse(contact, ra, rb, normal, j);
+            }
 
-        let inv_mass_a = a_state.inverse_mass();
-        let inv_mass_b = b_state.inverse_mass();
-        let inv_mass_sum = inv_mass_a + inv_mass_b;
-        if inv_mass_sum <= 0.0 {
-            return true;
+            let vel_along_tangent = self.relative_velocity(contact, ra, rb, tangent);
+            let k_tangent = self.effective_inverse_mass(contact, ra, rb, tangent);
+            if k_tangent > 0.0 {
+                let limit = friction * contact.normal_impulses[index];
+                let previous = contact.tangent_impulses[index];
+                let total = (previous - vel_along_tangent / k_tangent).clamp(-limit, limit);
+                contact.tangent_impulses[index] = total;
+                self.apply_contact_impulse(contact, ra, rb, tangent, total - previous);
+            }
         }
+    }
 
-        let e = ((a_state.restitution + b_state.restitution) * 0.5).clamp(0.0, 1.0);
-        let j = -(1.0 + e) * vel_along_normal / inv_mass_sum;
-        let impulse_x = j * nx;
-        let impulse_y = j * ny;
+    /// Speed of `b` relative to `a` along `direction` at the contact offsets `ra` and `rb`.
+    fn relative_velocity(
+        &self,
+        contact: &SolverContact,
+        ra: PhysicsVec2,
+        rb: PhysicsVec2,
+        direction: PhysicsVec2,
+    ) -> f64 {
+        let a = self.bodies[&contact.a].point_velocity(ra);
+        let b = self.bodies[&contact.b].point_velocity(rb);
+        (b.x - a.x) * direction.x + (b.y - a.y) * direction.y
+    }
 
-        let mut a_new = a_state;
-        let mut b_new = b_state;
+    /// How much relative speed along `direction` one unit of impulse changes.
+    fn effective_inverse_mass(
+        &self,
+        contact: &SolverContact,
+        ra: PhysicsVec2,
+        rb: PhysicsVec2,
+        direction: PhysicsVec2,
+    ) -> f64 {
+        let a = &self.bodies[&contact.a];
+        let b = &self.bodies[&contact.b];
+        let ra_cross = cross(ra, direction);
+        let rb_cross = cross(rb, direction);
+        a.inverse_mass
+            + b.inverse_mass
+            + a.inverse_inertia * ra_cross * ra_cross
+            + b.inverse_inertia * rb_cross * rb_cross
+    }
 
-        if inv_mass_a > 0.0 {
-            a_new.velocity.x -= impulse_x * inv_mass_a;
-            a_new.velocity.y -= impulse_y * inv_mass_a;
-        }
-        if inv_mass_b > 0.0 {
-            b_new.velocity.x += impulse_x * inv_mass_b;
-            b_new.velocity.y += impulse_y * inv_mass_b;
+    /// Pushes `b` by `magnitude` along `direction` at `rb`, and `a` equally back at `ra`.
+    fn apply_contact_impulse(
+        &mut self,
+        contact: &SolverContact,
+        ra: PhysicsVec2,
+        rb: PhysicsVec2,
+        direction: PhysicsVec2,
+        magnitude: f64,
+    ) {
+        let impulse = PhysicsVec2::new(direction.x * magnitude, direction.y * magnitude);
+        for (id, r, sign) in [(contact.a, ra, -1.0), (contact.b, rb, 1.0)] {
+            if let Some(body) = self.bodies.get_mut(&id) {
+                body.velocity.x += sign * impulse.x * body.inverse_mass;
+                body.velocity.y += sign * impulse.y * body.inverse_mass;
+                body.angular_velocity += sign * cross(r, impulse) * body.inverse_inertia;
+            }
         }
-
-        self.bodies.insert(a_id, a_new);
-        self.bodies.insert(b_id, b_new);
-        true
     }
 
     fn solve_position_contact(&mut self, a_id: NodeId, b_id: NodeId) -> bool {
@@ -336,10 +624,10 @@ impl PhysicsWorld {
             return false;
         };
 
-        let Some(a_state) = self.bodies.get(&a_id).cloned() else {
+        let Some(a_state) = self.bodies.get(&a_id) else {
             return false;
         };
-        let Some(b_state) = self.bodies.get(&b_id).cloned() else {
+        let Some(b_state) = self.bodies.get(&b_id) else {
             return false;
         };
 
@@ -358,60 +646,48 @@ impl PhysicsWorld {
             return true;
         }
 
-        let mut a_new = a_state;
-        let mut b_new = b_state;
-
-        if inv_mass_a > 0.0 {
-            a_new.position.x -= correction * nx * inv_mass_a;
-            a_new.position.y -= correction * ny * inv_mass_a;
-        }
-        if inv_mass_b > 0.0 {
-            b_new.position.x += correction * nx * inv_mass_b;
-            b_new.position.y += correction * ny * inv_mass_b;
-        }
-
-        self.bodies.insert(a_id, a_new);
-        self.bodies.insert(b_id, b_new);
+        self.nudge_position(
+            a_id,
+            -correction * nx * inv_mass_a,
+            -correction * ny * inv_mass_a,
+        );
+        self.nudge_position(
+            b_id,
+            correction * nx * inv_mass_b,
+            correction * ny * inv_mass_b,
+        );
         true
     }
 
-    fn resolve_collision_legacy(&mut self, a_id: NodeId, b_id: NodeId) -> bool {
-        let Some((nx, ny, penetration)) = self.collision_normal_penetration(a_id, b_id) else {
-            return false;
-        };
+    /// Pushes the pair apart and bounces it in one pass; returns the impulse applied.
+    fn resolve_collision_legacy(
+        &mut self,
+        a_id: NodeId,
+        b_id: NodeId,
+        contact: &PairContact,
+    ) -> f64 {
+        let (nx, ny, penetration) = (contact.normal.x, contact.normal.y, contact.depth);
 
-        let Some(a_state) = self.bodies.get(&a_id).cloned() else {
-            return false;
+        let Some(a_state) = self.bodies.get(&a_id) else {
+            return 0.0;
         };
-        let Some(b_state) = self.bodies.get(&b_id).cloned() else {
-            return false;
+        let Some(b_state) = self.bodies.get(&b_id) else {
+            return 0.0;
         };
 
-        let mut a_new = a_state.clone();
-        let mut b_new = b_state.clone();
-
-        match (a_state.kind, b_state.kind) {
-            (PhysicsBodyKind::Dynamic, PhysicsBodyKind::Dynamic) => {
-                a_new.position.x -= nx * penetration * 0.5;
-                a_new.position.y -= ny * penetration * 0.5;
-                b_new.position.x += nx * penetration * 0.5;
-                b_new.position.y += ny * penetration * 0.5;
-            }
-            (PhysicsBodyKind::Dynamic, PhysicsBodyKind::Static) => {
-                a_new.position.x -= nx * penetration;
-                a_new.position.y -= ny * penetration;
-            }
-            (PhysicsBodyKind::Static, PhysicsBodyKind::Dynamic) => {
-                b_new.position.x += nx * penetration;
-                b_new.position.y += ny * penetration;
-            }
-            (PhysicsBodyKind::Static, PhysicsBodyKind::Static) => {}
-        }
+        let (push_a, push_b) = match (a_state.inverse_mass() > 0.0, b_state.inverse_mass() > 0.0) {
+            (true, true) => (0.5, 0.5),
+            (true, false) => (1.0, 0.0),
+            (false, true) => (0.0, 1.0),
+            (false, false) => (0.0, 0.0),
+        };
 
         let rvx = b_state.velocity.x - a_state.velocity.x;
         let rvy = b_state.velocity.y - a_state.velocity.y;
         let vel_along_normal = rvx * nx + rvy * ny;
 
+        let mut impulse = None;
+        let mut applied = 0.0;
         if vel_along_normal < 0.0 {
             let e = ((a_state.restitution + b_state.restitution) * 0.5).clamp(0.0, 1.0);
             let inv_mass_a = a_state.inverse_mass();
@@ -420,26 +696,44 @@ impl PhysicsWorld {
             let denom = inv_mass_a + inv_mass_b;
             if denom > 0.0 {
                 let j = -(1.0 + e) * vel_along_normal / denom;
-                let impulse_x = j * nx;
-                let impulse_y = j * ny;
-
-                if inv_mass_a > 0.0 {
-                    a_new.velocity.x -= impulse_x * inv_mass_a;
-                    a_new.velocity.y -= impulse_y * inv_mass_a;
-                }
-                if inv_mass_b > 0.0 {
-                    b_new.velocity.x += impulse_x * inv_mass_b;
-                    b_new.velocity.y += impulse_y * inv_mass_b;
-                }
+                impulse = Some((j * nx, j * ny, inv_mass_a, inv_mass_b));
+                applied = j;
             }
         }
 
-        self.bodies.insert(a_id, a_new);
-        self.bodies.insert(b_id, b_new);
-        true
+        self.nudge_position(a_id, -nx * penetration * push_a, -ny * penetration * push_a);
+        self.nudge_position(b_id, nx * penetration * push_b, ny * penetration * push_b);
+        if let Some((impulse_x, impulse_y, inv_mass_a, inv_mass_b)) = impulse {
+            self.nudge_velocity(a_id, -impulse_x * inv_mass_a, -impulse_y * inv_mass_a);
+            self.nudge_velocity(b_id, impulse_x * inv_mass_b, impulse_y * inv_mass_b);
+        }
+        applied
+    }
+
+    /// Adds to a dynamic body's velocity; static and kinematic bodies ignore contacts.
+    fn nudge_velocity(&mut self, id: NodeId, dx: f64, dy: f64) {
+        if let Some(body) = self.bodies.get_mut(&id).filter(|b| b.inverse_mass() > 0.0) {
+            body.velocity.x += dx;
+            body.velocity.y += dy;
+        }
+    }
+
+    fn nudge_position(&mut self, id: NodeId, dx: f64, dy: f64) {
+        if let Some(body) = self.bodies.get_mut(&id).filter(|b| b.inverse_mass() > 0.0) {
+            body.position.x += dx;
+            body.position.y += dy;
+        }
     }
 }
 
+fn offset(point: PhysicsVec2, origin: PhysicsVec2) -> PhysicsVec2 {
+    PhysicsVec2::new(point.x - origin.x, point.y - origin.y)
+}
+
+fn cross(a: PhysicsVec2, b: PhysicsVec2) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
 pub fn upsert_scene_body(
     scene: &mut SceneGraph,
     node: NodeId,
@@ -453,9 +747,10 @@ pub fn upsert_scene_body(
 
 #[cfg(test)]
 mod tests {
+    use crate::collision::CollisionShape;
     use crate::scene::{NodeId, PhysicsBody2D, PhysicsBodyKind, SceneGraph};
 
-    use super::{PhysicsBackend, PhysicsVec2, PhysicsWorld};
+    use super::{ContactPhase, PhysicsBackend, PhysicsVec2, PhysicsWorld};
 
     #[test]
     fn defaults_to_box2d_backend() {
@@ -481,10 +776,11 @@ mod tests {
                 a,
                 PhysicsBody2D {
                     kind: PhysicsBodyKind::Dynamic,
-                    radius: 8.0,
+                    shape: CollisionShape::Circle { radius: 8.0 },
                     velocity_x: 120.0,
                     velocity_y: 0.0,
                     restitution: 1.0,
+                    ..PhysicsBody2D::default()
                 },
             )
             .expect("a body");
@@ -493,10 +789,11 @@ mod tests {
                 b,
                 PhysicsBody2D {
                     kind: PhysicsBodyKind::Static,
-                    radius: 8.0,
+                    shape: CollisionShape::Circle { radius: 8.0 },
                     velocity_x: 0.0,
                     velocity_y: 0.0,
                     restitution: 1.0,
+                    ..PhysicsBody2D::default()
                 },
             )
             .expect("b body");
@@ -512,7 +809,214 @@ mod tests {
         let second = world.step(1.0 / 60.0);
 
         assert_eq!(first.len(), 1, "first contact should emit one event");
-        assert!(second.is_empty(), "persistent contact should not re-emit");
+        assert_eq!(first[0].phase, ContactPhase::Begin);
+        // `a` starts 6 units into `b`, bounces straight back and is pushed almost clear, so the
+        // event reports the small overlap left after position correction.
+        assert!(
+            (first[0].normal.x - 1.0).abs() < 1e-9 && first[0].normal.y == 0.0,
+            "{:?}",
+            first[0].normal
+        );
+        assert!(
+            (first[0].point.x - 2.02096).abs() < 1e-9,
+            "{:?}",
+            first[0].point
+        );
+        assert!(
+            (first[0].depth - 0.04192).abs() < 1e-9,
+            "{}",
+            first[0].depth
+        );
+        // Impulse is mass-weighted: a's mass comes from unit density over its disc.
+        let mass_a = std::f64::consts::PI * 64.0;
+        assert!(
+            (first[0].impulse - 240.0 * mass_a).abs() < 1e-6,
+            "{}",
+            first[0].impulse
+        );
+        assert!(
+            second
+                .iter()
+                .all(|event| event.phase != ContactPhase::Begin),
+            "persistent contact should not re-emit"
+        );
+    }
+
+    #[test]
+    fn contacts_begin_on_the_step_bodies_first_overlap() {
+        let mut scene = SceneGraph::new();
+        let root = scene.root();
+        let a = scene.add_node(root, "a").expect("node a");
+        let b = scene.add_node(root, "b").expect("node b");
+
+        scene
+            .set_node_transform(b, 31.0, 0.0, 0.0, 1.0, 1.0)
+            .expect("b transform");
+        for (node, kind, velocity_x) in [
+            (a, PhysicsBodyKind::Dynamic, 120.0),
+            (b, PhysicsBodyKind::Static, 0.0),
+        ] {
+            scene
+                .set_physics_body(
+                    node,
+                    PhysicsBody2D {
+                        kind,
+                        shape: CollisionShape::Circle { radius: 8.0 },
+                        velocity_x,
+                        ..PhysicsBody2D::default()
+                    },
+                )
+                .expect("body");
+        }
+
+        // `a` moves 2 units a step: 17 apart after step 7, 15 apart after step 8.
+        let mut world = PhysicsWorld::new();
+        let mut begun = None;
+        for step in 1..=10 {
+            world.sync_from_scene(&scene);
+            let events = world.step(1.0 / 60.0);
+            world.apply_to_scene(&mut scene);
+            if let Some(event) = events
+                .iter()
+                .find(|event| event.phase == ContactPhase::Begin)
+            {
+                begun = Some((step, event.clone()));
+                break;
+            }
+        }
+
+        let (step, event) = begun.expect("contact should begin");
+        assert_eq!(step, 8);
+        assert_eq!((event.a, event.b), (a, b));
+        assert!(event.depth > 0.0, "{}", event.depth);
+    }
+
+    #[test]
+    fn sensors_report_begin_and_end_and_masks_skip_pairs() {
+        let mut scene = SceneGraph::new();
+        let root = scene.root();
+        let zone = scene.add_node(root, "zone").expect("zone node");
+        let wall = scene.add_node(root, "wall").expect("wall node");
+        let player = scene.add_node(root, "player").expect("player node");
+
+        scene
+            .set_node_transform(zone, 100.0, 0.0, 0.0, 1.0, 1.0)
+            .expect("zone transform");
+        scene
+            .set_node_transform(wall, 200.0, 0.0, 0.0, 1.0, 1.0)
+            .expect("wall transform");
+        scene
+            .set_node_transform(player, 40.0, 0.0, 0.0, 1.0, 1.0)
+            .expect("player transform");
+        scene
+            .set_physics_body(
+                zone,
+                PhysicsBody2D {
+                    kind: PhysicsBodyKind::Static,
+                    shape: CollisionShape::Circle { radius: 20.0 },
+                    sensor: true,
+                    ..PhysicsBody2D::default()
+                },
+            )
+            .expect("zone body");
+        scene
+            .set_physics_body(
+                wall,
+                PhysicsBody2D {
+                    kind: PhysicsBodyKind::Static,
+                    shape: CollisionShape::Box {
+                        half_width: 5.0,
+                        half_height: 50.0,
+                    },
+                    category: 0b10,
+                    ..PhysicsBody2D::default()
+                },
+            )
+            .expect("wall body");
+        scene
+            .set_physics_body(
+                player,
+                PhysicsBody2D {
+                    kind: PhysicsBodyKind::Dynamic,
+                    shape: CollisionShape::Circle { radius: 5.0 },
+                    velocity_x: 300.0,
+                    mask: 0b01,
+                    ..PhysicsBody2D::default()
+                },
+            )
+            .expect("player body");
+
+        let mut world = PhysicsWorld::new();
+        let mut events = Vec::new();
+        for _ in 0..60 {
+            world.sync_from_scene(&scene);
+            events.extend(world.step(1.0 / 60.0));
+            world.apply_to_scene(&mut scene);
+        }
+
+        let phases = events
+            .iter()
+            .map(|event| (event.a, event.b, event.phase, event.sensor))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            phases,
+            vec![
+                (zone, player, ContactPhase::Begin, true),
+                (zone, player, ContactPhase::End, true),
+            ]
+        );
+
+        // Neither the sensor nor the masked-out wall slowed the player down.
+        let node = scene.node(player).expect("player exists");
+        assert!(
+            (node.transform.x - 340.0).abs() < 1e-6,
+            "{}",
+            node.transform.x
+        );
+        assert_eq!(node.physics_body.as_ref().expect("body").velocity_x, 300.0);
+    }
+
+    #[test]
+    fn removing_a_body_ends_its_contacts_on_the_next_step() {
+        let mut scene = SceneGraph::new();
+        let root = scene.root();
+        let zone = scene.add_node(root, "zone").expect("zone node");
+        let coin = scene.add_node(root, "coin").expect("coin node");
+
+        scene
+            .set_node_transform(coin, 5.0, 0.0, 0.0, 1.0, 1.0)
+            .expect("coin transform");
+        for (node, sensor) in [(zone, true), (coin, false)] {
+            scene
+                .set_physics_body(
+                    node,
+                    PhysicsBody2D {
+                        kind: PhysicsBodyKind::Static,
+                        shape: CollisionShape::Circle { radius: 8.0 },
+                        sensor,
+                        ..PhysicsBody2D::default()
+                    },
+                )
+                .expect("body");
+        }
+
+        let mut world = PhysicsWorld::new();
+        world.sync_from_scene(&scene);
+        let begun = world.step(1.0 / 60.0);
+        assert_eq!(begun.len(), 1);
+        assert_eq!(begun[0].phase, ContactPhase::Begin);
+
+        scene.clear_physics_body(coin).expect("coin cleared");
+        world.sync_from_scene(&scene);
+        let ended = world.step(1.0 / 60.0);
+        let phases = ended
+            .iter()
+            .map(|event| (event.a, event.b, event.phase, event.sensor))
+            .collect::<Vec<_>>();
+        assert_eq!(phases, vec![(zone, coin, ContactPhase::End, true)]);
+
+        world.sync_from_scene(&scene);
+        assert!(world.step(1.0 / 60.0).is_empty());
     }
 
     #[test]
@@ -534,10 +1038,11 @@ mod tests {
                     a,
                     PhysicsBody2D {
                         kind: PhysicsBodyKind::Dynamic,
-                        radius: 8.0,
+                        shape: CollisionShape::Circle { radius: 8.0 },
                         velocity_x: 180.0,
                         velocity_y: 0.0,
                         restitution: 0.8,
+                        ..PhysicsBody2D::default()
                     },
                 )
                 .expect("a body");
@@ -546,10 +1051,11 @@ mod tests {
                     b,
                     PhysicsBody2D {
                         kind: PhysicsBodyKind::Static,
-                        radius: 8.0,
+                        shape: CollisionShape::Circle { radius: 8.0 },
                         velocity_x: 0.0,
                         velocity_y: 0.0,
                         restitution: 0.8,
+                        ..PhysicsBody2D::default()
                     },
                 )
                 .expect("b body");
@@ -570,4 +1076,304 @@ mod tests {
         assert_eq!(run(PhysicsBackend::Legacy), run(PhysicsBackend::Legacy));
         assert_eq!(run(PhysicsBackend::Box2d), run(PhysicsBackend::Box2d));
     }
+
+    #[test]
+    fn falling_box_settles_on_static_ground() {
+        let mut scene = SceneGraph::new();
+        let root = scene.root();
+        let crate_node = scene.add_node(root, "crate").expect("crate node");
+        let ground = scene.add_node(root, "ground").expect("ground node");
+        scene
+            .set_node_transform(crate_node, 0.0, 0.0, 0.0, 1.0, 1.0)
+            .expect("crate transform");
+        scene
+            .set_node_transform(ground, 0.0, 60.0, 0.0, 1.0, 1.0)
+            .expect("ground transform");
+        scene
+            .set_physics_body(
+                crate_node,
+                PhysicsBody2D {
+                    kind: PhysicsBodyKind::Dynamic,
+                    shape: CollisionShape::Box {
+                        half_width: 10.0,
+                        half_height: 10.0,
+                    },
+                    velocity_x: 0.0,
+                    velocity_y: 0.0,
+                    restitution: 0.0,
+                    ..PhysicsBody2D::default()
+                },
+            )
+            .expect("crate body");
+        scene
+            .set_physics_body(
+                ground,
+                PhysicsBody2D {
+                    kind: PhysicsBodyKind::Static,
+                    shape: CollisionShape::Box {
+                        half_width: 100.0,
+                        half_height: 10.0,
+                    },
+                    velocity_x: 0.0,
+                    velocity_y: 0.0,
+                    restitution: 0.0,
+                    ..PhysicsBody2D::default()
+                },
+            )
+            .expect("ground body");
+
+        let mut world = PhysicsWorld::new();
+        world.set_gravity(PhysicsVec2::new(0.0, 600.0));
+        for _ in 0..120 {
+            world.sync_from_scene(&scene);
+            let _ = world.step(1.0 / 60.0);
+            world.apply_to_scene(&mut scene);
+        }
+
+        // The ground's top is at y = 50, so the crate rests with its centre near y = 40.
+        let node = scene.node(crate_node).expect("crate exists");
+        assert!(
+            (node.transform.y - 40.0).abs() < 0.5,
+            "{}",
+            node.transform.y
+        );
+        // Contact points are solved one after another, which leaves only a trace of spin.
+        assert!(node.transform.x.abs() < 1e-3, "{}", node.transform.x);
+        assert!(
+            node.transform.rotation.abs() < 1e-3,
+            "{}",
+            node.transform.rotation
+        );
+    }
+
+    #[test]
+    fn broad_phase_keeps_every_touching_pair() {
+        let mut scene = SceneGraph::new();
+        let root = scene.root();
+        for i in 0..60 {
+            let node = scene.add_node(root, format!("b{i}")).expect("node");
+            // Scatter bodies so some overlap, some touch exactly and most are far apart.
+            let x = ((i * 37) % 23) as f64 * 9.0;
+            let y = ((i * 11) % 7) as f64 * 12.0;
+            scene
+                .set_node_transform(node, x, y, 0.3 * i as f64, 1.0, 1.0)
+                .expect("transform");
+            let shape = if i % 2 == 0 {
+                CollisionShape::Circle { radius: 6.0 }
+            } else {
+                CollisionShape::Box {
+                    half_width: 8.0,
+                    half_height: 3.0,
+                }
+            };
+            scene
+                .set_physics_body(
+                    node,
+                    PhysicsBody2D {
+                        kind: PhysicsBodyKind::Static,
+                        shape,
+                        velocity_x: 0.0,
+                        velocity_y: 0.0,
+                        restitution: 0.0,
+                        ..PhysicsBody2D::default()
+                    },
+                )
+                .expect("body");
+        }
+
+        let mut world = PhysicsWorld::new();
+        world.sync_from_scene(&scene);
+
+        let mut ids = world.bodies.keys().copied().collect::<Vec<_>>();
+        ids.sort_by_key(|id| id.0);
+        let mut expected = Vec::new();
+        for (i, a) in ids.iter().enumerate() {
+            for b in &ids[i + 1..] {
+                if world.contact(*a, *b).is_some() {
+                    expected.push((*a, *b));
+                }
+            }
+        }
+        let found = world
+            .candidate_pairs()
+            .into_iter()
+            .filter(|(a, b)| world.contact(*a, *b).is_some())
+            .collect::<Vec<_>>();
+
+        assert!(!expected.is_empty());
+        assert_eq!(found, expected);
+        assert!(world.candidate_pairs().len() < ids.len() * (ids.len() - 1) / 2);
+
+        // Spans follow each shape's rotated bounds: a flat plank reaches far sideways but not
+        // down to the ball below it, until it is stood on end.
+        let mut scene = SceneGraph::new();
+        let plank = place(
+            &mut scene,
+            "plank",
+            0.0,
+            0.0,
+            PhysicsBody2D {
+                shape: CollisionShape::Box {
+                    half_width: 50.0,
+                    half_height: 2.0,
+                },
+                ..PhysicsBody2D::default()
+            },
+        );
+        let ball = place(
+            &mut scene,
+            "ball",
+            0.0,
+            30.0,
+            PhysicsBody2D {
+                shape: CollisionShape::Circle { radius: 2.0 },
+                ..PhysicsBody2D::default()
+            },
+        );
+        let mut world = PhysicsWorld::new();
+        world.sync_from_scene(&scene);
+        assert!(world.candidate_pairs().is_empty());
+        scene
+            .set_node_transform(plank, 0.0, 0.0, std::f64::consts::FRAC_PI_2, 1.0, 1.0)
+            .expect("transform");
+        world.sync_from_scene(&scene);
+        assert_eq!(world.candidate_pairs(), vec![(plank, ball)]);
+    }
+
+    fn place(scene: &mut SceneGraph, name: &str, x: f64, y: f64, body: PhysicsBody2D) -> NodeId {
+        let node = scene.add_node(scene.root(), name).expect("node");
+        scene
+            .set_node_transform(node, x, y, 0.0, 1.0, 1.0)
+            .expect("transform");
+        scene.set_physics_body(node, body).expect("body");
+        node
+    }
+
+    fn run_steps(world: &mut PhysicsWorld, scene: &mut SceneGraph, steps: usize) {
+        for _ in 0..steps {
+            world.sync_from_scene(scene);
+            let _ = world.step(1.0 / 60.0);
+            world.apply_to_scene(scene);
+        }
+    }
+
+    #[test]
+    fn kinematic_platform_carries_a_resting_box_by_friction() {
+        let mut scene = SceneGraph::new();
+        let platform = place(
+            &mut scene,
+            "platform",
+            0.0,
+            50.0,
+            PhysicsBody2D {
+                kind: PhysicsBodyKind::Kinematic,
+                shape: CollisionShape::Box {
+                    half_width: 50.0,
+                    half_height: 5.0,
+                },
+                velocity_x: 30.0,
+                friction: 0.5,
+                ..PhysicsBody2D::default()
+            },
+        );
+        let cargo = place(
+            &mut scene,
+            "cargo",
+            0.0,
+            35.0,
+            PhysicsBody2D {
+                shape: CollisionShape::Box {
+                    half_width: 10.0,
+                    half_height: 10.0,
+                },
+                restitution: 0.0,
+                friction: 0.5,
+                ..PhysicsBody2D::default()
+            },
+        );
+
+        let mut world = PhysicsWorld::new();
+        world.set_gravity(PhysicsVec2::new(0.0, 600.0));
+        run_steps(&mut world, &mut scene, 60);
+
+        // The platform ignores gravity and the box's weight and keeps its own velocity.
+        let platform = scene.node(platform).expect("platform exists");
+        assert!((platform.transform.x - 30.0).abs() < 1e-9);
+        assert_eq!(platform.transform.y, 50.0);
+
+        // Friction drags the box along once it catches up with the platform.
+        let cargo = scene.node(cargo).expect("cargo exists");
+        assert!(
+            (25.0..=30.5).contains(&cargo.transform.x),
+            "{}",
+            cargo.transform.x
+        );
+        assert!(
+            (cargo.transform.y - 35.0).abs() < 0.5,
+            "{}",
+            cargo.transform.y
+        );
+    }
+
+    #[test]
+    fn mass_splits_impulses_and_damping_slows_bodies() {
+        let circle = CollisionShape::Circle { radius: 8.0 };
+        let mut scene = SceneGraph::new();
+        let heavy = place(
+            &mut scene,
+            "heavy",
+            0.0,
+            0.0,
+            PhysicsBody2D {
+                shape: circle.clone(),
+                velocity_x: 10.0,
+                restitution: 0.0,
+                mass: Some(3.0),
+                ..PhysicsBody2D::default()
+            },
+        );
+        let light = place(
+            &mut scene,
+            "light",
+            15.0,
+            0.0,
+            PhysicsBody2D {
+                shape: circle.clone(),
+                velocity_x: -10.0,
+                restitution: 0.0,
+                mass: Some(1.0),
+                ..PhysicsBody2D::default()
+            },
+        );
+        let drifter = place(
+            &mut scene,
+            "drifter",
+            0.0,
+            500.0,
+            PhysicsBody2D {
+                shape: circle.clone(),
+                velocity_x: 100.0,
+                angular_velocity: 2.0,
+                linear_damping: 1.0,
+                ..PhysicsBody2D::default()
+            },
+        );
+
+        let mut world = PhysicsWorld::new();
+        run_steps(&mut world, &mut scene, 1);
+
+        // A perfectly inelastic hit keeps the pair's momentum: 3 * 10 - 1 * 10 = 4 * 5.
+        let velocity = |scene: &SceneGraph, id| {
+            let body = scene.node(id).expect("node").physics_body.clone();
+            body.expect("body").velocity_x
+        };
+        assert!((velocity(&scene, heavy) - 5.0).abs() < 1e-9);
+        assert!((velocity(&scene, light) - 5.0).abs() < 1e-9);
+
+        run_steps(&mut world, &mut scene, 59);
+        let expected = 100.0 * (1.0 / (1.0 + 1.0 / 60.0_f64)).powi(60);
+        assert!((velocity(&scene, drifter) - expected).abs() < 1e-9);
+        let node = scene.node(drifter).expect("drifter exists");
+        assert!((node.transform.rotation - 2.0).abs() < 1e-9);
+    }
 }
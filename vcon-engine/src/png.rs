@@ -0,0 +1,703 @@
+//! Dependency-free PNG decoding (and a minimal encoder) for cartridge textures.
+//!
+//! Supports every non-interlaced colour type at the bit depths PNG allows, `tRNS`
+//! transparency, and verifies chunk CRCs and the zlib Adler-32 checksum. Output is always
+//! 8-bit RGBA; 16-bit samples keep their high byte.
+
+use crate::render::Texture;
+
+const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const MAX_DIMENSION: u32 = 16_384;
+
+pub fn decode_png(bytes: &[u8]) -> Result<Texture, String> {
+    if !bytes.starts_with(SIGNATURE) {
+        return Err("missing png signature".to_owned());
+    }
+
+    let mut cursor = SIGNATURE.len();
+    let mut header: Option<Header> = None;
+    let mut palette: Vec<[u8; 3]> = Vec::new();
+    let mut transparency: Option<Vec<u8>> = None;
+    let mut compressed = Vec::new();
+    let mut ended = false;
+
+    while cursor < bytes.len() {
+        let (kind, data) = read_chunk(bytes, &mut cursor)?;
+        match &kind {
+            b"IHDR" => header = Some(Header::parse(data)?),
+            b"PLTE" => {
+                if data.len() % 3 != 0 || data.len() > 256 * 3 {
+                    return Err("invalid PLTE chunk length".to_owned());
+                }
+                palette = data.chunks_exact(3).map(|c| [c[0], c[1], c[2]]).collect();
+            }
+            b"tRNS" => transparency = Some(data.to_vec()),
+            b"IDAT" => compressed.extend_from_slice(data),
+            b"IEND" => {
+                ended = true;
+                break;
+            }
+            _ => {
+                // Bit 5 of the first byte marks ancillary chunks, which are safe to skip.
+                if kind[0] & 0x20 == 0 {
+                    return Err(format!(
+                        "unsupported critical chunk `{}`",
+                        String::from_utf8_lossy(&kind)
+                    ));
+                }
+            }
+        }
+    }
+
+    let header = header.ok_or("missing IHDR chunk")?;
+    if !ended {
+        return Err("missing IEND chunk".to_owned());
+    }
+    if header.color_type == 3 && palette.is_empty() {
+        return Err("indexed png is missing its PLTE chunk".to_owned());
+    }
+
+    let stride = header.stride();
+    let expected = (stride + 1) * header.height as usize;
+    let raw = zlib_decompress(&compressed, expected)?;
+    if raw.len() < expected {
+        return Err("png image data is truncated".to_owned());
+    }
+    let rows = unfilter(&raw[..expected], stride, header.filter_bytes())?;
+
+    let mut rgba = Vec::with_capacity(header.width as usize * header.height as usize * 4);
+    for row in rows.chunks_exact(stride) {
+        for x in 0..header.width as usize {
+            rgba.extend_from_slice(&header.pixel(row, x, &palette, transparency.as_deref())?);
+        }
+    }
+
+    Ok(Texture {
+        width: header.width,
+        height: header.height,
+        pixels: rgba,
+    })
+}
+
+fn read_chunk<'a>(bytes: &'a [u8], cursor: &mut usize) -> Result<([u8; 4], &'a [u8]), String> {
+    let start = *cursor;
+    if bytes.len() < start + 8 {
+        return Err("truncated chunk header".to_owned());
+    }
+    let len = u32::from_be_bytes([
+        bytes[start],
+        bytes[start + 1],
+        bytes[start + 2],
+        bytes[start + 3],
+    ]) as usize;
+    let kind = [
+        bytes[start + 4],
+        bytes[start + 5],
+        bytes[start + 6],
+        bytes[start + 7],
+    ];
+    let data_end = start
+        .checked_add(8 + len)
+        .filter(|end| end + 4 <= bytes.len())
+        .ok_or("truncated chunk data")?;
+    let data = &bytes[start + 8..data_end];
+    let stored = u32::from_be_bytes([
+        bytes[data_end],
+        bytes[data_end + 1],
+        bytes[data_end + 2],
+        bytes[data_end + 3],
+    ]);
+    if crc32(&bytes[start + 4..data_end]) != stored {
+        return Err(format!(
+            "crc mismatch in `{}` chunk",
+            String::from_utf8_lossy(&kind)
+        ));
+    }
+    *cursor = data_end + 4;
+    Ok((kind, data))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Header {
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    color_type: u8,
+}
+
+impl Header {
+    fn parse(data: &[u8]) -> Result<Self, String> {
+        if data.len() != 13 {
+            return Err("invalid IHDR chunk length".to_owned());
+        }
+        let width = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+        let height = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+        let header = Self {
+            width,
+            height,
+            bit_depth: data[8],
+            color_type: data[9],
+        };
+        if width == 0 || height == 0 || width > MAX_DIMENSION || height > MAX_DIMENSION {
+            return Err(format!("unsupported png dimensions {width}x{height}"));
+        }
+        let depth_ok = match header.color_type {
+            0 => matches!(header.bit_depth, 1 | 2 | 4 | 8 | 16),
+            3 => matches!(header.bit_depth, 1 | 2 | 4 | 8),
+            2 | 4 | 6 => matches!(header.bit_depth, 8 | 16),
+            other => return Err(format!("invalid png color type {other}")),
+        };
+        if !depth_ok {
+            return Err(format!(
+                "invalid bit depth {} for color type {}",
+                header.bit_depth, header.color_type
+            ));
+        }
+        if data[10] != 0 || data[11] != 0 {
+            return Err("unsupported png compression or filter method".to_owned());
+        }
+        if data[12] != 0 {
+            return Err("interlaced png is not supported".to_owned());
+        }
+        Ok(header)
+    }
+
+    fn channels(&self) -> usize {
+        match self.color_type {
+            2 => 3,
+            4 => 2,
+            6 => 4,
+            _ => 1,
+        }
+    }
+
+    fn bits_per_pixel(&self) -> usize {
+        self.channels() * self.bit_depth as usize
+    }
+
+    fn stride(&self) -> usize {
+        (self.width as usize * self.bits_per_pixel()).div_ceil(8)
+    }
+
+    fn filter_bytes(&self) -> usize {
+        self.bits_per_pixel().div_ceil(8)
+    }
+
+    /// Sample `channel` of pixel `x`, still at the image's bit depth.
+    fn sample(&self, row: &[u8], x: usize, channel: usize) -> u16 {
+        let depth = self.bit_depth as usize;
+        let index = x * self.channels() + channel;
+        match depth {
+            16 => u16::from_be_bytes([row[index * 2], row[index * 2 + 1]]),
+            8 => row[index] as u16,
+            _ => {
+                let bit = index * depth;
+                let shift = 8 - depth - (bit % 8);
+                ((row[bit / 8] >> shift) & ((1 << depth) - 1) as u8) as u16
+            }
+        }
+    }
+
+    /// Scales a sample to 8 bits: low depths are replicated, 16-bit keeps the high byte.
+    fn scale_sample(&self, sample: u16) -> u8 {
+        match self.bit_depth {
+            16 => (sample >> 8) as u8,
+            8 => sample as u8,
+            depth => (sample as u32 * 255 / ((1u32 << depth) - 1)) as u8,
+        }
+    }
+
+    fn pixel(
+        &self,
+        row: &[u8],
+        x: usize,
+        palette: &[[u8; 3]],
+        transparency: Option<&[u8]>,
+    ) -> Result<[u8; 4], String> {
+        let sample = |c| self.sample(row, x, c);
+        let key_matches = |values: &[u16]| {
+            transparency.is_some_and(|t| {
+                t.len() == values.len() * 2
+                    && values
+                        .iter()
+                        .enumerate()
+                        .all(|(i, v)| u16::from_be_bytes([t[i * 2], t[i * 2 + 1]]) == *v)
+            })
+        };
+
+        Ok(match self.color_type {
+            0 => {
+                let gray = sample(0);
+                let alpha = if key_matches(&[gray]) { 0 } else { 255 };
+                let g = self.scale_sample(gray);
+                [g, g, g, alpha]
+            }
+            2 => {
+                let (r, g, b) = (sample(0), sample(1), sample(2));
+                let alpha = if key_matches(&[r, g, b]) { 0 } else { 255 };
+                [
+                    self.scale_sample(r),
+                    self.scale_sample(g),
+                    self.scale_sample(b),
+                    alpha,
+                ]
+            }
+            3 => {
+                let index = sample(0) as usize;
+                let [r, g, b] = *palette
+                    .get(index)
+                    .ok_or_else(|| format!("palette index {index} out of range"))?;
+                let alpha = transparency
+                    .and_then(|t| t.get(index).copied())
+                    .unwrap_or(255);
+                [r, g, b, alpha]
+            }
+            4 => {
+                let g = self.scale_sample(sample(0));
+                [g, g, g, self.scale_sample(sample(1))]
+            }
+            _ => [
+                self.scale_sample(sample(0)),
+                self.scale_sample(sample(1)),
+                self.scale_sample(sample(2)),
+                self.scale_sample(sample(3)),
+            ],
+        })
+    }
+}
+
+fn unfilter(raw: &[u8], stride: usize, bpp: usize) -> Result<Vec<u8>, String> {
+    let rows = raw.len() / (stride + 1);
+    let mut out = vec![0u8; rows * stride];
+    for y in 0..rows {
+        let filter = raw[y * (stride + 1)];
+        let src = &raw[y * (stride + 1) + 1..(y + 1) * (stride + 1)];
+        let (done, rest) = out.split_at_mut(y * stride);
+        let prior = if y == 0 {
+            None
+        } else {
+            Some(&done[(y - 1) * stride..])
+        };
+        let line = &mut rest[..stride];
+        for i in 0..stride {
+            let a = if i >= bpp { line[i - bpp] } else { 0 };
+            let b = prior.map_or(0, |p| p[i]);
+            let c = if i >= bpp {
+                prior.map_or(0, |p| p[i - bpp])
+            } else {
+                0
+            };
+            let predicted = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                4 => paeth(a, b, c),
+                other => return Err(format!("invalid png filter type {other}")),
+            };
+            line[i] = src[i].wrapping_add(predicted);
+        }
+    }
+    Ok(out)
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let pa = (p - a as i16).abs();
+    let pb = (p - b as i16).abs();
+    let pc = (p - c as i16).abs();
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in bytes.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= 65_521;
+        b %= 65_521;
+    }
+    (b << 16) | a
+}
+
+/// Inflates a zlib stream, failing as soon as the output would exceed `limit` bytes.
+fn zlib_decompress(data: &[u8], limit: usize) -> Result<Vec<u8>, String> {
+    if data.len() < 6 {
+        return Err("zlib stream is truncated".to_owned());
+    }
+    let (cmf, flg) = (data[0], data[1]);
+    if cmf & 0x0f != 8 || !((cmf as u16) << 8 | flg as u16).is_multiple_of(31) {
+        return Err("invalid zlib header".to_owned());
+    }
+    if flg & 0x20 != 0 {
+        return Err("zlib preset dictionaries are not supported".to_owned());
+    }
+
+    let mut reader = BitReader::new(&data[2..]);
+    let out = inflate(&mut reader, limit)?;
+    let tail = reader.byte_aligned_position() + 2;
+    let checksum = data
+        .get(tail..tail + 4)
+        .ok_or("zlib stream is missing its checksum")?;
+    if u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]) != adler32(&out) {
+        return Err("zlib checksum mismatch".to_owned());
+    }
+    Ok(out)
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            bit: 0,
+        }
+    }
+
+    fn bits(&mut self, count: u32) -> Result<u32, String> {
+        let mut value = 0;
+        for i in 0..count {
+            let byte = *self
+                .data
+                .get(self.pos)
+                .ok_or("deflate stream is truncated")?;
+            value |= (((byte >> self.bit) & 1) as u32) << i;
+            self.bit += 1;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.pos += 1;
+            }
+        }
+        Ok(value)
+    }
+
+    fn align(&mut self) {
+        if self.bit != 0 {
+            self.bit = 0;
+            self.pos += 1;
+        }
+    }
+
+    fn byte_aligned_position(&self) -> usize {
+        self.pos + usize::from(self.bit != 0)
+    }
+}
+
+/// Canonical Huffman table in the counts/symbols form used by zlib's `puff`.
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Self, String> {
+        let mut counts = [0u16; 16];
+        for len in lengths {
+            counts[*len as usize] += 1;
+        }
+        let mut next = [0u16; 16];
+        let mut total = 0;
+        for len in 1..16 {
+            next[len] = total;
+            total += counts[len];
+        }
+        let mut symbols = vec![0u16; total as usize];
+        for (symbol, len) in lengths.iter().enumerate() {
+            if *len != 0 {
+                symbols[next[*len as usize] as usize] = symbol as u16;
+                next[*len as usize] += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, reader: &mut BitReader<'_>) -> Result<u16, String> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for len in 1..16 {
+            code |= reader.bits(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("invalid huffman code in deflate stream".to_owned())
+    }
+}
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+const INFLATE_OVERFLOW: &str = "deflate stream is longer than the image data";
+
+fn inflate(reader: &mut BitReader<'_>, limit: usize) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(limit);
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                reader.align();
+                let start = reader.pos;
+                let header = reader
+                    .data
+                    .get(start..start + 4)
+                    .ok_or("stored deflate block is truncated")?;
+                let len = u16::from_le_bytes([header[0], header[1]]);
+                let nlen = u16::from_le_bytes([header[2], header[3]]);
+                if len != !nlen {
+                    return Err("stored deflate block length mismatch".to_owned());
+                }
+                let body = reader
+                    .data
+                    .get(start + 4..start + 4 + len as usize)
+                    .ok_or("stored deflate block is truncated")?;
+                if out.len() + body.len() > limit {
+                    return Err(INFLATE_OVERFLOW.to_owned());
+                }
+                out.extend_from_slice(body);
+                reader.pos = start + 4 + len as usize;
+            }
+            1 => {
+                let mut lengths = [0u8; 288];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+                let literals = Huffman::new(&lengths)?;
+                let distances = Huffman::new(&[5u8; 30])?;
+                inflate_block(reader, &mut out, limit, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = read_dynamic_tables(reader)?;
+                inflate_block(reader, &mut out, limit, &literals, &distances)?;
+            }
+            _ => return Err("invalid deflate block type".to_owned()),
+        }
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+fn read_dynamic_tables(reader: &mut BitReader<'_>) -> Result<(Huffman, Huffman), String> {
+    let literal_count = reader.bits(5)? as usize + 257;
+    let distance_count = reader.bits(5)? as usize + 1;
+    let code_count = reader.bits(4)? as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for slot in CODE_LENGTH_ORDER.iter().take(code_count) {
+        code_lengths[*slot] = reader.bits(3)? as u8;
+    }
+    let code_table = Huffman::new(&code_lengths)?;
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let symbol = code_table.decode(reader)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *lengths
+                    .last()
+                    .ok_or("repeat code with no previous length")?;
+                (previous, 3 + reader.bits(2)? as usize)
+            }
+            17 => (0, 3 + reader.bits(3)? as usize),
+            18 => (0, 11 + reader.bits(7)? as usize),
+            _ => return Err("invalid code length symbol".to_owned()),
+        };
+        if lengths.len() + repeat > literal_count + distance_count {
+            return Err("code length repeat overflows table".to_owned());
+        }
+        lengths.extend(std::iter::repeat_n(value, repeat));
+    }
+
+    Ok((
+        Huffman::new(&lengths[..literal_count])?,
+        Huffman::new(&lengths[literal_count..])?,
+    ))
+}
+
+fn inflate_block(
+    reader: &mut BitReader<'_>,
+    out: &mut Vec<u8>,
+    limit: usize,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result<(), String> {
+    loop {
+        let symbol = literals.decode(reader)? as usize;
+        match symbol {
+            0..=255 if out.len() >= limit => return Err(INFLATE_OVERFLOW.to_owned()),
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            257..=285 => {
+                let i = symbol - 257;
+                let length =
+                    LENGTH_BASE[i] as usize + reader.bits(LENGTH_EXTRA[i] as u32)? as usize;
+                let d = distances.decode(reader)? as usize;
+                if d >= DIST_BASE.len() {
+                    return Err("invalid deflate distance symbol".to_owned());
+                }
+                let distance = DIST_BASE[d] as usize + reader.bits(DIST_EXTRA[d] as u32)? as usize;
+                if distance > out.len() {
+                    return Err("deflate distance reaches before start of output".to_owned());
+                }
+                if out.len() + length > limit {
+                    return Err(INFLATE_OVERFLOW.to_owned());
+                }
+                let start = out.len() - distance;
+                for k in 0..length {
+                    out.push(out[start + k]);
+                }
+            }
+            _ => return Err("invalid deflate literal/length symbol".to_owned()),
+        }
+    }
+}
+
+/// Encodes 8-bit RGBA pixels as a PNG using stored (uncompressed) deflate blocks.
+pub fn encode_png_rgba(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = out.len();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(data);
+        let crc = crc32(&out[start..]);
+        out.extend_from_slice(&crc.to_be_bytes());
+    }
+
+    let mut raw = Vec::with_capacity(rgba.len() + height as usize);
+    for row in rgba.chunks_exact(width as usize * 4) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = raw.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[0x01, 0x00, 0x00, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        zlib.push(u8::from(blocks.peek().is_none()));
+        zlib.extend_from_slice(&(block.len() as u16).to_le_bytes());
+        zlib.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&width.to_be_bytes());
+    ihdr.extend_from_slice(&height.to_be_bytes());
+    ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut out = SIGNATURE.to_vec();
+    chunk(&mut out, b"IHDR", &ihdr);
+    chunk(&mut out, b"IDAT", &zlib);
+    chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_png, encode_png_rgba};
+
+    #[test]
+    fn decodes_rgba_png_with_alpha() {
+        let png = encode_png_rgba(2, 1, &[255, 0, 0, 255, 0, 0, 255, 128]);
+        let texture = decode_png(&png).expect("rgba png should decode");
+        assert_eq!((texture.width, texture.height), (2, 1));
+        assert_eq!(texture.pixels, vec![255, 0, 0, 255, 0, 0, 255, 128]);
+    }
+
+    #[test]
+    fn decodes_fixed_huffman_stream_with_back_references() {
+        // zlib.compress(bytes([0, 10, 20, 30] * 4), 9)
+        let zlib = [
+            0x78, 0xda, 0x63, 0xe0, 0x12, 0x91, 0x63, 0x40, 0xc2, 0x00, 0x07, 0x40, 0x00, 0xf1,
+        ];
+        let out = super::zlib_decompress(&zlib, 16).expect("fixed block should inflate");
+        assert_eq!(out, [0, 10, 20, 30].repeat(4));
+    }
+
+    #[test]
+    fn stops_inflating_past_the_expected_length() {
+        // zlib.compress(bytes([0, 10, 20, 30] * 4), 9)
+        let zlib = [
+            0x78, 0xda, 0x63, 0xe0, 0x12, 0x91, 0x63, 0x40, 0xc2, 0x00, 0x07, 0x40, 0x00, 0xf1,
+        ];
+        let err = super::zlib_decompress(&zlib, 15).unwrap_err();
+        assert!(err.contains("longer than the image data"), "{err}");
+
+        let rgba = [7u8; 4 * 4 * 4];
+        let mut png = encode_png_rgba(4, 4, &rgba);
+        // Shrink the IHDR height so the stored image data overruns it.
+        png[16 + 7] = 2;
+        let crc = super::crc32(&png[12..29]);
+        png[29..33].copy_from_slice(&crc.to_be_bytes());
+        assert!(decode_png(&png)
+            .unwrap_err()
+            .contains("longer than the image data"));
+    }
+
+    #[test]
+    fn round_trips_images_spanning_several_stored_blocks() {
+        let rgba: Vec<u8> = (0..200 * 100 * 4).map(|i| (i % 251) as u8).collect();
+        let texture = decode_png(&encode_png_rgba(200, 100, &rgba)).expect("png should decode");
+        assert_eq!((texture.width, texture.height), (200, 100));
+        assert_eq!(texture.pixels, rgba);
+    }
+
+    #[test]
+    fn rejects_corrupt_crc() {
+        let mut png = encode_png_rgba(1, 1, &[1, 2, 3, 255]);
+        let last = png.len() - 1;
+        png[last] ^= 0xff;
+        assert!(decode_png(&png).unwrap_err().contains("crc mismatch"));
+    }
+}
@@ -1,8 +1,48 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
-use font8x8::{UnicodeFonts, BASIC_FONTS};
+use serde::Deserialize;
+
+use crate::audio::{parse_wav_pcm, AudioClip, AudioClipStore};
+use crate::font::{Font, TextAlign, TextLayout, DEFAULT_TTF_SIZE};
+use crate::png::decode_png;
+use crate::tilemap::{Tilemap, TilemapError, TilemapStore, TILEMAP_CHUNK};
+
+/// Pixel compositing for draws. Every mode scales the source by its alpha and coverage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    /// Source-over.
+    #[default]
+    Alpha,
+    /// Adds source to destination, saturating; for glows and particles.
+    Add,
+    /// Darkens by multiplying channels; for shadows and light maps.
+    Multiply,
+    /// Inverse multiply that only lightens.
+    Screen,
+}
+
+impl BlendMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "alpha" => Some(Self::Alpha),
+            "add" => Some(Self::Add),
+            "multiply" => Some(Self::Multiply),
+            "screen" => Some(Self::Screen),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Alpha => "alpha",
+            Self::Add => "add",
+            Self::Multiply => "multiply",
+            Self::Screen => "screen",
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum DrawCommand {
@@ -34,36 +74,271 @@ pub enum DrawCommand {
         filled: bool,
         thickness: f64,
     },
+    /// `(x, y)` is where the origin lands; the origin is a fraction of the scaled sprite size
+    /// (`0, 0` top-left, `0.5, 0.5` centre) and is also the pivot for `rotation` (radians,
+    /// clockwise on screen). Flips mirror the texture before scaling and rotation.
     Sprite {
         asset_id: String,
         x: f64,
         y: f64,
         rotation: f64,
-        scale: f64,
+        scale_x: f64,
+        scale_y: f64,
+        flip_x: bool,
+        flip_y: bool,
+        origin_x: f64,
+        origin_y: f64,
         color: [u8; 4],
     },
+    /// `font` names a font asset; `None` uses the builtin 8x8 face. `(x, y)` is the top-left of
+    /// the laid-out block; see [`TextLayout`] for wrapping, alignment and line spacing.
     Text {
         value: String,
         x: f64,
         y: f64,
         size: f64,
+        font: Option<String>,
+        max_width: Option<f64>,
+        align: TextAlign,
+        line_spacing: f64,
+        color: [u8; 4],
+    },
+    /// Replaces the view so world `(x, y)` sits at the surface centre, magnified by `zoom` and
+    /// turned by `rotation` radians. Later draw commands are in world coordinates.
+    Camera {
+        x: f64,
+        y: f64,
+        zoom: f64,
+        rotation: f64,
+    },
+    PushTransform,
+    PopTransform,
+    Translate {
+        x: f64,
+        y: f64,
+    },
+    Scale {
+        x: f64,
+        y: f64,
+    },
+    Rotate {
+        rotation: f64,
+    },
+    /// Convex polygon through `points`, in either winding. Filled edges are anti-aliased.
+    Polygon {
+        points: Vec<[f64; 2]>,
+        color: [u8; 4],
+        filled: bool,
+        thickness: f64,
+    },
+    /// Triangle list: each run of three `vertices` is one triangle, shaded by interpolating the
+    /// matching `colors` (one per vertex).
+    Triangles {
+        vertices: Vec<[f64; 2]>,
+        colors: Vec<[u8; 4]>,
+    },
+    /// Texture `asset_id` mapped onto the quad `points` with normalized `uvs` (one per corner),
+    /// sampled nearest-neighbour and clamped to the texture edge.
+    TexturedQuad {
+        asset_id: String,
+        points: [[f64; 2]; 4],
+        uvs: [[f64; 2]; 4],
+        color: [u8; 4],
+    },
+    /// Draws runtime tilemap `tilemap` with its top-left corner at `(x, y)`.
+    Tilemap {
+        tilemap: String,
+        x: f64,
+        y: f64,
         color: [u8; 4],
     },
+    /// Later draws go to `layer`; layers rasterize in ascending order. Starts at 0 each frame.
+    Layer {
+        layer: i32,
+    },
+    /// Restricts later draws to a screen-space rectangle (not affected by the camera).
+    SetClip {
+        x: f64,
+        y: f64,
+        w: f64,
+        h: f64,
+    },
+    ResetClip,
+    /// Switches the frame to palette mode, or swaps the palette if it is already on. Draw colors
+    /// then carry a palette index in their red channel, textures are matched to the nearest
+    /// entry, and indices resolve to RGBA with the palette in effect when the frame finishes.
+    SetPalette {
+        colors: Vec<[u8; 4]>,
+    },
+    /// Returns to direct RGBA drawing.
+    ResetPalette,
+    /// How later draws combine with the pixels beneath them. `Clear` always overwrites.
+    Blend {
+        mode: BlendMode,
+    },
+    /// Allocates a persistent offscreen surface, cleared to transparent. Re-creating a target
+    /// with the same size keeps its pixels; a new size reallocates it.
+    CreateTarget {
+        name: String,
+        width: u32,
+        height: u32,
+    },
+    /// Sends later draws to a render target (`None` for the screen) until the next switch.
+    /// Sprites and textured quads sample a target as asset id `target:<name>`.
+    SetTarget {
+        name: Option<String>,
+    },
+    /// Frees a target at the end of the frame, after draws that sample it.
+    ReleaseTarget {
+        name: String,
+    },
 }
 
+/// Nesting limit for `PushTransform` within one frame.
+pub const MAX_TRANSFORM_DEPTH: usize = 32;
+pub const MAX_POLYGON_POINTS: usize = 64;
+pub const MAX_TRIANGLE_VERTICES: usize = 3 * 4096;
+pub const MAX_PALETTE_COLORS: usize = 256;
+pub const MAX_RENDER_TARGET_SIZE: u32 = 4096;
+/// Combined RGBA bytes all render targets may hold at once (four 1024x1024 targets).
+pub const MAX_RENDER_TARGET_BYTES: usize = 16 << 20;
+/// Asset id prefix that samples a render target instead of a texture asset.
+pub const RENDER_TARGET_PREFIX: &str = "target:";
+
 #[derive(Debug, Default, Clone)]
 pub struct FrameCommandBuffer {
     pub commands: Vec<DrawCommand>,
+    transform_depth: usize,
 }
 
 impl FrameCommandBuffer {
     pub fn push(&mut self, command: DrawCommand) -> Result<(), RenderValidationError> {
         validate_command(&command)?;
+        match command {
+            DrawCommand::PushTransform if self.transform_depth == MAX_TRANSFORM_DEPTH => {
+                return Err(RenderValidationError::InvalidCommand(format!(
+                    "push_transform nested deeper than {MAX_TRANSFORM_DEPTH}"
+                )));
+            }
+            DrawCommand::PushTransform => self.transform_depth += 1,
+            DrawCommand::PopTransform if self.transform_depth == 0 => {
+                return Err(RenderValidationError::InvalidCommand(
+                    "pop_transform without a matching push_transform".to_owned(),
+                ));
+            }
+            DrawCommand::PopTransform => self.transform_depth -= 1,
+            _ => {}
+        }
         self.commands.push(command);
         Ok(())
     }
 }
 
+/// 2D affine map `(x, y) -> (a*x + c*y + tx, b*x + d*y + ty)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Affine2D {
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+    tx: f64,
+    ty: f64,
+}
+
+impl Affine2D {
+    const IDENTITY: Self = Self {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        tx: 0.0,
+        ty: 0.0,
+    };
+
+    fn translation(x: f64, y: f64) -> Self {
+        Self {
+            tx: x,
+            ty: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    fn scaling(x: f64, y: f64) -> Self {
+        Self {
+            a: x,
+            d: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Clockwise on screen, since y points down.
+    fn rotation(radians: f64) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Self::IDENTITY
+        }
+    }
+
+    fn camera(x: f64, y: f64, zoom: f64, rotation: f64, surface: (u32, u32)) -> Self {
+        Self::translation(surface.0 as f64 / 2.0, surface.1 as f64 / 2.0)
+            .then(&Self::scaling(zoom, zoom))
+            .then(&Self::rotation(-rotation))
+            .then(&Self::translation(-x, -y))
+    }
+
+    /// `self` after `inner`: `inner` is applied to points first.
+    fn then(&self, inner: &Self) -> Self {
+        Self {
+            a: self.a * inner.a + self.c * inner.b,
+            b: self.b * inner.a + self.d * inner.b,
+            c: self.a * inner.c + self.c * inner.d,
+            d: self.b * inner.c + self.d * inner.d,
+            tx: self.a * inner.tx + self.c * inner.ty + self.tx,
+            ty: self.b * inner.tx + self.d * inner.ty + self.ty,
+        }
+    }
+
+    fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.a * x + self.c * y + self.tx,
+            self.b * x + self.d * y + self.ty,
+        )
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+        Some(Self {
+            a,
+            b,
+            c,
+            d,
+            tx: -(a * self.tx + c * self.ty),
+            ty: -(b * self.tx + d * self.ty),
+        })
+    }
+
+    fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+
+    /// Length scale for radii, thicknesses and text sizes.
+    fn uniform_scale(&self) -> f64 {
+        self.determinant().abs().sqrt()
+    }
+
+    fn is_axis_aligned(&self) -> bool {
+        self.b == 0.0 && self.c == 0.0
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct RenderStats {
     pub commands_executed: usize,
@@ -77,9 +352,168 @@ pub struct Texture {
     pub pixels: Vec<u8>,
 }
 
+/// Resolved placement of a `DrawCommand::Sprite`, see its docs for the conventions.
+#[derive(Debug, Clone, Copy)]
+struct SpritePlacement {
+    x: f64,
+    y: f64,
+    rotation: f64,
+    scale: (f64, f64),
+    flip: (bool, bool),
+    origin: (f64, f64),
+}
+
+impl SpritePlacement {
+    /// Carries the placement through `view`. The view's rotation adds to the sprite's, and its
+    /// axis scales multiply the sprite's own; a mirroring view flips the sprite vertically.
+    fn transformed(&self, view: &Affine2D) -> Self {
+        let (x, y) = view.apply(self.x, self.y);
+        let axis_x = view.a.hypot(view.b);
+        let axis_y = view.determinant() / axis_x;
+        let (flip_y, origin_y) = if axis_y < 0.0 {
+            (!self.flip.1, 1.0 - self.origin.1)
+        } else {
+            (self.flip.1, self.origin.1)
+        };
+        Self {
+            x,
+            y,
+            rotation: self.rotation + view.b.atan2(view.a),
+            scale: (self.scale.0 * axis_x, self.scale.1 * axis_y.abs()),
+            flip: (self.flip.0, flip_y),
+            origin: (self.origin.0, origin_y),
+        }
+    }
+}
+
+/// Cartridge assets indexed by file stem: `.ppm`/`.png` textures, `.wav` audio clips and
+/// `.fnt`/`.ttf`/`.otf` fonts.
+///
+/// A `<stem>.atlas.toml` sidecar cuts the `<stem>` image into named sprites that are
+/// registered as `<stem>/<name>` textures next to the full image. A `<stem>.font.toml`
+/// sidecar picks the pixel size and extra characters a TTF font is rasterized with.
 #[derive(Debug, Default, Clone)]
 pub struct AssetStore {
     textures: HashMap<String, Texture>,
+    fonts: HashMap<String, Font>,
+    audio: AudioClipStore,
+}
+
+const ATLAS_SUFFIX: &str = ".atlas.toml";
+const FONT_SUFFIX: &str = ".font.toml";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FontFile {
+    #[serde(default)]
+    size: Option<u32>,
+    /// Characters to rasterize in addition to printable ASCII.
+    #[serde(default)]
+    chars: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AtlasFile {
+    #[serde(default)]
+    sprites: BTreeMap<String, AtlasRect>,
+    #[serde(default)]
+    strips: Vec<AtlasStrip>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AtlasRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+/// Equal-sized frames laid out left to right, wrapping after `columns`: `<name>_0`, `<name>_1`...
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AtlasStrip {
+    name: String,
+    #[serde(default)]
+    x: u32,
+    #[serde(default)]
+    y: u32,
+    w: u32,
+    h: u32,
+    frames: u32,
+    columns: Option<u32>,
+}
+
+impl AtlasFile {
+    fn regions(&self) -> Result<Vec<(String, AtlasRect)>, String> {
+        let mut regions: Vec<(String, AtlasRect)> = self
+            .sprites
+            .iter()
+            .map(|(name, rect)| (name.clone(), *rect))
+            .collect();
+        for strip in &self.strips {
+            let columns = strip.columns.unwrap_or(strip.frames).max(1);
+            for frame in 0..strip.frames {
+                let offset = |origin: u32, index: u32, size: u32| {
+                    index
+                        .checked_mul(size)
+                        .and_then(|step| origin.checked_add(step))
+                        .ok_or_else(|| {
+                            format!("strip `{}` frame {frame} is out of range", strip.name)
+                        })
+                };
+                regions.push((
+                    format!("{}_{frame}", strip.name),
+                    AtlasRect {
+                        x: offset(strip.x, frame % columns, strip.w)?,
+                        y: offset(strip.y, frame / columns, strip.h)?,
+                        w: strip.w,
+                        h: strip.h,
+                    },
+                ));
+            }
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for (name, _) in &regions {
+            if name.is_empty() || name.contains('/') {
+                return Err(format!("invalid sprite name `{name}`"));
+            }
+            if !seen.insert(name.as_str()) {
+                return Err(format!("duplicate sprite name `{name}`"));
+            }
+        }
+        Ok(regions)
+    }
+}
+
+impl Texture {
+    /// Tinted texel at normalized `(u, v)`, clamped to the texture edge.
+    fn sample_nearest(&self, u: f64, v: f64, tint: [u8; 4]) -> [u8; 4] {
+        let x = ((u * self.width as f64).floor() as i64).clamp(0, self.width as i64 - 1) as u32;
+        let y = ((v * self.height as f64).floor() as i64).clamp(0, self.height as i64 - 1) as u32;
+        let i = ((y * self.width + x) * 4) as usize;
+        let src = &self.pixels[i..i + 4];
+        [0, 1, 2, 3].map(|c| ((src[c] as u16 * tint[c] as u16) / 255) as u8)
+    }
+
+    /// Copies out a sub-rectangle, or `None` if it is empty or exceeds the texture bounds.
+    pub fn crop(&self, x: u32, y: u32, w: u32, h: u32) -> Option<Texture> {
+        if w == 0 || h == 0 || x.checked_add(w)? > self.width || y.checked_add(h)? > self.height {
+            return None;
+        }
+        let mut pixels = Vec::with_capacity((w * h * 4) as usize);
+        for row in y..y + h {
+            let start = ((row * self.width + x) * 4) as usize;
+            pixels.extend_from_slice(&self.pixels[start..start + (w * 4) as usize]);
+        }
+        Some(Texture {
+            width: w,
+            height: h,
+            pixels,
+        })
+    }
 }
 
 impl AssetStore {
@@ -90,117 +524,276 @@ impl AssetStore {
             return Ok(store);
         }
 
+        let mut files = Vec::new();
         for entry in std::fs::read_dir(dir)
             .map_err(|source| AssetLoadError::ReadDir(dir.to_path_buf(), source))?
         {
             let entry =
                 entry.map_err(|source| AssetLoadError::ReadDir(dir.to_path_buf(), source))?;
             let path = entry.path();
-            if !path.is_file() {
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+        // Atlases and BMFont pages reference images, so every image must be indexed first.
+        files.sort();
+        let mut atlases = Vec::new();
+        let mut bmfonts = Vec::new();
+
+        for path in files {
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if file_name.ends_with(ATLAS_SUFFIX) {
+                atlases.push(path);
                 continue;
             }
-            if path.extension().and_then(|e| e.to_str()) != Some("ppm") {
+            let extension = path.extension().and_then(|e| e.to_str());
+            if extension == Some("fnt") {
+                bmfonts.push(path);
+                continue;
+            }
+            if !matches!(extension, Some("ppm" | "png" | "wav" | "ttf" | "otf")) {
                 continue;
             }
 
             let bytes = std::fs::read(&path)
                 .map_err(|source| AssetLoadError::ReadFile(path.clone(), source))?;
-            let texture = parse_ppm_p6(&bytes)
-                .map_err(|msg| AssetLoadError::InvalidPpm(path.clone(), msg))?;
             let id = path
                 .file_stem()
                 .and_then(|s| s.to_str())
                 .ok_or_else(|| AssetLoadError::InvalidAssetId(path.clone()))?
                 .to_owned();
+            if extension == Some("wav") {
+                let clip = parse_wav_pcm(&bytes)
+                    .map_err(|msg| AssetLoadError::InvalidWav(path.clone(), msg))?;
+                store.audio.insert(id, clip);
+                continue;
+            }
+            if matches!(extension, Some("ttf" | "otf")) {
+                let font = load_ttf(&path, &bytes)
+                    .map_err(|msg| AssetLoadError::InvalidFont(path.clone(), msg))?;
+                store.insert_font(&path, id, font)?;
+                continue;
+            }
+
+            let texture = if extension == Some("png") {
+                decode_png(&bytes).map_err(|msg| AssetLoadError::InvalidPng(path.clone(), msg))?
+            } else {
+                parse_ppm_p6(&bytes).map_err(|msg| AssetLoadError::InvalidPpm(path.clone(), msg))?
+            };
+            if store.textures.contains_key(&id) {
+                return Err(AssetLoadError::DuplicateTexture(path, id));
+            }
             store.textures.insert(id, texture);
         }
 
+        for path in atlases {
+            store.load_atlas(&path)?;
+        }
+        for path in bmfonts {
+            store.load_bmfont(&path)?;
+        }
+
         Ok(store)
     }
 
+    fn load_bmfont(&mut self, path: &Path) -> Result<(), AssetLoadError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|source| AssetLoadError::ReadFile(path.to_path_buf(), source))?;
+        let id = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| AssetLoadError::InvalidAssetId(path.to_path_buf()))?
+            .to_owned();
+        let font = Font::from_bmfont(&text, |file| {
+            Path::new(file)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| self.textures.get(stem))
+        })
+        .map_err(|msg| AssetLoadError::InvalidFont(path.to_path_buf(), msg))?;
+        self.insert_font(path, id, font)
+    }
+
+    fn insert_font(&mut self, path: &Path, id: String, font: Font) -> Result<(), AssetLoadError> {
+        if self.fonts.contains_key(&id) {
+            return Err(AssetLoadError::DuplicateFont(path.to_path_buf(), id));
+        }
+        self.fonts.insert(id, font);
+        Ok(())
+    }
+
+    fn load_atlas(&mut self, path: &Path) -> Result<(), AssetLoadError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|source| AssetLoadError::ReadFile(path.to_path_buf(), source))?;
+        let invalid = |msg: String| AssetLoadError::InvalidAtlas(path.to_path_buf(), msg);
+        let stem = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(ATLAS_SUFFIX))
+            .filter(|stem| !stem.is_empty())
+            .ok_or_else(|| AssetLoadError::InvalidAssetId(path.to_path_buf()))?
+            .to_owned();
+        let atlas: AtlasFile = toml::from_str(&text).map_err(|err| invalid(err.to_string()))?;
+        let image = self
+            .textures
+            .get(&stem)
+            .ok_or_else(|| invalid(format!("no `{stem}.png` or `{stem}.ppm` image to slice")))?;
+
+        let mut sprites = Vec::new();
+        for (name, rect) in atlas.regions().map_err(invalid)? {
+            let sprite = image.crop(rect.x, rect.y, rect.w, rect.h).ok_or_else(|| {
+                invalid(format!(
+                    "sprite `{name}` rect {}x{}+{}+{} is outside the {}x{} image",
+                    rect.w, rect.h, rect.x, rect.y, image.width, image.height
+                ))
+            })?;
+            sprites.push((format!("{stem}/{name}"), sprite));
+        }
+        self.textures.extend(sprites);
+        Ok(())
+    }
+
     pub fn texture(&self, id: &str) -> Option<&Texture> {
         self.textures.get(id)
     }
+
+    pub fn font(&self, id: &str) -> Option<&Font> {
+        self.fonts.get(id)
+    }
+
+    pub fn font_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.fonts.keys().map(String::as_str).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    pub fn clip(&self, id: &str) -> Option<&AudioClip> {
+        self.audio.clip(id)
+    }
+
+    /// Clip index handed to the mixer.
+    pub fn audio_clips(&self) -> &AudioClipStore {
+        &self.audio
+    }
+
+    /// How many `tile_size` tiles texture `tileset` holds, counted row-major.
+    pub fn tileset_tile_count(
+        &self,
+        tileset: &str,
+        tile_size: (u32, u32),
+    ) -> Result<u32, TilemapError> {
+        let texture = self
+            .texture(tileset)
+            .ok_or_else(|| TilemapError::UnknownTileset(tileset.to_owned()))?;
+        let across = texture.width / tile_size.0.max(1);
+        let down = texture.height / tile_size.1.max(1);
+        Ok(across * down)
+    }
+
+    /// Texture ids in sorted order.
+    pub fn texture_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.textures.keys().map(String::as_str).collect();
+        ids.sort_unstable();
+        ids
+    }
 }
 
 #[derive(Debug, Clone)]
-struct FontGlyph {
-    u: u32,
-    v: u32,
-    w: u32,
-    h: u32,
+pub struct SoftwareFrame {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+    clip: Option<PixelRect>,
+    blend: BlendMode,
+    indexed: Option<IndexedBuffer>,
+    targets: BTreeMap<String, Texture>,
 }
 
+/// Palette-mode framebuffer: one index per pixel, resolved into the RGBA pixels at present.
 #[derive(Debug, Clone)]
-struct FontAtlas {
-    width: u32,
-    alpha: Vec<u8>,
-    glyphs: HashMap<char, FontGlyph>,
-}
-
-impl FontAtlas {
-    fn builtin() -> Self {
-        let cell_w = 8u32;
-        let cell_h = 8u32;
-        let cols = 16u32;
-        let rows = 8u32;
-        let width = cols * cell_w;
-        let height = rows * cell_h;
-        let mut alpha = vec![0u8; (width * height) as usize];
-        let mut glyphs = HashMap::new();
-
-        for code in 32u8..=127u8 {
-            let ch = code as char;
-            let idx = (code - 32) as u32;
-            let gx = (idx % cols) * cell_w;
-            let gy = (idx / cols) * cell_h;
-
-            if let Some(bitmap) = BASIC_FONTS.get(ch) {
-                for (row, bits) in bitmap.iter().enumerate() {
-                    for col in 0..8usize {
-                        let on = (bits >> col) & 1 == 1;
-                        let x = gx + col as u32;
-                        let y = gy + row as u32;
-                        let off = (y * width + x) as usize;
-                        alpha[off] = if on { 255 } else { 0 };
-                    }
-                }
-            }
+struct IndexedBuffer {
+    palette: Vec<[u8; 4]>,
+    indices: Vec<u8>,
+    /// Nearest palette entry per texel color, cleared whenever the palette changes.
+    nearest: HashMap<[u8; 3], u8>,
+}
 
-            glyphs.insert(
-                ch,
-                FontGlyph {
-                    u: gx,
-                    v: gy,
-                    w: cell_w,
-                    h: cell_h,
-                },
-            );
+impl IndexedBuffer {
+    /// Starts from `rgba` matched to `palette`, so switching modes keeps the image.
+    fn new(palette: Vec<[u8; 4]>, rgba: &[u8]) -> Self {
+        let mut buffer = Self {
+            palette,
+            indices: Vec::with_capacity(rgba.len() / 4),
+            nearest: HashMap::new(),
+        };
+        for px in rgba.chunks_exact(4) {
+            let index = buffer.quantize([px[0], px[1], px[2]]);
+            buffer.indices.push(index);
         }
+        buffer
+    }
 
-        Self {
-            width,
-            alpha,
-            glyphs,
+    fn set_palette(&mut self, palette: &[[u8; 4]]) {
+        if self.palette != palette {
+            self.palette = palette.to_vec();
+            self.nearest.clear();
         }
     }
 
-    fn glyph(&self, ch: char) -> Option<&FontGlyph> {
-        self.glyphs.get(&ch)
+    /// First palette entry closest to `rgb` by squared distance.
+    fn quantize(&mut self, rgb: [u8; 3]) -> u8 {
+        let palette = &self.palette;
+        *self.nearest.entry(rgb).or_insert_with(|| {
+            let distance = |entry: &[u8; 4]| -> i32 {
+                (0..3)
+                    .map(|c| (entry[c] as i32 - rgb[c] as i32).pow(2))
+                    .sum()
+            };
+            (0..palette.len())
+                .min_by_key(|i| distance(&palette[*i]))
+                .unwrap_or(0) as u8
+        })
     }
 
-    fn alpha_at(&self, x: u32, y: u32) -> u8 {
-        let idx = (y * self.width + x) as usize;
-        self.alpha[idx]
+    /// Indices past the end of the palette resolve to transparent black.
+    fn resolve(&self, rgba: &mut [u8]) {
+        for (px, index) in rgba.chunks_exact_mut(4).zip(&self.indices) {
+            let color = self.palette.get(*index as usize).unwrap_or(&[0, 0, 0, 0]);
+            px.copy_from_slice(color);
+        }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct SoftwareFrame {
-    width: u32,
-    height: u32,
-    pixels: Vec<u8>,
-    font: FontAtlas,
+/// Half-open pixel rectangle `[x0, x1) x [y0, y1)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PixelRect {
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+}
+
+impl PixelRect {
+    fn from_rect(x: f64, y: f64, w: f64, h: f64) -> Self {
+        Self {
+            x0: x.round() as i32,
+            y0: y.round() as i32,
+            x1: (x + w).round() as i32,
+            y1: (y + h).round() as i32,
+        }
+    }
+
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x0 && x < self.x1 && y >= self.y0 && y < self.y1
+    }
+}
+
+struct QueuedDraw<'a> {
+    command: &'a DrawCommand,
+    view: Affine2D,
+    clip: Option<PixelRect>,
+    blend: BlendMode,
+    layer: i32,
 }
 
 impl SoftwareFrame {
@@ -210,7 +803,37 @@ impl SoftwareFrame {
             width,
             height,
             pixels: vec![0; pixel_count],
-            font: FontAtlas::builtin(),
+            clip: None,
+            blend: BlendMode::Alpha,
+            indexed: None,
+            targets: BTreeMap::new(),
+        }
+    }
+
+    fn from_texture(texture: Texture) -> Self {
+        Self {
+            width: texture.width,
+            height: texture.height,
+            pixels: texture.pixels,
+            clip: None,
+            blend: BlendMode::Alpha,
+            indexed: None,
+            targets: BTreeMap::new(),
+        }
+    }
+
+    /// Resolves palette indices into the RGBA pixels; a no-op outside palette mode.
+    fn present_indices(&mut self) {
+        if let Some(buffer) = &self.indexed {
+            buffer.resolve(&mut self.pixels);
+        }
+    }
+
+    fn into_texture(self) -> Texture {
+        Texture {
+            width: self.width,
+            height: self.height,
+            pixels: self.pixels,
         }
     }
 
@@ -223,79 +846,394 @@ impl SoftwareFrame {
         commands: &FrameCommandBuffer,
         assets: Option<&AssetStore>,
     ) -> RenderStats {
-        let mut stats = RenderStats::default();
+        self.apply_with_resources(commands, assets, None)
+    }
 
+    pub fn apply_with_resources(
+        &mut self,
+        commands: &FrameCommandBuffer,
+        assets: Option<&AssetStore>,
+        tilemaps: Option<&TilemapStore>,
+    ) -> RenderStats {
+        let mut stats = RenderStats::default();
+        let mut camera = Affine2D::IDENTITY;
+        let mut model = Affine2D::IDENTITY;
+        let mut saved = Vec::new();
+        let mut layer = 0;
+        let mut clip = None;
+        let mut blend = BlendMode::Alpha;
+        let mut targets = std::mem::take(&mut self.targets);
+        let mut target: Option<&str> = None;
+        let mut cameras = HashMap::new();
+        let mut released = Vec::new();
+        let mut screen = Vec::with_capacity(commands.commands.len());
+        let mut passes: Vec<(&str, Vec<QueuedDraw<'_>>)> = Vec::new();
+        let mut queued_draws = false;
+
+        // State commands resolve in submission order. Each target pass (the draws between a
+        // switch to a target and the next switch) then rasterizes by layer, and finally every
+        // screen draw of the frame does, as one queue. The sorts are stable so submission order
+        // still decides overlap within a layer.
         for command in &commands.commands {
             match command {
-                DrawCommand::Clear { color } => {
-                    self.clear(*color);
-                    stats.commands_executed += 1;
-                }
-                DrawCommand::Line {
-                    x1,
-                    y1,
-                    x2,
-                    y2,
-                    color,
-                    thickness,
-                } => {
-                    self.draw_line(*x1, *y1, *x2, *y2, *color, *thickness);
-                    stats.commands_executed += 1;
-                }
-                DrawCommand::Rect {
+                DrawCommand::Camera {
                     x,
                     y,
-                    w,
-                    h,
-                    color,
-                    filled,
-                    thickness,
+                    zoom,
+                    rotation,
                 } => {
-                    self.draw_rect((*x, *y), (*w, *h), *color, *filled, *thickness);
-                    stats.commands_executed += 1;
+                    let surface = match target {
+                        Some(name) => targets.get(name).map_or((0, 0), |t| (t.width, t.height)),
+                        None => (self.width, self.height),
+                    };
+                    camera = Affine2D::camera(*x, *y, *zoom, *rotation, surface);
                 }
-                DrawCommand::Circle {
-                    x,
-                    y,
-                    r,
-                    color,
-                    filled,
-                    thickness,
+                DrawCommand::CreateTarget {
+                    name,
+                    width,
+                    height,
                 } => {
-                    self.draw_circle(*x, *y, *r, *color, *filled, *thickness);
-                    stats.commands_executed += 1;
+                    if !create_target(&mut targets, name, *width, *height) {
+                        stats.commands_unsupported += 1;
+                        continue;
+                    }
                 }
-                DrawCommand::Sprite {
-                    asset_id,
-                    x,
-                    y,
-                    scale,
-                    color,
-                    ..
-                } => {
-                    if let Some(texture) = assets.and_then(|a| a.texture(asset_id)) {
-                        self.draw_texture(texture, *x, *y, *scale, *color);
-                        stats.commands_executed += 1;
-                    } else {
+                DrawCommand::SetTarget { name } => {
+                    let next = name.as_deref();
+                    if next.is_some_and(|name| !targets.contains_key(name)) {
+                        stats.commands_unsupported += 1;
+                        continue;
+                    }
+                    // Each surface keeps its own camera; clips are in the old surface's pixels.
+                    cameras.insert(target, camera);
+                    camera = cameras.get(&next).copied().unwrap_or(Affine2D::IDENTITY);
+                    clip = None;
+                    target = next;
+                    if let Some(name) = next {
+                        passes.push((name, Vec::new()));
+                    }
+                }
+                DrawCommand::ReleaseTarget { name } => {
+                    if !targets.contains_key(name) {
                         stats.commands_unsupported += 1;
+                        continue;
                     }
+                    released.push(name.as_str());
                 }
-                DrawCommand::Text {
-                    value,
-                    x,
-                    y,
-                    size,
-                    color,
-                } => {
-                    self.draw_text_atlas(value, *x, *y, *size, *color);
-                    stats.commands_executed += 1;
+                DrawCommand::PushTransform => saved.push(model),
+                DrawCommand::PopTransform => model = saved.pop().unwrap_or(Affine2D::IDENTITY),
+                DrawCommand::Translate { x, y } => {
+                    model = model.then(&Affine2D::translation(*x, *y));
+                }
+                DrawCommand::Scale { x, y } => model = model.then(&Affine2D::scaling(*x, *y)),
+                DrawCommand::Rotate { rotation } => {
+                    model = model.then(&Affine2D::rotation(*rotation));
+                }
+                DrawCommand::Layer { layer: next } => layer = *next,
+                DrawCommand::SetClip { x, y, w, h } => {
+                    clip = Some(PixelRect::from_rect(*x, *y, *w, *h));
+                }
+                DrawCommand::ResetClip => clip = None,
+                DrawCommand::Blend { mode } => blend = *mode,
+                // Queued draws all rasterize in whichever mode is in force at the end, so the
+                // mode may only change before the frame's first draw; new palette colors still
+                // apply to the whole frame.
+                DrawCommand::SetPalette { .. } | DrawCommand::ResetPalette
+                    if queued_draws
+                        && self.indexed.is_some()
+                            != matches!(command, DrawCommand::SetPalette { .. }) =>
+                {
+                    stats.commands_unsupported += 1;
+                    continue;
+                }
+                DrawCommand::SetPalette { colors } => match self.indexed.as_mut() {
+                    Some(buffer) => buffer.set_palette(colors),
+                    None => self.indexed = Some(IndexedBuffer::new(colors.clone(), &self.pixels)),
+                },
+                DrawCommand::ResetPalette => self.indexed = None,
+                _ => {
+                    let queue = match target {
+                        Some(_) => &mut passes.last_mut().expect("target pass is open").1,
+                        None => &mut screen,
+                    };
+                    queue.push(QueuedDraw {
+                        command,
+                        view: camera.then(&model),
+                        clip,
+                        blend,
+                        layer,
+                    });
+                    queued_draws = true;
+                    continue;
                 }
             }
+            stats.commands_executed += 1;
         }
+        for (name, mut queue) in passes {
+            queue.sort_by_key(|draw| draw.layer);
+            // Render into the target through a frame that borrows its pixels, so it cannot be
+            // sampled while it is being drawn to.
+            let Some(texture) = targets.remove(name) else {
+                continue;
+            };
+            let mut surface = SoftwareFrame::from_texture(texture);
+            if let Some(buffer) = &self.indexed {
+                surface.indexed = Some(IndexedBuffer::new(buffer.palette.clone(), &surface.pixels));
+            }
+            for draw in queue {
+                surface.rasterize(draw, assets, tilemaps, &targets, &mut stats);
+            }
+            surface.present_indices();
+            targets.insert(name.to_owned(), surface.into_texture());
+        }
+        screen.sort_by_key(|draw| draw.layer);
+        for draw in screen {
+            self.rasterize(draw, assets, tilemaps, &targets, &mut stats);
+        }
+        for name in released {
+            targets.remove(name);
+        }
+        self.targets = targets;
+        self.clip = None;
+        self.blend = BlendMode::Alpha;
+        self.present_indices();
 
         stats
     }
 
+    fn rasterize(
+        &mut self,
+        draw: QueuedDraw<'_>,
+        assets: Option<&AssetStore>,
+        tilemaps: Option<&TilemapStore>,
+        targets: &BTreeMap<String, Texture>,
+        stats: &mut RenderStats,
+    ) {
+        let QueuedDraw {
+            command,
+            view,
+            clip,
+            blend,
+            ..
+        } = draw;
+        self.clip = clip;
+        self.blend = blend;
+        match command {
+            DrawCommand::Clear { color } => {
+                self.clear(*color);
+                stats.commands_executed += 1;
+            }
+            DrawCommand::Line {
+                x1,
+                y1,
+                x2,
+                y2,
+                color,
+                thickness,
+            } => {
+                let (x1, y1) = view.apply(*x1, *y1);
+                let (x2, y2) = view.apply(*x2, *y2);
+                let thickness = *thickness * view.uniform_scale();
+                self.draw_line(x1, y1, x2, y2, *color, thickness);
+                stats.commands_executed += 1;
+            }
+            DrawCommand::Rect {
+                x,
+                y,
+                w,
+                h,
+                color,
+                filled,
+                thickness,
+            } => {
+                let thickness = *thickness * view.uniform_scale();
+                if view.is_axis_aligned() {
+                    let (x0, y0) = view.apply(*x, *y);
+                    let (x1, y1) = view.apply(*x + *w, *y + *h);
+                    let min = (x0.min(x1), y0.min(y1));
+                    let max = (x0.max(x1), y0.max(y1));
+                    self.draw_rect(min, max, *color, *filled, thickness);
+                } else {
+                    let corners = [(*x, *y), (*x + *w, *y), (*x + *w, *y + *h), (*x, *y + *h)]
+                        .map(|(cx, cy)| view.apply(cx, cy));
+                    self.draw_polygon(&corners, *color, *filled, thickness);
+                }
+                stats.commands_executed += 1;
+            }
+            DrawCommand::Circle {
+                x,
+                y,
+                r,
+                color,
+                filled,
+                thickness,
+            } => {
+                let (x, y) = view.apply(*x, *y);
+                let scale = view.uniform_scale();
+                self.draw_circle(x, y, *r * scale, *color, *filled, *thickness * scale);
+                stats.commands_executed += 1;
+            }
+            DrawCommand::Sprite {
+                asset_id,
+                x,
+                y,
+                rotation,
+                scale_x,
+                scale_y,
+                flip_x,
+                flip_y,
+                origin_x,
+                origin_y,
+                color,
+            } => {
+                if let Some(texture) = resolve_texture(asset_id, assets, targets) {
+                    let placement = SpritePlacement {
+                        x: *x,
+                        y: *y,
+                        rotation: *rotation,
+                        scale: (*scale_x, *scale_y),
+                        flip: (*flip_x, *flip_y),
+                        origin: (*origin_x, *origin_y),
+                    };
+                    self.draw_texture(texture, &placement.transformed(&view), *color);
+                    stats.commands_executed += 1;
+                } else {
+                    stats.commands_unsupported += 1;
+                }
+            }
+            DrawCommand::Polygon {
+                points,
+                color,
+                filled,
+                thickness,
+            } => {
+                let points: Vec<(f64, f64)> =
+                    points.iter().map(|p| view.apply(p[0], p[1])).collect();
+                if *filled {
+                    self.fill_convex(&points, *color);
+                } else {
+                    let thickness = *thickness * view.uniform_scale();
+                    self.draw_polygon(&points, *color, false, thickness);
+                }
+                stats.commands_executed += 1;
+            }
+            DrawCommand::Triangles { vertices, colors } => {
+                // Edges shared by two triangles of the list are interior and stay hard.
+                let edge_key = |a: [f64; 2], b: [f64; 2]| {
+                    let (a, b) = (a.map(f64::to_bits), b.map(f64::to_bits));
+                    if a <= b {
+                        (a, b)
+                    } else {
+                        (b, a)
+                    }
+                };
+                let mut edge_uses: HashMap<_, u32> = HashMap::new();
+                for corners in vertices.chunks_exact(3) {
+                    for (a, b) in [(1, 2), (2, 0), (0, 1)] {
+                        *edge_uses
+                            .entry(edge_key(corners[a], corners[b]))
+                            .or_default() += 1;
+                    }
+                }
+                for (corners, shades) in vertices.chunks_exact(3).zip(colors.chunks_exact(3)) {
+                    let smooth = [(1, 2), (2, 0), (0, 1)]
+                        .map(|(a, b)| edge_uses[&edge_key(corners[a], corners[b])] == 1);
+                    let corners = [0, 1, 2].map(|i| view.apply(corners[i][0], corners[i][1]));
+                    // Indices cannot be interpolated, so palette mode takes the nearest vertex.
+                    let indexed = self.indexed.is_some();
+                    let shade = |w: [f64; 3]| {
+                        if indexed {
+                            let nearest =
+                                (0..3).fold(0, |best, i| if w[i] > w[best] { i } else { best });
+                            return shades[nearest];
+                        }
+                        [0, 1, 2, 3].map(|c| {
+                            (0..3)
+                                .map(|i| w[i] * shades[i][c] as f64)
+                                .sum::<f64>()
+                                .round()
+                                .clamp(0.0, 255.0) as u8
+                        })
+                    };
+                    self.fill_triangle(corners, smooth, shade, Self::write_color);
+                }
+                stats.commands_executed += 1;
+            }
+            DrawCommand::TexturedQuad {
+                asset_id,
+                points,
+                uvs,
+                color,
+            } => {
+                if let Some(texture) = resolve_texture(asset_id, assets, targets) {
+                    let corners = points.map(|p| view.apply(p[0], p[1]));
+                    // The shared diagonal is edge 1 of the first half and edge 2 of the second.
+                    for ([a, b, c], smooth) in [
+                        ([0, 1, 2], [true, false, true]),
+                        ([0, 2, 3], [true, true, false]),
+                    ] {
+                        let sample = |w: [f64; 3]| {
+                            let u = w[0] * uvs[a][0] + w[1] * uvs[b][0] + w[2] * uvs[c][0];
+                            let v = w[0] * uvs[a][1] + w[1] * uvs[b][1] + w[2] * uvs[c][1];
+                            texture.sample_nearest(u, v, *color)
+                        };
+                        let corners = [corners[a], corners[b], corners[c]];
+                        self.fill_triangle(corners, smooth, sample, Self::write_texel);
+                    }
+                    stats.commands_executed += 1;
+                } else {
+                    stats.commands_unsupported += 1;
+                }
+            }
+            DrawCommand::Tilemap {
+                tilemap,
+                x,
+                y,
+                color,
+            } => {
+                let map = tilemaps.and_then(|store| store.get(tilemap));
+                let tileset = map.and_then(|m| assets.and_then(|a| a.texture(m.tileset())));
+                if let (Some(map), Some(tileset)) = (map, tileset) {
+                    let placement = view.then(&Affine2D::translation(*x, *y));
+                    self.draw_tilemap(map, tileset, &placement, *color);
+                    stats.commands_executed += 1;
+                } else {
+                    stats.commands_unsupported += 1;
+                }
+            }
+            DrawCommand::Text {
+                value,
+                x,
+                y,
+                size,
+                font,
+                max_width,
+                align,
+                line_spacing,
+                color,
+            } => {
+                let font = match font {
+                    Some(id) => assets.and_then(|a| a.font(id)),
+                    None => Some(Font::builtin()),
+                };
+                if let Some(font) = font {
+                    let (x, y) = view.apply(*x, *y);
+                    let layout = TextLayout {
+                        max_width: max_width.map(|w| w * view.uniform_scale()),
+                        align: *align,
+                        line_spacing: *line_spacing,
+                    };
+                    let size = *size * view.uniform_scale();
+                    self.draw_text(font, value, (x, y), size, &layout, *color);
+                    stats.commands_executed += 1;
+                } else {
+                    stats.commands_unsupported += 1;
+                }
+            }
+            _ => unreachable!("state commands are resolved before rasterizing"),
+        }
+    }
+
     pub fn write_ppm(&self, path: &Path) -> Result<(), RenderIoError> {
         let mut file = std::fs::File::create(path)
             .map_err(|source| RenderIoError::Write(path.to_path_buf(), source))?;
@@ -328,7 +1266,28 @@ impl SoftwareFrame {
         self.height
     }
 
+    pub fn render_target(&self, name: &str) -> Option<&Texture> {
+        self.targets.get(name)
+    }
+
+    /// Bytes held by live render targets, counted against [`MAX_RENDER_TARGET_BYTES`].
+    pub fn render_target_bytes(&self) -> usize {
+        target_bytes(&self.targets)
+    }
+
     fn clear(&mut self, color: [u8; 4]) {
+        if let Some(clip) = self.clip {
+            for y in clip.y0.max(0)..clip.y1.min(self.height as i32) {
+                for x in clip.x0.max(0)..clip.x1.min(self.width as i32) {
+                    self.write_pixel(x, y, color);
+                }
+            }
+            return;
+        }
+        if let Some(buffer) = self.indexed.as_mut() {
+            buffer.indices.fill(color[0]);
+            return;
+        }
         for px in self.pixels.chunks_exact_mut(4) {
             px.copy_from_slice(&color);
         }
@@ -374,18 +1333,16 @@ impl SoftwareFrame {
 
     fn draw_rect(
         &mut self,
-        pos: (f64, f64),
-        size: (f64, f64),
+        min: (f64, f64),
+        max: (f64, f64),
         color: [u8; 4],
         filled: bool,
         thickness: f64,
     ) {
-        let (x, y) = pos;
-        let (w, h) = size;
-        let x0 = x.round() as i32;
-        let y0 = y.round() as i32;
-        let x1 = (x + w).round() as i32;
-        let y1 = (y + h).round() as i32;
+        let x0 = min.0.round() as i32;
+        let y0 = min.1.round() as i32;
+        let x1 = max.0.round() as i32;
+        let y1 = max.1.round() as i32;
 
         if filled {
             for yy in y0..y1 {
@@ -404,6 +1361,264 @@ impl SoftwareFrame {
         }
     }
 
+    /// Scanline fill (pixel centres, even-odd rule) or closed outline through `points`.
+    fn draw_polygon(
+        &mut self,
+        points: &[(f64, f64)],
+        color: [u8; 4],
+        filled: bool,
+        thickness: f64,
+    ) {
+        if points.len() < 2 {
+            return;
+        }
+        if !filled {
+            for (i, &(x1, y1)) in points.iter().enumerate() {
+                let (x2, y2) = points[(i + 1) % points.len()];
+                self.draw_line(x1, y1, x2, y2, color, thickness);
+            }
+            return;
+        }
+
+        let min_y = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+        let max_y = points.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+        let y_start = min_y.floor().max(0.0) as i32;
+        let y_end = max_y.ceil().min(self.height as f64) as i32;
+        let mut crossings = Vec::with_capacity(points.len());
+        for py in y_start..y_end {
+            let sample_y = py as f64 + 0.5;
+            crossings.clear();
+            for (i, &(x1, y1)) in points.iter().enumerate() {
+                let (x2, y2) = points[(i + 1) % points.len()];
+                if (y1 <= sample_y) != (y2 <= sample_y) {
+                    crossings.push(x1 + (sample_y - y1) / (y2 - y1) * (x2 - x1));
+                }
+            }
+            crossings.sort_by(f64::total_cmp);
+            for span in crossings.chunks_exact(2) {
+                let x_start = (span[0] - 0.5).ceil().max(0.0) as i32;
+                let x_end = (span[1] - 0.5).ceil().min(self.width as f64) as i32;
+                for px in x_start..x_end {
+                    self.put_pixel(px, py, color);
+                }
+            }
+        }
+    }
+
+    /// Anti-aliased convex fill: coverage is the pixel centre's distance inside the nearest
+    /// edge plus half a pixel, like filled circles.
+    fn fill_convex(&mut self, points: &[(f64, f64)], color: [u8; 4]) {
+        let area: f64 = (0..points.len())
+            .map(|i| cross(points[i], points[(i + 1) % points.len()], (0.0, 0.0)))
+            .sum();
+        if area == 0.0 || !area.is_finite() {
+            return;
+        }
+        let winding = area.signum();
+        let edges: Vec<((f64, f64), (f64, f64))> = (0..points.len())
+            .filter_map(|i| {
+                let a = points[i];
+                let b = points[(i + 1) % points.len()];
+                let len = (b.0 - a.0).hypot(b.1 - a.1);
+                (len > 0.0).then(|| {
+                    (
+                        a,
+                        (winding * (b.0 - a.0) / len, winding * (b.1 - a.1) / len),
+                    )
+                })
+            })
+            .collect();
+
+        let min_x = points.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+        let max_x = points.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+        let min_y = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+        let max_y = points.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+        let x_range = (min_x - 1.0).floor().max(0.0) as i32
+            ..(max_x + 1.0).ceil().min(self.width as f64) as i32;
+        let y_range = (min_y - 1.0).floor().max(0.0) as i32
+            ..(max_y + 1.0).ceil().min(self.height as f64) as i32;
+
+        for py in y_range {
+            for px in x_range.clone() {
+                let sample = (px as f64 + 0.5, py as f64 + 0.5);
+                let inside = edges
+                    .iter()
+                    .map(|(a, dir)| dir.0 * (sample.1 - a.1) - dir.1 * (sample.0 - a.0))
+                    .fold(f64::INFINITY, f64::min);
+                let coverage = (inside + 0.5).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    self.blend_pixel(px, py, color, coverage);
+                }
+            }
+        }
+    }
+
+    /// Fills the triangle, passing barycentric weights to `shade` and edge coverage to `write`.
+    /// Edges flagged in `smooth` are anti-aliased like `fill_convex`; on the others a centre
+    /// exactly on the edge belongs to one side only, so triangles that share an edge never draw
+    /// it twice.
+    fn fill_triangle(
+        &mut self,
+        v: [(f64, f64); 3],
+        smooth: [bool; 3],
+        mut shade: impl FnMut([f64; 3]) -> [u8; 4],
+        write: fn(&mut Self, i32, i32, [u8; 4], f64),
+    ) {
+        let area = cross(v[0], v[1], v[2]);
+        if area == 0.0 || !area.is_finite() {
+            return;
+        }
+        let winding = area.signum();
+        // Edge i is opposite vertex i, so its edge function is that vertex's weight.
+        let edges = [(v[1], v[2]), (v[2], v[0]), (v[0], v[1])];
+        let owns = edges.map(|(a, b)| {
+            let (dx, dy) = (winding * (b.0 - a.0), winding * (b.1 - a.1));
+            dy > 0.0 || (dy == 0.0 && dx < 0.0)
+        });
+        let lengths = edges.map(|(a, b)| (b.0 - a.0).hypot(b.1 - a.1));
+        let pad = if smooth.contains(&true) { 1.0 } else { 0.0 };
+
+        let min_x = v.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+        let max_x = v.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+        let min_y = v.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+        let max_y = v.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+        let x_range = (min_x - pad).floor().max(0.0) as i32
+            ..(max_x + pad).ceil().min(self.width as f64) as i32;
+        let y_range = (min_y - pad).floor().max(0.0) as i32
+            ..(max_y + pad).ceil().min(self.height as f64) as i32;
+
+        for py in y_range {
+            'pixels: for px in x_range.clone() {
+                let sample = (px as f64 + 0.5, py as f64 + 0.5);
+                let mut weights = [0.0; 3];
+                let mut coverage = 1.0f64;
+                for i in 0..3 {
+                    let e = winding * cross(edges[i].0, edges[i].1, sample);
+                    if smooth[i] {
+                        coverage = coverage.min(e / lengths[i] + 0.5);
+                        if coverage <= 0.0 {
+                            continue 'pixels;
+                        }
+                    } else if e < 0.0 || (e == 0.0 && !owns[i]) {
+                        continue 'pixels;
+                    }
+                    weights[i] = e.max(0.0);
+                }
+                // Centres just outside a smooth edge shade as the nearest point on it.
+                let total: f64 = weights.iter().sum();
+                let color = shade(weights.map(|w| w / total));
+                write(self, px, py, color, coverage);
+            }
+        }
+    }
+
+    /// Inverse-maps every screen pixel covered by a visible, non-empty chunk back into map
+    /// space, so cost scales with on-screen area rather than map size.
+    fn draw_tilemap(
+        &mut self,
+        map: &Tilemap,
+        tileset: &Texture,
+        placement: &Affine2D,
+        tint: [u8; 4],
+    ) {
+        let Some(to_map) = placement.inverse() else {
+            return;
+        };
+        let (tile_w, tile_h) = map.tile_size();
+        let tiles_per_row = tileset.width / tile_w;
+        if tiles_per_row == 0 || tileset.height < tile_h {
+            return;
+        }
+        let (tile_w, tile_h) = (tile_w as f64, tile_h as f64);
+        let chunk_w = tile_w * TILEMAP_CHUNK as f64;
+        let chunk_h = tile_h * TILEMAP_CHUNK as f64;
+        let map_w = tile_w * map.columns() as f64;
+        let map_h = tile_h * map.rows() as f64;
+
+        // Chunks whose map-space bounds meet the visible screen area.
+        let screen = self.visible_rect();
+        let visible = bounding_box(
+            [
+                (screen.x0, screen.y0),
+                (screen.x1, screen.y0),
+                (screen.x0, screen.y1),
+                (screen.x1, screen.y1),
+            ]
+            .map(|(x, y)| to_map.apply(x as f64, y as f64)),
+        );
+        let chunk_range = |min: f64, max: f64, size: f64, count: u32| {
+            let first = (min / size).floor().max(0.0) as u32;
+            let last = ((max / size).ceil().max(0.0) as u32).min(count);
+            first..last
+        };
+
+        for cy in chunk_range(visible.1, visible.3, chunk_h, map.chunks_down()) {
+            for cx in chunk_range(visible.0, visible.2, chunk_w, map.chunks_across()) {
+                if !map.chunk_occupied(cx, cy) {
+                    continue;
+                }
+                let x0 = cx as f64 * chunk_w;
+                let y0 = cy as f64 * chunk_h;
+                let x1 = (x0 + chunk_w).min(map_w);
+                let y1 = (y0 + chunk_h).min(map_h);
+                let on_screen = bounding_box(
+                    [(x0, y0), (x1, y0), (x0, y1), (x1, y1)].map(|(x, y)| placement.apply(x, y)),
+                );
+                let px_range = on_screen.0.floor().max(screen.x0 as f64) as i32
+                    ..on_screen.2.ceil().min(screen.x1 as f64) as i32;
+                let py_range = on_screen.1.floor().max(screen.y0 as f64) as i32
+                    ..on_screen.3.ceil().min(screen.y1 as f64) as i32;
+
+                for py in py_range {
+                    for px in px_range.clone() {
+                        let (mx, my) = to_map.apply(px as f64 + 0.5, py as f64 + 0.5);
+                        // Half-open chunk bounds so neighbouring chunks never share a pixel.
+                        if mx < x0 || mx >= x1 || my < y0 || my >= y1 {
+                            continue;
+                        }
+                        let column = (mx / tile_w) as u32;
+                        let row = (my / tile_h) as u32;
+                        let Some(tile) = map.tile(column, row).filter(|t| *t >= 0) else {
+                            continue;
+                        };
+                        let tile = tile as u32;
+                        let u = ((mx - column as f64 * tile_w) as u32).min(tile_w as u32 - 1);
+                        let v = ((my - row as f64 * tile_h) as u32).min(tile_h as u32 - 1);
+                        let tx = (tile % tiles_per_row) * tile_w as u32 + u;
+                        let ty = (tile / tiles_per_row) * tile_h as u32 + v;
+                        if ty >= tileset.height {
+                            continue;
+                        }
+                        let i = ((ty * tileset.width + tx) * 4) as usize;
+                        let src = &tileset.pixels[i..i + 4];
+                        let color =
+                            [0, 1, 2, 3].map(|c| ((src[c] as u16 * tint[c] as u16) / 255) as u8);
+                        self.write_texel(px, py, color, 1.0);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The frame, narrowed to the active clip rect.
+    fn visible_rect(&self) -> PixelRect {
+        let full = PixelRect {
+            x0: 0,
+            y0: 0,
+            x1: self.width as i32,
+            y1: self.height as i32,
+        };
+        match self.clip {
+            Some(clip) => PixelRect {
+                x0: clip.x0.max(0),
+                y0: clip.y0.max(0),
+                x1: clip.x1.min(full.x1).max(clip.x0.max(0)),
+                y1: clip.y1.min(full.y1).max(clip.y0.max(0)),
+            },
+            None => full,
+        }
+    }
+
     fn draw_circle(
         &mut self,
         x: f64,
@@ -457,86 +1672,156 @@ impl SoftwareFrame {
         }
     }
 
-    fn draw_texture(&mut self, texture: &Texture, x: f64, y: f64, scale: f64, tint: [u8; 4]) {
-        let s = scale.max(0.01);
-        let out_w = ((texture.width as f64) * s).round().max(1.0) as u32;
-        let out_h = ((texture.height as f64) * s).round().max(1.0) as u32;
-        let x0 = x.round() as i32;
-        let y0 = y.round() as i32;
-
-        for oy in 0..out_h {
-            for ox in 0..out_w {
-                let sx = ((ox as f64 / out_w as f64) * texture.width as f64)
-                    .floor()
-                    .min((texture.width - 1) as f64) as u32;
-                let sy = ((oy as f64 / out_h as f64) * texture.height as f64)
-                    .floor()
-                    .min((texture.height - 1) as f64) as u32;
-                let src_i = ((sy * texture.width + sx) * 4) as usize;
-
-                let src = &texture.pixels[src_i..src_i + 4];
-                let color = [
-                    ((src[0] as u16 * tint[0] as u16) / 255) as u8,
-                    ((src[1] as u16 * tint[1] as u16) / 255) as u8,
-                    ((src[2] as u16 * tint[2] as u16) / 255) as u8,
-                    ((src[3] as u16 * tint[3] as u16) / 255) as u8,
-                ];
-
-                self.put_pixel(x0 + ox as i32, y0 + oy as i32, color);
+    fn draw_texture(&mut self, texture: &Texture, placement: &SpritePlacement, tint: [u8; 4]) {
+        let (sx, sy) = (placement.scale.0.max(0.01), placement.scale.1.max(0.01));
+        let (flip_x, flip_y) = placement.flip;
+        let texel = |u: u32, v: u32| {
+            let u = if flip_x { texture.width - 1 - u } else { u };
+            let v = if flip_y { texture.height - 1 - v } else { v };
+            let i = ((v * texture.width + u) * 4) as usize;
+            let src = &texture.pixels[i..i + 4];
+            [
+                ((src[0] as u16 * tint[0] as u16) / 255) as u8,
+                ((src[1] as u16 * tint[1] as u16) / 255) as u8,
+                ((src[2] as u16 * tint[2] as u16) / 255) as u8,
+                ((src[3] as u16 * tint[3] as u16) / 255) as u8,
+            ]
+        };
+
+        if placement.rotation == 0.0 {
+            // Unrotated sprites snap to whole destination pixels.
+            // Offsets are walked only across the visible part, so huge scales stay cheap.
+            let out_w = ((texture.width as f64) * sx).round().max(1.0) as u32;
+            let out_h = ((texture.height as f64) * sy).round().max(1.0) as u32;
+            let x0 = (placement.x - placement.origin.0 * out_w as f64).round() as i64;
+            let y0 = (placement.y - placement.origin.1 * out_h as f64).round() as i64;
+            let visible = self.visible_rect();
+            let ox_range =
+                (visible.x0 as i64 - x0).max(0)..(visible.x1 as i64 - x0).min(out_w as i64);
+            let oy_range =
+                (visible.y0 as i64 - y0).max(0)..(visible.y1 as i64 - y0).min(out_h as i64);
+
+            for oy in oy_range {
+                for ox in ox_range.clone() {
+                    let u = ((ox as f64 / out_w as f64) * texture.width as f64)
+                        .floor()
+                        .min((texture.width - 1) as f64) as u32;
+                    let v = ((oy as f64 / out_h as f64) * texture.height as f64)
+                        .floor()
+                        .min((texture.height - 1) as f64) as u32;
+                    self.write_texel((x0 + ox) as i32, (y0 + oy) as i32, texel(u, v), 1.0);
+                }
             }
+            return;
         }
-    }
-
-    fn draw_text_atlas(&mut self, text: &str, x: f64, y: f64, size: f64, color: [u8; 4]) {
-        let scale = (size / 8.0).max(1.0);
-        let mut pen_x = x;
-
-        for ch in text.chars() {
-            if ch == '\n' {
-                pen_x = x;
-                continue;
-            }
 
-            if let Some(glyph) = self.font.glyph(ch).cloned() {
-                self.blit_font_glyph(&glyph, pen_x, y, scale, color);
+        // Rotated sprites are inverse-mapped: each destination pixel centre inside the
+        // rotated bounds is taken back into texture space and sampled nearest-neighbour.
+        let (w, h) = (texture.width as f64 * sx, texture.height as f64 * sy);
+        let (pivot_x, pivot_y) = (placement.origin.0 * w, placement.origin.1 * h);
+        let (sin, cos) = placement.rotation.sin_cos();
+        let corners = [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)].map(|(lx, ly)| {
+            let (dx, dy) = (lx - pivot_x, ly - pivot_y);
+            (
+                placement.x + dx * cos - dy * sin,
+                placement.y + dx * sin + dy * cos,
+            )
+        });
+        let min_x = corners.iter().map(|c| c.0).fold(f64::INFINITY, f64::min);
+        let max_x = corners
+            .iter()
+            .map(|c| c.0)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let min_y = corners.iter().map(|c| c.1).fold(f64::INFINITY, f64::min);
+        let max_y = corners
+            .iter()
+            .map(|c| c.1)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let visible = self.visible_rect();
+        let x_start = min_x.floor().max(visible.x0 as f64) as i32;
+        let x_end = max_x.ceil().min(visible.x1 as f64) as i32;
+        let y_start = min_y.floor().max(visible.y0 as f64) as i32;
+        let y_end = max_y.ceil().min(visible.y1 as f64) as i32;
+
+        for py in y_start..y_end {
+            for px in x_start..x_end {
+                let dx = px as f64 + 0.5 - placement.x;
+                let dy = py as f64 + 0.5 - placement.y;
+                let lx = dx * cos + dy * sin + pivot_x;
+                let ly = -dx * sin + dy * cos + pivot_y;
+                if lx < 0.0 || ly < 0.0 || lx >= w || ly >= h {
+                    continue;
+                }
+                let u = ((lx / sx) as u32).min(texture.width - 1);
+                let v = ((ly / sy) as u32).min(texture.height - 1);
+                self.write_texel(px, py, texel(u, v), 1.0);
             }
-            pen_x += (8.0 * scale) + (1.0 * scale);
         }
     }
 
-    fn blit_font_glyph(&mut self, glyph: &FontGlyph, x: f64, y: f64, scale: f64, color: [u8; 4]) {
-        let x0 = x.round() as i32;
-        let y0 = y.round() as i32;
-        let out_w = ((glyph.w as f64) * scale).round().max(1.0) as u32;
-        let out_h = ((glyph.h as f64) * scale).round().max(1.0) as u32;
+    /// Writes a texture color, matched to the nearest palette entry in palette mode.
+    fn write_texel(&mut self, x: i32, y: i32, color: [u8; 4], coverage: f64) {
+        let color = match self.indexed.as_mut() {
+            Some(buffer) => [
+                buffer.quantize([color[0], color[1], color[2]]),
+                0,
+                0,
+                color[3],
+            ],
+            None => color,
+        };
+        self.write_color(x, y, color, coverage);
+    }
 
-        for oy in 0..out_h {
-            for ox in 0..out_w {
-                let gx = ((ox as f64 / out_w as f64) * glyph.w as f64)
-                    .floor()
-                    .min((glyph.w - 1) as f64) as u32;
-                let gy = ((oy as f64 / out_h as f64) * glyph.h as f64)
-                    .floor()
-                    .min((glyph.h - 1) as f64) as u32;
+    /// Opaque, fully covered colors overwrite, the rest blend and fully transparent ones are
+    /// skipped.
+    fn write_color(&mut self, x: i32, y: i32, color: [u8; 4], coverage: f64) {
+        match color[3] {
+            0 => {}
+            255 if coverage >= 1.0 => self.put_pixel(x, y, color),
+            _ => self.blend_pixel(x, y, color, coverage),
+        }
+    }
 
-                let alpha = self.font.alpha_at(glyph.u + gx, glyph.v + gy);
-                if alpha == 0 {
-                    continue;
+    fn draw_text(
+        &mut self,
+        font: &Font,
+        text: &str,
+        (x, y): (f64, f64),
+        size: f64,
+        layout: &TextLayout,
+        color: [u8; 4],
+    ) {
+        let scale = font.scale_for(size);
+        for placed in font.layout(text, x, y, scale, layout) {
+            let Some(quad) = font.glyph_quad(placed.ch, placed.x, placed.y, scale) else {
+                continue;
+            };
+            for oy in 0..quad.out_h {
+                for ox in 0..quad.out_w {
+                    let (px, py) = (quad.x0 + ox as i32, quad.y0 + oy as i32);
+                    match quad.coverage(ox, oy) {
+                        0 => {}
+                        255 => self.put_pixel(px, py, color),
+                        alpha => self.blend_pixel(px, py, color, alpha as f64 / 255.0),
+                    }
                 }
-
-                let blended = [
-                    ((color[0] as u16 * alpha as u16) / 255) as u8,
-                    ((color[1] as u16 * alpha as u16) / 255) as u8,
-                    ((color[2] as u16 * alpha as u16) / 255) as u8,
-                    ((color[3] as u16 * alpha as u16) / 255) as u8,
-                ];
-                self.put_pixel(x0 + ox as i32, y0 + oy as i32, blended);
             }
         }
     }
 
+    /// Writes an opaque-coverage pixel through the current blend mode.
     fn put_pixel(&mut self, x: i32, y: i32, color: [u8; 4]) {
-        if x < 0 || y < 0 {
+        match self.blend {
+            _ if self.indexed.is_some() => self.write_pixel(x, y, color),
+            BlendMode::Alpha => self.write_pixel(x, y, color),
+            _ => self.blend_pixel(x, y, color, 1.0),
+        }
+    }
+
+    fn write_pixel(&mut self, x: i32, y: i32, color: [u8; 4]) {
+        if x < 0 || y < 0 || self.clip.is_some_and(|clip| !clip.contains(x, y)) {
             return;
         }
         let x = x as u32;
@@ -545,12 +1830,17 @@ impl SoftwareFrame {
             return;
         }
 
-        let idx = ((y as usize) * (self.width as usize) + (x as usize)) * 4;
+        let pixel = (y as usize) * (self.width as usize) + (x as usize);
+        if let Some(buffer) = self.indexed.as_mut() {
+            buffer.indices[pixel] = color[0];
+            return;
+        }
+        let idx = pixel * 4;
         self.pixels[idx..idx + 4].copy_from_slice(&color);
     }
 
     fn blend_pixel(&mut self, x: i32, y: i32, color: [u8; 4], coverage: f64) {
-        if x < 0 || y < 0 {
+        if x < 0 || y < 0 || self.clip.is_some_and(|clip| !clip.contains(x, y)) {
             return;
         }
         let x = x as u32;
@@ -560,6 +1850,13 @@ impl SoftwareFrame {
         }
 
         let src_alpha = ((color[3] as f64 / 255.0) * coverage).clamp(0.0, 1.0);
+        if let Some(buffer) = self.indexed.as_mut() {
+            // Indices cannot be mixed, so coverage picks the source or keeps the destination.
+            if src_alpha >= 0.5 {
+                buffer.indices[(y as usize) * (self.width as usize) + (x as usize)] = color[0];
+            }
+            return;
+        }
         if src_alpha <= 0.0 {
             return;
         }
@@ -580,9 +1877,45 @@ impl SoftwareFrame {
         let src_g = color[1] as f64 / 255.0;
         let src_b = color[2] as f64 / 255.0;
 
-        let out_r = ((src_r * src_alpha) + (dst_r * dst_a * (1.0 - src_alpha))) / out_a;
-        let out_g = ((src_g * src_alpha) + (dst_g * dst_a * (1.0 - src_alpha))) / out_a;
-        let out_b = ((src_b * src_alpha) + (dst_b * dst_a * (1.0 - src_alpha))) / out_a;
+        let (out_r, out_g, out_b, out_a) = match self.blend {
+            BlendMode::Alpha => (
+                ((src_r * src_alpha) + (dst_r * dst_a * (1.0 - src_alpha))) / out_a,
+                ((src_g * src_alpha) + (dst_g * dst_a * (1.0 - src_alpha))) / out_a,
+                ((src_b * src_alpha) + (dst_b * dst_a * (1.0 - src_alpha))) / out_a,
+                out_a,
+            ),
+            BlendMode::Add => {
+                // Premultiplied sum; channels saturate once the total passes the alpha.
+                let out_a = (src_alpha + dst_a).min(1.0);
+                let add = |src: f64, dst: f64| ((src * src_alpha) + (dst * dst_a)) / out_a;
+                (
+                    add(src_r, dst_r),
+                    add(src_g, dst_g),
+                    add(src_b, dst_b),
+                    out_a,
+                )
+            }
+            BlendMode::Multiply | BlendMode::Screen => {
+                // Separable blend: mix the source with B(dst, src) by the destination's alpha,
+                // then composite source-over.
+                let mode = self.blend;
+                let mix = |src: f64, dst: f64| {
+                    let blended = if mode == BlendMode::Multiply {
+                        src * dst
+                    } else {
+                        src + dst - src * dst
+                    };
+                    let src = (1.0 - dst_a) * src + dst_a * blended;
+                    ((src * src_alpha) + (dst * dst_a * (1.0 - src_alpha))) / out_a
+                };
+                (
+                    mix(src_r, dst_r),
+                    mix(src_g, dst_g),
+                    mix(src_b, dst_b),
+                    out_a,
+                )
+            }
+        };
 
         self.pixels[idx] = (out_r * 255.0).clamp(0.0, 255.0) as u8;
         self.pixels[idx + 1] = (out_g * 255.0).clamp(0.0, 255.0) as u8;
@@ -616,48 +1949,277 @@ fn validate_command(command: &DrawCommand) -> Result<(), RenderValidationError>
             }
             if !filled && *thickness <= 0.0 {
                 return Err(RenderValidationError::InvalidCommand(
-                    "outlined rect thickness must be > 0".to_owned(),
+                    "outlined rect thickness must be > 0".to_owned(),
+                ));
+            }
+            Ok(())
+        }
+        DrawCommand::Circle {
+            r,
+            filled,
+            thickness,
+            ..
+        } => {
+            if *r <= 0.0 {
+                return Err(RenderValidationError::InvalidCommand(
+                    "circle radius must be > 0".to_owned(),
+                ));
+            }
+            if !filled && *thickness <= 0.0 {
+                return Err(RenderValidationError::InvalidCommand(
+                    "outlined circle thickness must be > 0".to_owned(),
+                ));
+            }
+            Ok(())
+        }
+        DrawCommand::Sprite {
+            asset_id,
+            x,
+            y,
+            rotation,
+            scale_x,
+            scale_y,
+            origin_x,
+            origin_y,
+            ..
+        } => {
+            if asset_id.trim().is_empty() {
+                return Err(RenderValidationError::InvalidCommand(
+                    "sprite asset_id must be non-empty".to_owned(),
+                ));
+            }
+            if !(*scale_x > 0.0 && *scale_y > 0.0 && scale_x.is_finite() && scale_y.is_finite()) {
+                return Err(RenderValidationError::InvalidCommand(
+                    "sprite scale must be > 0".to_owned(),
+                ));
+            }
+            if !(x.is_finite() && y.is_finite()) {
+                return Err(RenderValidationError::InvalidCommand(
+                    "sprite position must be finite".to_owned(),
+                ));
+            }
+            if !rotation.is_finite() || !origin_x.is_finite() || !origin_y.is_finite() {
+                return Err(RenderValidationError::InvalidCommand(
+                    "sprite rotation and origin must be finite".to_owned(),
+                ));
+            }
+            Ok(())
+        }
+        DrawCommand::Text {
+            size,
+            font,
+            max_width,
+            line_spacing,
+            ..
+        } => {
+            if *size <= 0.0 {
+                return Err(RenderValidationError::InvalidCommand(
+                    "text size must be > 0".to_owned(),
+                ));
+            }
+            if max_width.is_some_and(|w| !(w > 0.0 && w.is_finite())) {
+                return Err(RenderValidationError::InvalidCommand(
+                    "text max_width must be > 0".to_owned(),
+                ));
+            }
+            if !(*line_spacing >= 0.0 && line_spacing.is_finite()) {
+                return Err(RenderValidationError::InvalidCommand(
+                    "text line_spacing must be >= 0".to_owned(),
+                ));
+            }
+            if font.as_deref() == Some("") {
+                return Err(RenderValidationError::InvalidCommand(
+                    "text font id must not be empty".to_owned(),
+                ));
+            }
+            Ok(())
+        }
+        DrawCommand::Camera {
+            x,
+            y,
+            zoom,
+            rotation,
+        } => {
+            if !(*zoom > 0.0 && zoom.is_finite()) {
+                return Err(RenderValidationError::InvalidCommand(
+                    "camera zoom must be > 0".to_owned(),
+                ));
+            }
+            if !(x.is_finite() && y.is_finite() && rotation.is_finite()) {
+                return Err(RenderValidationError::InvalidCommand(
+                    "camera position and rotation must be finite".to_owned(),
+                ));
+            }
+            Ok(())
+        }
+        DrawCommand::Polygon {
+            points,
+            filled,
+            thickness,
+            ..
+        } => {
+            if points.len() < 3 || points.len() > MAX_POLYGON_POINTS {
+                return Err(RenderValidationError::InvalidCommand(format!(
+                    "polygon needs 3..={MAX_POLYGON_POINTS} points, got {}",
+                    points.len()
+                )));
+            }
+            if !points.iter().flatten().all(|v| v.is_finite()) {
+                return Err(RenderValidationError::InvalidCommand(
+                    "polygon points must be finite".to_owned(),
+                ));
+            }
+            if !is_convex(points) {
+                return Err(RenderValidationError::InvalidCommand(
+                    "polygon must be convex".to_owned(),
+                ));
+            }
+            if !filled && *thickness <= 0.0 {
+                return Err(RenderValidationError::InvalidCommand(
+                    "outlined polygon thickness must be > 0".to_owned(),
+                ));
+            }
+            Ok(())
+        }
+        DrawCommand::Triangles { vertices, colors } => {
+            if vertices.is_empty()
+                || !vertices.len().is_multiple_of(3)
+                || vertices.len() > MAX_TRIANGLE_VERTICES
+            {
+                return Err(RenderValidationError::InvalidCommand(format!(
+                    "triangle list needs a non-zero multiple of 3 vertices up to \
+                     {MAX_TRIANGLE_VERTICES}, got {}",
+                    vertices.len()
+                )));
+            }
+            if colors.len() != vertices.len() {
+                return Err(RenderValidationError::InvalidCommand(format!(
+                    "triangle list has {} vertices but {} colors",
+                    vertices.len(),
+                    colors.len()
+                )));
+            }
+            if !vertices.iter().flatten().all(|v| v.is_finite()) {
+                return Err(RenderValidationError::InvalidCommand(
+                    "triangle vertices must be finite".to_owned(),
+                ));
+            }
+            Ok(())
+        }
+        DrawCommand::TexturedQuad {
+            asset_id,
+            points,
+            uvs,
+            ..
+        } => {
+            if asset_id.trim().is_empty() {
+                return Err(RenderValidationError::InvalidCommand(
+                    "textured quad asset_id must be non-empty".to_owned(),
+                ));
+            }
+            if !points.iter().chain(uvs).flatten().all(|v| v.is_finite()) {
+                return Err(RenderValidationError::InvalidCommand(
+                    "textured quad points and uvs must be finite".to_owned(),
+                ));
+            }
+            Ok(())
+        }
+        DrawCommand::Tilemap { tilemap, x, y, .. } => {
+            if tilemap.trim().is_empty() {
+                return Err(RenderValidationError::InvalidCommand(
+                    "tilemap name must be non-empty".to_owned(),
+                ));
+            }
+            if !(x.is_finite() && y.is_finite()) {
+                return Err(RenderValidationError::InvalidCommand(
+                    "tilemap position must be finite".to_owned(),
+                ));
+            }
+            Ok(())
+        }
+        DrawCommand::PushTransform
+        | DrawCommand::PopTransform
+        | DrawCommand::Layer { .. }
+        | DrawCommand::Blend { .. }
+        | DrawCommand::ResetPalette
+        | DrawCommand::ResetClip => Ok(()),
+        DrawCommand::SetPalette { colors } => {
+            if !(1..=MAX_PALETTE_COLORS).contains(&colors.len()) {
+                return Err(RenderValidationError::InvalidCommand(format!(
+                    "palette must have 1..={MAX_PALETTE_COLORS} colors"
+                )));
+            }
+            Ok(())
+        }
+        DrawCommand::CreateTarget {
+            name,
+            width,
+            height,
+        } => {
+            if name.is_empty() {
+                return Err(RenderValidationError::InvalidCommand(
+                    "render target name must not be empty".to_owned(),
+                ));
+            }
+            if !(1..=MAX_RENDER_TARGET_SIZE).contains(width)
+                || !(1..=MAX_RENDER_TARGET_SIZE).contains(height)
+            {
+                return Err(RenderValidationError::InvalidCommand(format!(
+                    "render target size must be 1..={MAX_RENDER_TARGET_SIZE} per side"
+                )));
+            }
+            if *width as usize * *height as usize * 4 > MAX_RENDER_TARGET_BYTES {
+                return Err(RenderValidationError::InvalidCommand(format!(
+                    "render target {width}x{height} exceeds {MAX_RENDER_TARGET_BYTES} bytes"
+                )));
+            }
+            Ok(())
+        }
+        DrawCommand::SetTarget { name } => {
+            if name.as_deref() == Some("") {
+                return Err(RenderValidationError::InvalidCommand(
+                    "render target name must not be empty".to_owned(),
                 ));
             }
             Ok(())
         }
-        DrawCommand::Circle {
-            r,
-            filled,
-            thickness,
-            ..
-        } => {
-            if *r <= 0.0 {
+        DrawCommand::ReleaseTarget { name } => {
+            if name.is_empty() {
                 return Err(RenderValidationError::InvalidCommand(
-                    "circle radius must be > 0".to_owned(),
+                    "render target name must not be empty".to_owned(),
                 ));
             }
-            if !filled && *thickness <= 0.0 {
+            Ok(())
+        }
+        DrawCommand::SetClip { x, y, w, h } => {
+            let finite = x.is_finite() && y.is_finite() && w.is_finite() && h.is_finite();
+            if !(finite && *w >= 0.0 && *h >= 0.0) {
                 return Err(RenderValidationError::InvalidCommand(
-                    "outlined circle thickness must be > 0".to_owned(),
+                    "clip rect must be finite with w, h >= 0".to_owned(),
                 ));
             }
             Ok(())
         }
-        DrawCommand::Sprite {
-            asset_id, scale, ..
-        } => {
-            if asset_id.trim().is_empty() {
+        DrawCommand::Translate { x, y } => {
+            if !(x.is_finite() && y.is_finite()) {
                 return Err(RenderValidationError::InvalidCommand(
-                    "sprite asset_id must be non-empty".to_owned(),
+                    "translate offsets must be finite".to_owned(),
                 ));
             }
-            if *scale <= 0.0 {
+            Ok(())
+        }
+        DrawCommand::Scale { x, y } => {
+            if !(x.is_finite() && y.is_finite() && *x != 0.0 && *y != 0.0) {
                 return Err(RenderValidationError::InvalidCommand(
-                    "sprite scale must be > 0".to_owned(),
+                    "scale factors must be finite and non-zero".to_owned(),
                 ));
             }
             Ok(())
         }
-        DrawCommand::Text { size, .. } => {
-            if *size <= 0.0 {
+        DrawCommand::Rotate { rotation } => {
+            if !rotation.is_finite() {
                 return Err(RenderValidationError::InvalidCommand(
-                    "text size must be > 0".to_owned(),
+                    "rotate angle must be finite".to_owned(),
                 ));
             }
             Ok(())
@@ -665,6 +2227,112 @@ fn validate_command(command: &DrawCommand) -> Result<(), RenderValidationError>
     }
 }
 
+/// `(min_x, min_y, max_x, max_y)` of `points`.
+fn bounding_box(points: [(f64, f64); 4]) -> (f64, f64, f64, f64) {
+    points.iter().fold(
+        (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |(x0, y0, x1, y1), p| (x0.min(p.0), y0.min(p.1), x1.max(p.0), y1.max(p.1)),
+    )
+}
+
+/// Twice the signed area of triangle `a, b, p`.
+fn cross(a: (f64, f64), b: (f64, f64), p: (f64, f64)) -> f64 {
+    (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+}
+
+/// Every turn bends the same way (collinear points allowed) and the outline goes around once.
+fn is_convex(points: &[[f64; 2]]) -> bool {
+    let n = points.len();
+    let mut turn_sign = 0.0;
+    for i in 0..n {
+        let [ax, ay] = points[i];
+        let [bx, by] = points[(i + 1) % n];
+        let [cx, cy] = points[(i + 2) % n];
+        let turn = cross((ax, ay), (bx, by), (cx, cy));
+        if turn != 0.0 {
+            if turn_sign != 0.0 && turn.signum() != turn_sign {
+                return false;
+            }
+            turn_sign = turn.signum();
+        }
+    }
+    // Consistent turns alone still admit star polygons that wind twice; a convex outline
+    // reverses horizontal direction at most twice.
+    let dxs: Vec<f64> = (0..n)
+        .map(|i| points[(i + 1) % n][0] - points[i][0])
+        .filter(|dx| *dx != 0.0)
+        .collect();
+    let flips = (0..dxs.len())
+        .filter(|&i| dxs[i].signum() != dxs[(i + 1) % dxs.len()].signum())
+        .count();
+    turn_sign != 0.0 && flips <= 2
+}
+
+fn resolve_texture<'a>(
+    id: &str,
+    assets: Option<&'a AssetStore>,
+    targets: &'a BTreeMap<String, Texture>,
+) -> Option<&'a Texture> {
+    match id.strip_prefix(RENDER_TARGET_PREFIX) {
+        Some(name) => targets.get(name),
+        None => assets.and_then(|a| a.texture(id)),
+    }
+}
+
+fn target_bytes(targets: &BTreeMap<String, Texture>) -> usize {
+    targets.values().map(|t| t.pixels.len()).sum()
+}
+
+/// Creates or resizes `name`, returning `false` when that would exceed the memory budget.
+fn create_target(
+    targets: &mut BTreeMap<String, Texture>,
+    name: &str,
+    width: u32,
+    height: u32,
+) -> bool {
+    let existing = targets.get(name);
+    if existing.is_some_and(|t| t.width == width && t.height == height) {
+        return true;
+    }
+    let bytes = width as usize * height as usize * 4;
+    let freed = existing.map_or(0, |t| t.pixels.len());
+    if target_bytes(targets) - freed + bytes > MAX_RENDER_TARGET_BYTES {
+        return false;
+    }
+    targets.insert(
+        name.to_owned(),
+        Texture {
+            width,
+            height,
+            pixels: vec![0; bytes],
+        },
+    );
+    true
+}
+
+fn load_ttf(path: &Path, bytes: &[u8]) -> Result<Font, String> {
+    let file_name = path.file_stem().and_then(|n| n.to_str()).unwrap_or("");
+    let sidecar = path.with_file_name(format!("{file_name}{FONT_SUFFIX}"));
+    let settings = match std::fs::read_to_string(&sidecar) {
+        Ok(text) => toml::from_str::<FontFile>(&text)
+            .map_err(|err| format!("{}: {err}", sidecar.display()))?,
+        Err(_) => FontFile {
+            size: None,
+            chars: String::new(),
+        },
+    };
+    Font::from_ttf(
+        bytes,
+        settings.size.unwrap_or(DEFAULT_TTF_SIZE),
+        &settings.chars,
+    )
+}
+
 fn parse_ppm_p6(bytes: &[u8]) -> Result<Texture, String> {
     let mut i = 0usize;
     let magic = next_token(bytes, &mut i).ok_or("missing magic")?;
@@ -761,7 +2429,19 @@ pub enum AssetLoadError {
     ReadFile(PathBuf, std::io::Error),
     #[error("invalid ppm texture {0}: {1}")]
     InvalidPpm(PathBuf, String),
-    #[error("invalid asset id for texture file {0}")]
+    #[error("invalid png texture {0}: {1}")]
+    InvalidPng(PathBuf, String),
+    #[error("invalid sprite atlas {0}: {1}")]
+    InvalidAtlas(PathBuf, String),
+    #[error("texture {0} reuses asset id `{1}` already taken by another image")]
+    DuplicateTexture(PathBuf, String),
+    #[error("invalid font {0}: {1}")]
+    InvalidFont(PathBuf, String),
+    #[error("font {0} reuses font id `{1}` already taken by another font")]
+    DuplicateFont(PathBuf, String),
+    #[error("invalid wav clip {0}: {1}")]
+    InvalidWav(PathBuf, String),
+    #[error("invalid asset id for asset file {0}")]
     InvalidAssetId(PathBuf),
 }
 
@@ -769,7 +2449,11 @@ pub enum AssetLoadError {
 mod tests {
     use std::fs;
 
-    use super::{AssetStore, DrawCommand, FrameCommandBuffer, SoftwareFrame};
+    use super::{AssetStore, BlendMode, DrawCommand, FrameCommandBuffer, SoftwareFrame, Texture};
+    use crate::audio::encode_wav_pcm16;
+    use crate::font::TextAlign;
+    use crate::png::encode_png_rgba;
+    use crate::tilemap::{Tilemap, TilemapStore};
 
     #[test]
     fn accepts_valid_rect() {
@@ -852,6 +2536,10 @@ mod tests {
                 x: 1.0,
                 y: 1.0,
                 size: 8.0,
+                font: None,
+                max_width: None,
+                align: TextAlign::Left,
+                line_spacing: 1.0,
                 color: [0, 255, 0, 255],
             })
             .expect("text should validate");
@@ -869,6 +2557,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn indexes_wav_clips_alongside_textures() {
+        let dir = std::env::temp_dir().join("vcon-render-audio-asset-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("temp dir should be created");
+        fs::write(dir.join("hero.ppm"), b"P6\n1 1\n255\n\xff\x00\x00").expect("ppm write");
+        fs::write(dir.join("jump.wav"), encode_wav_pcm16(&[0.5, 0.5], 22_050)).expect("wav write");
+        fs::write(dir.join("notes.txt"), b"ignored").expect("txt write");
+
+        let assets = AssetStore::load_from_dir(&dir).expect("asset store should load");
+        assert_eq!(assets.texture_ids(), vec!["hero"]);
+        assert_eq!(assets.audio_clips().ids(), vec!["jump"]);
+        assert_eq!(assets.clip("jump").map(|c| c.sample_rate), Some(22_050));
+        assert!(assets.clip("hero").is_none());
+
+        fs::write(dir.join("broken.wav"), b"RIFF").expect("wav write");
+        let err = AssetStore::load_from_dir(&dir).expect_err("broken wav should fail");
+        assert!(err.to_string().contains("invalid wav clip"), "{err}");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn loads_texture_asset_and_renders_sprite() {
         let dir = std::env::temp_dir().join("vcon-render-asset-test");
@@ -896,7 +2606,12 @@ mod tests {
                 x: 0.0,
                 y: 0.0,
                 rotation: 0.0,
-                scale: 1.0,
+                scale_x: 1.0,
+                scale_y: 1.0,
+                flip_x: false,
+                flip_y: false,
+                origin_x: 0.0,
+                origin_y: 0.0,
                 color: [255, 255, 255, 255],
             })
             .expect("sprite should validate");
@@ -911,4 +2626,962 @@ mod tests {
 
         let _ = fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn draws_text_with_bmfont_asset() {
+        let dir = std::env::temp_dir().join("vcon-render-bmfont-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("temp dir should be created");
+
+        // One 2x2 glyph `I`: left column opaque white, right column transparent.
+        let rgba = [[255, 255, 255, 255], [0, 0, 0, 0]].concat().repeat(2);
+        fs::write(dir.join("tiny_0.png"), encode_png_rgba(2, 2, &rgba)).expect("png write");
+        fs::write(
+            dir.join("tiny.fnt"),
+            "info face=\"tiny\" size=2\ncommon lineHeight=3 base=2 pages=1\n\
+             page id=0 file=\"tiny_0.png\"\n\
+             char id=73 x=0 y=0 width=2 height=2 xoffset=0 yoffset=1 xadvance=2 page=0\n",
+        )
+        .expect("fnt write");
+        let assets = AssetStore::load_from_dir(&dir).expect("asset store should load");
+        assert_eq!(assets.font_ids(), vec!["tiny"]);
+
+        let mut commands = FrameCommandBuffer::default();
+        commands
+            .push(DrawCommand::Clear {
+                color: [0, 0, 0, 255],
+            })
+            .expect("clear should validate");
+        for font in [Some("tiny"), Some("missing")] {
+            commands
+                .push(DrawCommand::Text {
+                    value: "II".to_owned(),
+                    x: 0.0,
+                    y: 0.0,
+                    size: 2.0,
+                    font: font.map(str::to_owned),
+                    max_width: None,
+                    align: TextAlign::Left,
+                    line_spacing: 1.0,
+                    color: [255, 0, 0, 255],
+                })
+                .expect("text should validate");
+        }
+        let mut frame = SoftwareFrame::new(4, 3);
+        let stats = frame.apply_with_assets(&commands, Some(&assets));
+        assert_eq!(stats.commands_unsupported, 1);
+        let red: Vec<bool> = frame.pixels().chunks(4).map(|px| px[0] == 255).collect();
+        assert_eq!(
+            red,
+            [
+                [false, false, false, false],
+                [true, false, true, false],
+                [true, false, true, false]
+            ]
+            .concat()
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn renders_into_targets_and_samples_them_as_sprites() {
+        let sprite = |asset_id: &str, x: f64| DrawCommand::Sprite {
+            asset_id: asset_id.to_owned(),
+            x,
+            y: 0.0,
+            rotation: 0.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            flip_x: false,
+            flip_y: false,
+            origin_x: 0.0,
+            origin_y: 0.0,
+            color: [255, 255, 255, 255],
+        };
+        let red = [255, 0, 0, 255];
+        let mut buffer = FrameCommandBuffer::default();
+        for command in [
+            DrawCommand::Clear {
+                color: [0, 0, 0, 255],
+            },
+            DrawCommand::CreateTarget {
+                name: "mini".to_owned(),
+                width: 2,
+                height: 1,
+            },
+            DrawCommand::SetTarget {
+                name: Some("mini".to_owned()),
+            },
+            DrawCommand::Camera {
+                x: 2.0,
+                y: 0.5,
+                zoom: 1.0,
+                rotation: 0.0,
+            },
+            // The camera centres on the 2x1 target, not the screen, so world x 1 is its pixel 0.
+            DrawCommand::Rect {
+                x: 1.0,
+                y: 0.0,
+                w: 1.0,
+                h: 1.0,
+                color: red,
+                filled: true,
+                thickness: 1.0,
+            },
+            // A target cannot sample itself while it is being drawn to.
+            sprite("target:mini", 0.0),
+            DrawCommand::SetTarget { name: None },
+            sprite("target:mini", 1.0),
+            DrawCommand::ReleaseTarget {
+                name: "mini".to_owned(),
+            },
+        ] {
+            buffer.push(command).expect("command should validate");
+        }
+
+        let mut frame = SoftwareFrame::new(4, 1);
+        let stats = frame.apply(&buffer);
+        assert_eq!(stats.commands_unsupported, 1);
+        assert_eq!(&frame.pixels()[4..8], &red);
+        // The target's transparent right pixel leaves the screen untouched.
+        assert_eq!(&frame.pixels()[8..12], &[0, 0, 0, 255]);
+        assert!(frame.render_target("mini").is_none());
+        assert_eq!(frame.render_target_bytes(), 0);
+
+        // Four 1024x1024 targets fill the budget, so a fifth is refused.
+        let mut buffer = FrameCommandBuffer::default();
+        for index in 0..5 {
+            buffer
+                .push(DrawCommand::CreateTarget {
+                    name: format!("big{index}"),
+                    width: 1024,
+                    height: 1024,
+                })
+                .expect("target should validate");
+        }
+        buffer
+            .push(DrawCommand::SetTarget {
+                name: Some("big4".to_owned()),
+            })
+            .expect("set target should validate");
+        let stats = frame.apply(&buffer);
+        assert_eq!(stats.commands_unsupported, 2);
+        assert_eq!(frame.render_target_bytes(), super::MAX_RENDER_TARGET_BYTES);
+        assert!(frame.render_target("big3").is_some() && frame.render_target("big4").is_none());
+
+        // Screen layers sort across a target pass, and the screen samples the finished target.
+        let blue = [0, 0, 255, 255];
+        let mut buffer = FrameCommandBuffer::default();
+        for command in [
+            DrawCommand::Layer { layer: 2 },
+            filled_rect(0.0, 0.0, 2.0, 1.0),
+            DrawCommand::CreateTarget {
+                name: "mini".to_owned(),
+                width: 1,
+                height: 1,
+            },
+            DrawCommand::SetTarget {
+                name: Some("mini".to_owned()),
+            },
+            DrawCommand::Clear { color: blue },
+            DrawCommand::SetTarget { name: None },
+            DrawCommand::Layer { layer: 1 },
+            DrawCommand::Clear { color: red },
+            DrawCommand::Layer { layer: 3 },
+            sprite("target:mini", 1.0),
+        ] {
+            buffer.push(command).expect("command should validate");
+        }
+        let mut frame = SoftwareFrame::new(3, 1);
+        frame.apply(&buffer);
+        let px: Vec<&[u8]> = frame.pixels().chunks(4).collect();
+        assert_eq!(px, [&[255, 255, 255, 255][..], &blue, &red]);
+
+        assert!(FrameCommandBuffer::default()
+            .push(DrawCommand::CreateTarget {
+                name: "huge".to_owned(),
+                width: 4096,
+                height: 4096,
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn blend_modes_combine_with_destination() {
+        let rect = |x: f64, color: [u8; 4]| DrawCommand::Rect {
+            x,
+            y: 0.0,
+            w: 1.0,
+            h: 1.0,
+            color,
+            filled: true,
+            thickness: 1.0,
+        };
+        let source = [100, 50, 200, 255];
+        let mut buffer = FrameCommandBuffer::default();
+        let mut commands = vec![
+            DrawCommand::Blend {
+                mode: BlendMode::Add,
+            },
+            // Clear ignores the blend mode.
+            DrawCommand::Clear {
+                color: [100, 100, 100, 255],
+            },
+        ];
+        for (x, mode) in [BlendMode::Add, BlendMode::Multiply, BlendMode::Screen]
+            .into_iter()
+            .enumerate()
+        {
+            commands.push(DrawCommand::Blend { mode });
+            commands.push(rect(x as f64, source));
+        }
+        commands.push(DrawCommand::Blend {
+            mode: BlendMode::Alpha,
+        });
+        commands.push(rect(3.0, source));
+        for command in commands {
+            buffer.push(command).expect("command should validate");
+        }
+
+        let mut frame = SoftwareFrame::new(4, 1);
+        frame.apply(&buffer);
+        let px: Vec<&[u8]> = frame.pixels().chunks(4).collect();
+        assert_eq!(px[0], &[200, 150, 255, 255]);
+        assert_eq!(px[1], &[39, 19, 78, 255]);
+        assert_eq!(px[2], &[160, 130, 221, 255]);
+        assert_eq!(px[3], &source);
+
+        // Adding onto a transparent surface keeps the source color at the source alpha.
+        let mut buffer = FrameCommandBuffer::default();
+        buffer
+            .push(DrawCommand::Blend {
+                mode: BlendMode::Add,
+            })
+            .expect("blend should validate");
+        buffer
+            .push(rect(0.0, [255, 0, 0, 128]))
+            .expect("rect should validate");
+        let mut frame = SoftwareFrame::new(1, 1);
+        frame.apply(&buffer);
+        assert_eq!(frame.pixels(), &[255, 0, 0, 128]);
+        assert_eq!(
+            BlendMode::parse("screen").map(BlendMode::as_str),
+            Some("screen")
+        );
+    }
+
+    #[test]
+    fn palette_mode_draws_indices_and_recolors_on_swap() {
+        let rect = |x: f64, color: [u8; 4]| DrawCommand::Rect {
+            x,
+            y: 0.0,
+            w: 1.0,
+            h: 1.0,
+            color,
+            filled: true,
+            thickness: 1.0,
+        };
+        let palette = vec![
+            [0, 0, 0, 255],
+            [255, 0, 0, 255],
+            [0, 255, 0, 255],
+            [0, 0, 255, 255],
+        ];
+        let frame_with = |commands: Vec<DrawCommand>| {
+            let mut buffer = FrameCommandBuffer::default();
+            for command in commands {
+                buffer.push(command).expect("command should validate");
+            }
+            buffer
+        };
+        let mut frame = SoftwareFrame::new(4, 1);
+        frame.apply(&frame_with(vec![
+            DrawCommand::SetPalette {
+                colors: palette.clone(),
+            },
+            DrawCommand::Clear {
+                color: [0, 0, 0, 255],
+            },
+            rect(1.0, [1, 0, 0, 255]),
+            DrawCommand::CreateTarget {
+                name: "swatch".to_owned(),
+                width: 1,
+                height: 1,
+            },
+            DrawCommand::SetTarget {
+                name: Some("swatch".to_owned()),
+            },
+            DrawCommand::Clear {
+                color: [2, 0, 0, 255],
+            },
+            DrawCommand::SetTarget { name: None },
+            // Texels are matched back to the nearest palette entry.
+            DrawCommand::Sprite {
+                asset_id: "target:swatch".to_owned(),
+                x: 3.0,
+                y: 0.0,
+                rotation: 0.0,
+                scale_x: 1.0,
+                scale_y: 1.0,
+                flip_x: false,
+                flip_y: false,
+                origin_x: 0.0,
+                origin_y: 0.0,
+                color: [255, 255, 255, 255],
+            },
+        ]));
+        let px: Vec<&[u8]> = frame.pixels().chunks(4).collect();
+        assert_eq!(px, [&palette[0], &palette[1], &palette[0], &palette[2]]);
+
+        // Indices persist, so swapping entries recolors without redrawing.
+        let mut swapped = palette.clone();
+        swapped.swap(1, 2);
+        frame.apply(&frame_with(vec![DrawCommand::SetPalette {
+            colors: swapped,
+        }]));
+        let px: Vec<&[u8]> = frame.pixels().chunks(4).collect();
+        assert_eq!(px, [&palette[0], &palette[2], &palette[0], &palette[1]]);
+
+        // Switching modes after the frame has drawn would reinterpret those draws, so it is
+        // refused in either direction.
+        let stats = frame.apply(&frame_with(vec![
+            rect(0.0, [1, 0, 0, 255]),
+            DrawCommand::ResetPalette,
+        ]));
+        assert_eq!(stats.commands_unsupported, 1);
+        let px: Vec<&[u8]> = frame.pixels().chunks(4).collect();
+        assert_eq!(px, [&palette[2], &palette[2], &palette[0], &palette[1]]);
+
+        frame.apply(&frame_with(vec![
+            DrawCommand::ResetPalette,
+            rect(0.0, [10, 20, 30, 255]),
+        ]));
+        assert_eq!(&frame.pixels()[..8], &[10, 20, 30, 255, 0, 255, 0, 255]);
+
+        let stats = frame.apply(&frame_with(vec![
+            rect(0.0, [40, 50, 60, 255]),
+            DrawCommand::SetPalette {
+                colors: palette.clone(),
+            },
+        ]));
+        assert_eq!(stats.commands_unsupported, 1);
+        assert_eq!(&frame.pixels()[..4], &[40, 50, 60, 255]);
+
+        let mut buffer = FrameCommandBuffer::default();
+        assert!(buffer
+            .push(DrawCommand::SetPalette { colors: Vec::new() })
+            .is_err());
+    }
+
+    #[test]
+    fn slices_png_sprite_sheet_into_named_sprites() {
+        let dir = std::env::temp_dir().join("vcon-render-atlas-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("temp dir should be created");
+
+        // 4x2 sheet: top row red/green/blue/half-transparent white, bottom row transparent.
+        let mut rgba = Vec::new();
+        for px in [
+            [255, 0, 0, 255],
+            [0, 255, 0, 255],
+            [0, 0, 255, 255],
+            [255, 255, 255, 128],
+        ] {
+            rgba.extend_from_slice(&px);
+        }
+        rgba.extend_from_slice(&[0; 16]);
+        fs::write(dir.join("hero.png"), encode_png_rgba(4, 2, &rgba)).expect("png write");
+        fs::write(
+            dir.join("hero.atlas.toml"),
+            "[sprites]\nghost = { x = 3, y = 0, w = 1, h = 2 }\n\n\
+             [[strips]]\nname = \"run\"\nw = 1\nh = 1\nframes = 3\n",
+        )
+        .expect("atlas write");
+
+        let assets = AssetStore::load_from_dir(&dir).expect("asset store should load");
+        assert_eq!(
+            assets.texture_ids(),
+            vec![
+                "hero",
+                "hero/ghost",
+                "hero/run_0",
+                "hero/run_1",
+                "hero/run_2"
+            ]
+        );
+        assert_eq!(
+            assets.texture("hero/run_2").map(|t| t.pixels.clone()),
+            Some(vec![0, 0, 255, 255])
+        );
+
+        let mut commands = FrameCommandBuffer::default();
+        commands
+            .push(DrawCommand::Clear {
+                color: [0, 0, 0, 255],
+            })
+            .expect("clear should validate");
+        for (asset_id, x) in [("hero/run_1", 0.0), ("hero/ghost", 1.0)] {
+            commands
+                .push(DrawCommand::Sprite {
+                    asset_id: asset_id.to_owned(),
+                    x,
+                    y: 0.0,
+                    rotation: 0.0,
+                    scale_x: 1.0,
+                    scale_y: 1.0,
+                    flip_x: false,
+                    flip_y: false,
+                    origin_x: 0.0,
+                    origin_y: 0.0,
+                    color: [255, 255, 255, 255],
+                })
+                .expect("sprite should validate");
+        }
+
+        let mut frame = SoftwareFrame::new(2, 2);
+        let stats = frame.apply_with_assets(&commands, Some(&assets));
+        assert_eq!(stats.commands_unsupported, 0);
+        let px = frame.pixels();
+        assert_eq!(&px[0..4], &[0, 255, 0, 255]);
+        // Half-transparent texel blends over black; the fully transparent one leaves it alone.
+        assert!((127..=129).contains(&px[4]), "{:?}", &px[4..8]);
+        assert_eq!(&px[12..16], &[0, 0, 0, 255]);
+
+        fs::write(
+            dir.join("hero.atlas.toml"),
+            "[sprites]\nwide = { x = 2, y = 0, w = 4, h = 1 }\n",
+        )
+        .expect("atlas write");
+        let err = AssetStore::load_from_dir(&dir).expect_err("out-of-bounds rect should fail");
+        assert!(err.to_string().contains("outside the 4x2 image"), "{err}");
+
+        fs::write(
+            dir.join("hero.atlas.toml"),
+            "[[strips]]\nname = \"huge\"\nx = 4294967295\nw = 2\nh = 1\nframes = 2\n",
+        )
+        .expect("atlas write");
+        let err = AssetStore::load_from_dir(&dir).expect_err("overflowing strip should fail");
+        assert!(err.to_string().contains("frame 1 is out of range"), "{err}");
+
+        fs::write(dir.join("hero.ppm"), b"P6\n1 1\n255\n\x00\x00\x00").expect("ppm write");
+        let err = AssetStore::load_from_dir(&dir).expect_err("duplicate stem should fail");
+        assert!(err.to_string().contains("reuses asset id `hero`"), "{err}");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    fn sprite(asset_id: &str, x: f64, y: f64) -> DrawCommand {
+        DrawCommand::Sprite {
+            asset_id: asset_id.to_owned(),
+            x,
+            y,
+            rotation: 0.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            flip_x: false,
+            flip_y: false,
+            origin_x: 0.0,
+            origin_y: 0.0,
+            color: [255, 255, 255, 255],
+        }
+    }
+
+    fn render_sprite(command: DrawCommand, size: u32) -> Vec<[u8; 4]> {
+        let mut assets = AssetStore::default();
+        // 2x1 strip: red then green.
+        assets.textures.insert(
+            "strip".to_owned(),
+            Texture {
+                width: 2,
+                height: 1,
+                pixels: vec![255, 0, 0, 255, 0, 255, 0, 255],
+            },
+        );
+        let mut commands = FrameCommandBuffer::default();
+        commands
+            .push(DrawCommand::Clear {
+                color: [0, 0, 0, 255],
+            })
+            .expect("clear should validate");
+        commands.push(command).expect("sprite should validate");
+
+        let mut frame = SoftwareFrame::new(size, size);
+        let stats = frame.apply_with_assets(&commands, Some(&assets));
+        assert_eq!(stats.commands_unsupported, 0);
+        frame
+            .pixels()
+            .chunks_exact(4)
+            .map(|p| [p[0], p[1], p[2], p[3]])
+            .collect()
+    }
+
+    #[test]
+    fn transforms_sprites_about_their_origin() {
+        const R: [u8; 4] = [255, 0, 0, 255];
+        const G: [u8; 4] = [0, 255, 0, 255];
+        const K: [u8; 4] = [0, 0, 0, 255];
+
+        let mut flipped = sprite("strip", 0.0, 0.0);
+        if let DrawCommand::Sprite { flip_x, .. } = &mut flipped {
+            *flip_x = true;
+        }
+        assert_eq!(&render_sprite(flipped, 2)[..2], &[G, R]);
+
+        let mut stretched = sprite("strip", 4.0, 1.0);
+        if let DrawCommand::Sprite {
+            scale_x,
+            scale_y,
+            origin_x,
+            origin_y,
+            ..
+        } = &mut stretched
+        {
+            (*scale_x, *scale_y) = (2.0, 1.0);
+            (*origin_x, *origin_y) = (1.0, 1.0);
+        }
+        assert_eq!(&render_sprite(stretched, 4)[..4], &[R, R, G, G]);
+
+        // Only the on-screen part of a huge sprite is walked; the texel seam lands at x = 2.
+        let mut huge = sprite("strip", 2.0 - 1e9, -10.0);
+        if let DrawCommand::Sprite {
+            scale_x, scale_y, ..
+        } = &mut huge
+        {
+            (*scale_x, *scale_y) = (1e9, 1e9);
+        }
+        let pixels = render_sprite(huge, 4);
+        assert!(pixels.chunks_exact(4).all(|row| row == [R, R, G, G]));
+
+        // A quarter turn about the top-left corner lays the strip out downwards,
+        // to the left of the pivot.
+        let mut rotated = sprite("strip", 2.0, 0.0);
+        if let DrawCommand::Sprite { rotation, .. } = &mut rotated {
+            *rotation = std::f64::consts::FRAC_PI_2;
+        }
+        let pixels = render_sprite(rotated, 3);
+        assert_eq!([pixels[0], pixels[1], pixels[2]], [K, R, K]);
+        assert_eq!([pixels[3], pixels[4], pixels[5]], [K, G, K]);
+        assert_eq!([pixels[6], pixels[7], pixels[8]], [K, K, K]);
+
+        let mut centred = sprite("strip", 1.0, 0.5);
+        if let DrawCommand::Sprite {
+            rotation,
+            origin_x,
+            origin_y,
+            ..
+        } = &mut centred
+        {
+            *rotation = std::f64::consts::PI;
+            (*origin_x, *origin_y) = (0.5, 0.5);
+        }
+        let pixels = render_sprite(centred, 2);
+        assert_eq!(&pixels[..2], &[G, R]);
+    }
+
+    #[test]
+    fn rejects_non_finite_sprite_transform() {
+        let mut commands = FrameCommandBuffer::default();
+        let mut bad = sprite("strip", 0.0, 0.0);
+        if let DrawCommand::Sprite { scale_y, .. } = &mut bad {
+            *scale_y = 0.0;
+        }
+        assert!(commands.push(bad).is_err());
+        let mut bad = sprite("strip", 0.0, 0.0);
+        if let DrawCommand::Sprite { rotation, .. } = &mut bad {
+            *rotation = f64::NAN;
+        }
+        assert!(commands.push(bad).is_err());
+        assert!(commands.push(sprite("strip", f64::NAN, 0.0)).is_err());
+        assert!(commands.push(sprite("strip", 0.0, f64::INFINITY)).is_err());
+    }
+
+    fn filled_rect(x: f64, y: f64, w: f64, h: f64) -> DrawCommand {
+        DrawCommand::Rect {
+            x,
+            y,
+            w,
+            h,
+            color: [255, 255, 255, 255],
+            filled: true,
+            thickness: 1.0,
+        }
+    }
+
+    fn lit_pixels(commands: Vec<DrawCommand>, size: u32) -> Vec<(u32, u32)> {
+        let mut buffer = FrameCommandBuffer::default();
+        for command in commands {
+            buffer.push(command).expect("command should validate");
+        }
+        let mut frame = SoftwareFrame::new(size, size);
+        frame.apply(&buffer);
+        frame
+            .pixels()
+            .chunks_exact(4)
+            .enumerate()
+            .filter(|(_, p)| p[0] == 255)
+            .map(|(i, _)| (i as u32 % size, i as u32 / size))
+            .collect()
+    }
+
+    #[test]
+    fn camera_centres_world_point_and_zooms() {
+        let lit = lit_pixels(
+            vec![
+                DrawCommand::Camera {
+                    x: 100.0,
+                    y: 50.0,
+                    zoom: 2.0,
+                    rotation: 0.0,
+                },
+                filled_rect(100.0, 50.0, 1.0, 1.0),
+            ],
+            8,
+        );
+        assert_eq!(lit, vec![(4, 4), (5, 4), (4, 5), (5, 5)]);
+    }
+
+    #[test]
+    fn transform_stack_nests_and_restores() {
+        let lit = lit_pixels(
+            vec![
+                DrawCommand::PushTransform,
+                DrawCommand::Translate { x: 2.0, y: 1.0 },
+                DrawCommand::Scale { x: 2.0, y: 1.0 },
+                filled_rect(0.0, 0.0, 1.0, 1.0),
+                DrawCommand::PopTransform,
+                filled_rect(0.0, 3.0, 1.0, 1.0),
+            ],
+            4,
+        );
+        assert_eq!(lit, vec![(2, 1), (3, 1), (0, 3)]);
+
+        // A quarter turn about the origin sends +x to +y; the rect becomes a polygon fill.
+        let lit = lit_pixels(
+            vec![
+                DrawCommand::Translate { x: 2.0, y: 0.0 },
+                DrawCommand::Rotate {
+                    rotation: std::f64::consts::FRAC_PI_2,
+                },
+                filled_rect(0.0, 0.0, 3.0, 1.0),
+            ],
+            4,
+        );
+        assert_eq!(lit, vec![(1, 0), (1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn rejects_unbalanced_transform_stack() {
+        let mut buffer = FrameCommandBuffer::default();
+        let err = buffer
+            .push(DrawCommand::PopTransform)
+            .expect_err("pop without push should fail");
+        assert!(err
+            .to_string()
+            .contains("without a matching push_transform"));
+
+        for _ in 0..super::MAX_TRANSFORM_DEPTH {
+            buffer
+                .push(DrawCommand::PushTransform)
+                .expect("push within limit");
+        }
+        assert!(buffer.push(DrawCommand::PushTransform).is_err());
+        assert!(buffer
+            .push(DrawCommand::Camera {
+                x: 0.0,
+                y: 0.0,
+                zoom: 0.0,
+                rotation: 0.0,
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn layers_sort_stably_and_clip_rects_scissor_draws() {
+        let red = [255, 0, 0, 255];
+        let blue = [0, 0, 255, 255];
+        let rect = |x, color| DrawCommand::Rect {
+            x,
+            y: 0.0,
+            w: 2.0,
+            h: 1.0,
+            color,
+            filled: true,
+            thickness: 1.0,
+        };
+        let mut buffer = FrameCommandBuffer::default();
+        for command in [
+            DrawCommand::Layer { layer: 1 },
+            rect(0.0, red),
+            DrawCommand::Layer { layer: 0 },
+            rect(0.0, blue),
+            rect(2.0, blue),
+            rect(2.0, red),
+            DrawCommand::SetClip {
+                x: 0.0,
+                y: 1.0,
+                w: 1.0,
+                h: 1.0,
+            },
+            DrawCommand::Clear { color: blue },
+            DrawCommand::ResetClip,
+        ] {
+            buffer.push(command).expect("command should validate");
+        }
+
+        let mut frame = SoftwareFrame::new(4, 2);
+        let stats = frame.apply(&buffer);
+        assert_eq!(stats.commands_executed, 9);
+        let px: Vec<&[u8]> = frame.pixels().chunks_exact(4).collect();
+        // The layer-1 HUD rect was submitted first but lands on top.
+        assert_eq!(&px[..4], &[&red[..], &red[..], &red[..], &red[..]]);
+        // The clear only touched the clip rect.
+        assert_eq!(&px[4..6], &[&blue[..], &[0, 0, 0, 0][..]]);
+    }
+
+    #[test]
+    fn validates_convex_polygons_and_triangle_lists() {
+        let polygon = |points: Vec<[f64; 2]>| DrawCommand::Polygon {
+            points,
+            color: [255, 255, 255, 255],
+            filled: true,
+            thickness: 1.0,
+        };
+        let mut buffer = FrameCommandBuffer::default();
+        let square = vec![[0.0, 0.0], [4.0, 0.0], [4.0, 4.0], [0.0, 4.0]];
+        let mut reversed = square.clone();
+        reversed.reverse();
+        assert!(buffer.push(polygon(square)).is_ok());
+        assert!(buffer.push(polygon(reversed)).is_ok());
+        let dart = vec![[0.0, 0.0], [4.0, 2.0], [0.0, 4.0], [1.0, 2.0]];
+        assert!(buffer.push(polygon(dart)).is_err());
+        let star: Vec<[f64; 2]> = (0..5)
+            .map(|i| {
+                let angle = i as f64 * 4.0 * std::f64::consts::PI / 5.0;
+                [angle.cos() * 10.0, angle.sin() * 10.0]
+            })
+            .collect();
+        assert!(buffer.push(polygon(star)).is_err());
+
+        let err = buffer
+            .push(DrawCommand::Triangles {
+                vertices: vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]],
+                colors: vec![[0, 0, 0, 255]; 2],
+            })
+            .expect_err("color count must match");
+        assert!(err.to_string().contains("3 vertices but 2 colors"), "{err}");
+    }
+
+    #[test]
+    fn rasterizes_polygons_triangles_and_textured_quads() {
+        // Anti-aliased edge: a half-covered pixel column blends at roughly half strength.
+        let mut buffer = FrameCommandBuffer::default();
+        buffer
+            .push(DrawCommand::Clear {
+                color: [0, 0, 0, 255],
+            })
+            .expect("clear should validate");
+        buffer
+            .push(DrawCommand::Polygon {
+                points: vec![[0.0, 0.0], [2.5, 0.0], [2.5, 1.0], [0.0, 1.0]],
+                color: [255, 255, 255, 255],
+                filled: true,
+                thickness: 1.0,
+            })
+            .expect("polygon should validate");
+        let mut frame = SoftwareFrame::new(4, 1);
+        frame.apply(&buffer);
+        let px = frame.pixels();
+        assert_eq!(&px[0..4], &[255, 255, 255, 255]);
+        assert!((126..=129).contains(&px[8]), "{:?}", &px[8..12]);
+        assert_eq!(px[12], 0);
+
+        // Two triangles sharing a diagonal cover a 2x2 quad exactly once; per-vertex colors
+        // interpolate across each triangle.
+        let mut buffer = FrameCommandBuffer::default();
+        let white = [255, 255, 255, 128];
+        buffer
+            .push(DrawCommand::Clear {
+                color: [0, 0, 0, 255],
+            })
+            .expect("clear should validate");
+        buffer
+            .push(DrawCommand::Triangles {
+                vertices: vec![
+                    [0.0, 0.0],
+                    [2.0, 0.0],
+                    [2.0, 2.0],
+                    [0.0, 0.0],
+                    [2.0, 2.0],
+                    [0.0, 2.0],
+                ],
+                colors: vec![white; 6],
+            })
+            .expect("triangles should validate");
+        let mut frame = SoftwareFrame::new(2, 2);
+        frame.apply(&buffer);
+        let first = frame.pixels()[0];
+        assert!((127..=128).contains(&first), "{first}");
+        assert!(frame
+            .pixels()
+            .chunks_exact(4)
+            .all(|p| p == [first, first, first, 255]));
+
+        let mut buffer = FrameCommandBuffer::default();
+        buffer
+            .push(DrawCommand::Triangles {
+                vertices: vec![[0.0, 0.0], [8.0, 0.0], [0.0, 8.0]],
+                colors: vec![[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]],
+            })
+            .expect("triangles should validate");
+        let mut frame = SoftwareFrame::new(8, 8);
+        frame.apply(&buffer);
+        let corner = &frame.pixels()[0..4];
+        assert!(
+            corner[0] > 200 && corner[1] < 30 && corner[2] < 30,
+            "{corner:?}"
+        );
+
+        // Mirrored UVs flip the red/green strip across the quad.
+        let mut assets = AssetStore::default();
+        assets.textures.insert(
+            "strip".to_owned(),
+            Texture {
+                width: 2,
+                height: 1,
+                pixels: vec![255, 0, 0, 255, 0, 255, 0, 255],
+            },
+        );
+        let mut buffer = FrameCommandBuffer::default();
+        buffer
+            .push(DrawCommand::TexturedQuad {
+                asset_id: "strip".to_owned(),
+                points: [[0.0, 0.0], [4.0, 0.0], [4.0, 1.0], [0.0, 1.0]],
+                uvs: [[1.0, 0.0], [0.0, 0.0], [0.0, 1.0], [1.0, 1.0]],
+                color: [255, 255, 255, 255],
+            })
+            .expect("quad should validate");
+        let mut frame = SoftwareFrame::new(4, 1);
+        let stats = frame.apply_with_assets(&buffer, Some(&assets));
+        assert_eq!(stats.commands_unsupported, 0);
+        let px: Vec<u8> = frame.pixels().chunks_exact(4).map(|p| p[1]).collect();
+        assert_eq!(px, vec![255, 255, 0, 0]);
+
+        // Outer triangle edges are anti-aliased like polygons; the quad diagonal stays hard.
+        let mut buffer = FrameCommandBuffer::default();
+        buffer
+            .push(DrawCommand::Clear {
+                color: [0, 0, 0, 255],
+            })
+            .expect("clear should validate");
+        let white = [255, 255, 255, 255];
+        buffer
+            .push(DrawCommand::Triangles {
+                vertices: vec![
+                    [0.0, 0.0],
+                    [2.5, 0.0],
+                    [2.5, 1.0],
+                    [0.0, 0.0],
+                    [2.5, 1.0],
+                    [0.0, 1.0],
+                ],
+                colors: vec![white; 6],
+            })
+            .expect("triangles should validate");
+        assets.textures.insert(
+            "white".to_owned(),
+            Texture {
+                width: 1,
+                height: 1,
+                pixels: white.to_vec(),
+            },
+        );
+        buffer
+            .push(DrawCommand::TexturedQuad {
+                asset_id: "white".to_owned(),
+                points: [[0.0, 1.0], [2.5, 1.0], [2.5, 2.0], [0.0, 2.0]],
+                uvs: [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+                color: white,
+            })
+            .expect("quad should validate");
+        let mut frame = SoftwareFrame::new(4, 2);
+        frame.apply_with_assets(&buffer, Some(&assets));
+        for row in frame.pixels().chunks_exact(16) {
+            assert_eq!(&row[0..8], &[255; 8]);
+            assert!((126..=129).contains(&row[8]), "{row:?}");
+            assert_eq!(row[12], 0);
+        }
+    }
+
+    #[test]
+    fn draws_visible_tilemap_cells_from_tileset() {
+        let mut assets = AssetStore::default();
+        // Two 2x2 tiles side by side: red then green.
+        let mut pixels = Vec::new();
+        for _ in 0..2 {
+            pixels.extend_from_slice(&[255, 0, 0, 255, 255, 0, 0, 255]);
+            pixels.extend_from_slice(&[0, 255, 0, 255, 0, 255, 0, 255]);
+        }
+        assets.textures.insert(
+            "tiles".to_owned(),
+            Texture {
+                width: 4,
+                height: 2,
+                pixels,
+            },
+        );
+        let tile_count = assets
+            .tileset_tile_count("tiles", (2, 2))
+            .expect("tileset should exist");
+        assert_eq!(tile_count, 2);
+        let mut tilemaps = TilemapStore::default();
+        let map = Tilemap::new("tiles", 3, 1, (2, 2), vec![0, -1, 1]).expect("tilemap");
+        tilemaps
+            .insert("level", map, tile_count)
+            .expect("tiles fit the tileset");
+
+        let render = |commands: Vec<DrawCommand>, tilemaps: &TilemapStore| {
+            let mut buffer = FrameCommandBuffer::default();
+            for command in commands {
+                buffer.push(command).expect("command should validate");
+            }
+            let mut frame = SoftwareFrame::new(8, 2);
+            let stats = frame.apply_with_resources(&buffer, Some(&assets), Some(tilemaps));
+            assert_eq!(stats.commands_unsupported, 0);
+            frame
+                .pixels()
+                .chunks_exact(4)
+                .take(8)
+                .map(|p| match p {
+                    [255, 0, 0, 255] => 'r',
+                    [0, 255, 0, 255] => 'g',
+                    _ => '.',
+                })
+                .collect::<String>()
+        };
+        let tilemap = |x| DrawCommand::Tilemap {
+            tilemap: "level".to_owned(),
+            x,
+            y: 0.0,
+            color: [255, 255, 255, 255],
+        };
+
+        assert_eq!(render(vec![tilemap(1.0)], &tilemaps), ".rr..gg.");
+        let clipped = vec![
+            DrawCommand::SetClip {
+                x: 0.0,
+                y: 0.0,
+                w: 6.0,
+                h: 2.0,
+            },
+            tilemap(1.0),
+        ];
+        assert_eq!(render(clipped, &tilemaps), ".rr..g..");
+
+        tilemaps
+            .set_tile("level", 1, 0, 1, tile_count)
+            .expect("cell should update");
+        let zoomed = vec![DrawCommand::Scale { x: 2.0, y: 1.0 }, tilemap(0.0)];
+        assert_eq!(render(zoomed, &tilemaps), "rrrrgggg");
+    }
 }
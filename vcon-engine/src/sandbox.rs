@@ -156,6 +156,7 @@ mod tests {
             assets_path: "assets".to_owned(),
             save_quota_mb: 8,
             permissions: vec!["storage".to_owned(), "network".to_owned()],
+            display: None,
         };
 
         let violations = validate_manifest_permissions(&manifest);
@@ -1,31 +1,82 @@
 use std::collections::HashMap;
 
+use crate::collision::CollisionShape;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct NodeId(pub u32);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PhysicsBodyKind {
     Static,
+    /// Moves by its own velocity but ignores gravity and is never pushed by contacts.
+    Kinematic,
     Dynamic,
 }
 
+impl PhysicsBodyKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "static" => Some(PhysicsBodyKind::Static),
+            "kinematic" => Some(PhysicsBodyKind::Kinematic),
+            "dynamic" => Some(PhysicsBodyKind::Dynamic),
+            _ => None,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            PhysicsBodyKind::Static => "static",
+            PhysicsBodyKind::Kinematic => "kinematic",
+            PhysicsBodyKind::Dynamic => "dynamic",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct PhysicsBody2D {
     pub kind: PhysicsBodyKind,
-    pub radius: f64,
+    pub shape: CollisionShape,
     pub velocity_x: f64,
     pub velocity_y: f64,
+    /// Radians per second.
+    pub angular_velocity: f64,
     pub restitution: f64,
+    /// Mass per unit of shape area, used when `mass` is not given.
+    pub density: f64,
+    /// Overrides the mass derived from `density`.
+    pub mass: Option<f64>,
+    /// Coulomb friction coefficient; a pair uses the geometric mean of both bodies.
+    pub friction: f64,
+    /// Damping rate: each step scales velocity by `1 / (1 + dt * linear_damping)`, so 1.0
+    /// keeps about 37% of the speed after one second.
+    pub linear_damping: f64,
+    /// Damping rate for spin, applied to angular velocity the same way as `linear_damping`.
+    pub angular_damping: f64,
+    /// Layer bits this body belongs to.
+    pub category: u32,
+    /// Layer bits this body collides with; a pair interacts only when each accepts the other.
+    pub mask: u32,
+    /// Sensors report overlaps but are never pushed apart.
+    pub sensor: bool,
 }
 
 impl Default for PhysicsBody2D {
     fn default() -> Self {
         Self {
             kind: PhysicsBodyKind::Dynamic,
-            radius: 16.0,
+            shape: CollisionShape::Circle { radius: 16.0 },
             velocity_x: 0.0,
             velocity_y: 0.0,
+            angular_velocity: 0.0,
             restitution: 0.5,
+            density: 1.0,
+            mass: None,
+            friction: 0.2,
+            linear_damping: 0.0,
+            angular_damping: 0.0,
+            category: 1,
+            mask: u32::MAX,
+            sensor: false,
         }
     }
 }
@@ -168,14 +219,32 @@ impl SceneGraph {
     }
 
     pub fn set_physics_body(&mut self, id: NodeId, body: PhysicsBody2D) -> Result<(), SceneError> {
-        if body.radius <= 0.0 {
+        body.shape
+            .validate()
+            .map_err(|reason| SceneError::InvalidPhysicsBody(format!("physics body {reason}")))?;
+        if !(0.0..=1.0).contains(&body.restitution) {
             return Err(SceneError::InvalidPhysicsBody(
-                "physics body radius must be greater than 0".to_owned(),
+                "physics body restitution must be within [0.0, 1.0]".to_owned(),
             ));
         }
-        if !(0.0..=1.0).contains(&body.restitution) {
+        let positive = |value: f64| value.is_finite() && value > 0.0;
+        if !positive(body.density) || body.mass.is_some_and(|mass| !positive(mass)) {
             return Err(SceneError::InvalidPhysicsBody(
-                "physics body restitution must be within [0.0, 1.0]".to_owned(),
+                "physics body density and mass must be greater than 0".to_owned(),
+            ));
+        }
+        let settings = [body.friction, body.linear_damping, body.angular_damping];
+        if settings
+            .iter()
+            .any(|value| !(value.is_finite() && *value >= 0.0))
+        {
+            return Err(SceneError::InvalidPhysicsBody(
+                "physics body friction and damping must be at least 0".to_owned(),
+            ));
+        }
+        if !body.angular_velocity.is_finite() {
+            return Err(SceneError::InvalidPhysicsBody(
+                "physics body angular velocity must be finite".to_owned(),
             ));
         }
 
@@ -208,6 +277,20 @@ impl SceneGraph {
         Ok(())
     }
 
+    pub fn update_physics_spin(
+        &mut self,
+        id: NodeId,
+        rotation: f64,
+        angular_velocity: f64,
+    ) -> Result<(), SceneError> {
+        let node = self.nodes.get_mut(&id).ok_or(SceneError::NodeMissing(id))?;
+        node.transform.rotation = rotation;
+        if let Some(body) = node.physics_body.as_mut() {
+            body.angular_velocity = angular_velocity;
+        }
+        Ok(())
+    }
+
     pub fn nodes(&self) -> impl Iterator<Item = &SceneNode> {
         self.nodes.values()
     }
@@ -246,6 +329,7 @@ pub enum SceneError {
 #[cfg(test)]
 mod tests {
     use super::{NodeId, PhysicsBody2D, PhysicsBodyKind, SceneGraph};
+    use crate::collision::CollisionShape;
 
     #[test]
     fn builds_hierarchy_and_preserves_update_order() {
@@ -292,10 +376,11 @@ mod tests {
                 node,
                 PhysicsBody2D {
                     kind: PhysicsBodyKind::Dynamic,
-                    radius: 12.0,
+                    shape: CollisionShape::Circle { radius: 12.0 },
                     velocity_x: 20.0,
                     velocity_y: -5.0,
                     restitution: 0.8,
+                    ..PhysicsBody2D::default()
                 },
             )
             .expect("assign body");
@@ -306,6 +391,21 @@ mod tests {
             .physics_body
             .as_ref()
             .expect("body exists");
-        assert_eq!(body.radius, 12.0);
+        assert_eq!(body.shape, CollisionShape::Circle { radius: 12.0 });
+
+        let err = scene
+            .set_physics_body(
+                node,
+                PhysicsBody2D {
+                    mass: Some(0.0),
+                    ..PhysicsBody2D::default()
+                },
+            )
+            .expect_err("massless body should fail");
+        assert!(err.to_string().contains("density and mass"));
+        assert_eq!(
+            PhysicsBodyKind::parse("kinematic").map(PhysicsBodyKind::as_str),
+            Some("kinematic")
+        );
     }
 }
@@ -81,6 +81,7 @@ mod tests {
             assets_path: "assets".to_owned(),
             save_quota_mb: 8,
             permissions: vec!["storage".to_owned()],
+            display: None,
         }
     }
 
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+/// Cells per side of the square chunks used to skip empty and off-screen regions.
+pub const TILEMAP_CHUNK: u32 = 16;
+pub const MAX_TILEMAP_CELLS: usize = 1 << 20;
+
+/// A grid of tile indices into a tileset texture. Tiles are numbered row-major across the
+/// tileset in `tile_w` x `tile_h` steps; negative cells are empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tilemap {
+    tileset: String,
+    columns: u32,
+    rows: u32,
+    tile_w: u32,
+    tile_h: u32,
+    cells: Vec<i32>,
+    /// Non-empty cell count per chunk, row-major.
+    chunk_fill: Vec<u32>,
+}
+
+impl Tilemap {
+    pub fn new(
+        tileset: impl Into<String>,
+        columns: u32,
+        rows: u32,
+        tile_size: (u32, u32),
+        cells: Vec<i32>,
+    ) -> Result<Self, TilemapError> {
+        let (tile_w, tile_h) = tile_size;
+        let cell_count = columns as usize * rows as usize;
+        if columns == 0 || rows == 0 || tile_w == 0 || tile_h == 0 {
+            return Err(TilemapError::InvalidSize(
+                "columns, rows and tile size must be > 0".to_owned(),
+            ));
+        }
+        if cell_count > MAX_TILEMAP_CELLS {
+            return Err(TilemapError::InvalidSize(format!(
+                "{columns}x{rows} exceeds {MAX_TILEMAP_CELLS} cells"
+            )));
+        }
+        if cells.len() != cell_count {
+            return Err(TilemapError::CellCount {
+                expected: cell_count,
+                actual: cells.len(),
+            });
+        }
+
+        let mut map = Self {
+            tileset: tileset.into(),
+            columns,
+            rows,
+            tile_w,
+            tile_h,
+            cells,
+            chunk_fill: Vec::new(),
+        };
+        map.chunk_fill = vec![0; (map.chunks_across() * map.chunks_down()) as usize];
+        for y in 0..rows {
+            for x in 0..columns {
+                if map.cells[(y * columns + x) as usize] >= 0 {
+                    let chunk = map.chunk_index(x, y);
+                    map.chunk_fill[chunk] += 1;
+                }
+            }
+        }
+        Ok(map)
+    }
+
+    pub fn tileset(&self) -> &str {
+        &self.tileset
+    }
+
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    pub fn tile_size(&self) -> (u32, u32) {
+        (self.tile_w, self.tile_h)
+    }
+
+    pub fn cells(&self) -> &[i32] {
+        &self.cells
+    }
+
+    pub fn tile(&self, x: u32, y: u32) -> Option<i32> {
+        (x < self.columns && y < self.rows).then(|| self.cells[(y * self.columns + x) as usize])
+    }
+
+    /// Replaces one cell and returns its previous tile.
+    pub fn set(&mut self, x: u32, y: u32, tile: i32) -> Result<i32, TilemapError> {
+        let previous = self.tile(x, y).ok_or(TilemapError::OutOfBounds { x, y })?;
+        let chunk = self.chunk_index(x, y);
+        match (previous >= 0, tile >= 0) {
+            (false, true) => self.chunk_fill[chunk] += 1,
+            (true, false) => self.chunk_fill[chunk] -= 1,
+            _ => {}
+        }
+        self.cells[(y * self.columns + x) as usize] = tile;
+        Ok(previous)
+    }
+
+    pub fn chunks_across(&self) -> u32 {
+        self.columns.div_ceil(TILEMAP_CHUNK)
+    }
+
+    pub fn chunks_down(&self) -> u32 {
+        self.rows.div_ceil(TILEMAP_CHUNK)
+    }
+
+    /// Whether chunk `(cx, cy)` holds any non-empty cell.
+    pub fn chunk_occupied(&self, cx: u32, cy: u32) -> bool {
+        cx < self.chunks_across()
+            && cy < self.chunks_down()
+            && self.chunk_fill[(cy * self.chunks_across() + cx) as usize] > 0
+    }
+
+    fn chunk_index(&self, x: u32, y: u32) -> usize {
+        ((y / TILEMAP_CHUNK) * self.chunks_across() + x / TILEMAP_CHUNK) as usize
+    }
+
+    fn max_tile(&self) -> Option<i32> {
+        self.cells.iter().copied().max().filter(|tile| *tile >= 0)
+    }
+}
+
+/// Tilemaps created by the cartridge at runtime, keyed by name.
+#[derive(Debug, Default, Clone)]
+pub struct TilemapStore {
+    maps: HashMap<String, Tilemap>,
+}
+
+impl TilemapStore {
+    /// Creates or replaces `name`. `tile_count` is how many tiles the tileset holds.
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        map: Tilemap,
+        tile_count: u32,
+    ) -> Result<(), TilemapError> {
+        if let Some(tile) = map.max_tile().filter(|tile| *tile as u32 >= tile_count) {
+            return Err(TilemapError::TileOutOfRange { tile, tile_count });
+        }
+        self.maps.insert(name.into(), map);
+        Ok(())
+    }
+
+    pub fn set_tile(
+        &mut self,
+        name: &str,
+        x: u32,
+        y: u32,
+        tile: i32,
+        tile_count: u32,
+    ) -> Result<i32, TilemapError> {
+        if tile >= 0 && tile as u32 >= tile_count {
+            return Err(TilemapError::TileOutOfRange { tile, tile_count });
+        }
+        self.maps
+            .get_mut(name)
+            .ok_or_else(|| TilemapError::UnknownTilemap(name.to_owned()))?
+            .set(x, y, tile)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Tilemap> {
+        self.maps.get(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.maps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.maps.is_empty()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TilemapError {
+    #[error("invalid tilemap size: {0}")]
+    InvalidSize(String),
+    #[error("tilemap expects {expected} cells, got {actual}")]
+    CellCount { expected: usize, actual: usize },
+    #[error("tile cell ({x}, {y}) is outside the tilemap")]
+    OutOfBounds { x: u32, y: u32 },
+    #[error("tile {tile} is outside a tileset of {tile_count} tiles")]
+    TileOutOfRange { tile: i32, tile_count: u32 },
+    #[error("unknown tilemap `{0}`")]
+    UnknownTilemap(String),
+    #[error("unknown tileset texture `{0}`")]
+    UnknownTileset(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Tilemap, TilemapError, TilemapStore, TILEMAP_CHUNK};
+
+    #[test]
+    fn tracks_chunk_occupancy_through_edits() {
+        let columns = TILEMAP_CHUNK * 2;
+        let mut map = Tilemap::new("tiles", columns, 1, (8, 8), vec![-1; columns as usize])
+            .expect("tilemap should build");
+        assert!(!map.chunk_occupied(0, 0) && !map.chunk_occupied(1, 0));
+
+        assert_eq!(map.set(TILEMAP_CHUNK + 3, 0, 2).expect("in bounds"), -1);
+        assert!(!map.chunk_occupied(0, 0) && map.chunk_occupied(1, 0));
+        map.set(TILEMAP_CHUNK + 3, 0, -1).expect("in bounds");
+        assert!(!map.chunk_occupied(1, 0));
+
+        assert!(matches!(
+            map.set(columns, 0, 1),
+            Err(TilemapError::OutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn store_rejects_tiles_beyond_tileset() {
+        let mut store = TilemapStore::default();
+        let map = Tilemap::new("tiles", 2, 1, (8, 8), vec![0, 4]).expect("tilemap should build");
+        assert!(matches!(
+            store.insert("main", map.clone(), 4),
+            Err(TilemapError::TileOutOfRange { tile: 4, .. })
+        ));
+        store
+            .insert("main", map, 5)
+            .expect("tile 4 fits five tiles");
+        assert!(store.set_tile("main", 0, 0, 5, 5).is_err());
+        assert_eq!(store.set_tile("main", 0, 0, 3, 5).expect("valid tile"), 0);
+        assert!(matches!(
+            store.set_tile("other", 0, 0, 1, 5),
+            Err(TilemapError::UnknownTilemap(_))
+        ));
+
+        let err = Tilemap::new("tiles", 2, 2, (8, 8), vec![0; 3]).expect_err("short grid");
+        assert_eq!(err.to_string(), "tilemap expects 4 cells, got 3");
+    }
+}
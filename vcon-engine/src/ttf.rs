@@ -0,0 +1,928 @@
+//! Dependency-free TrueType parsing and glyph rasterization for cartridge fonts.
+//!
+//! Reads `cmap` formats 4 and 12, simple and composite `glyf` outlines, `hmtx` advances and
+//! format 0 `kern` pairs. CFF-flavoured OpenType fonts and font collections are rejected.
+//! Outlines are flattened to lines and rasterized with exact signed-area coverage.
+
+use std::collections::HashMap;
+
+const MAX_COMPONENT_DEPTH: u32 = 8;
+/// Components one glyph may expand to across all nesting levels.
+const MAX_GLYPH_COMPONENTS: usize = 256;
+/// Outline points one glyph may expand to, counting every placed component.
+const MAX_GLYPH_POINTS: usize = 16_384;
+/// Glyph bitmaps wider or taller than this are treated as malformed.
+const MAX_GLYPH_EXTENT: u32 = 1024;
+
+/// A parsed TrueType face borrowing the font file.
+#[derive(Debug, Clone)]
+pub struct TtfFace<'a> {
+    units_per_em: f64,
+    ascent: f64,
+    descent: f64,
+    line_gap: f64,
+    long_loca: bool,
+    num_glyphs: u16,
+    num_h_metrics: u16,
+    loca: &'a [u8],
+    glyf: &'a [u8],
+    hmtx: &'a [u8],
+    cmap: CharMap<'a>,
+    kerning: HashMap<(u16, u16), i16>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CharMap<'a> {
+    Segments(&'a [u8]),
+    Groups(&'a [u8]),
+}
+
+/// 8-bit coverage for one glyph plus its placement relative to the pen and baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphBitmap {
+    pub width: u32,
+    pub height: u32,
+    /// Pixels from the pen position to the bitmap's left edge.
+    pub left: i32,
+    /// Pixels from the baseline up to the bitmap's top edge.
+    pub top: i32,
+    pub advance: f64,
+    pub coverage: Vec<u8>,
+}
+
+/// What one glyph has expanded to so far; composites that reuse wide composites would
+/// otherwise multiply their work at every nesting level.
+#[derive(Debug, Default)]
+struct OutlineBudget {
+    components: usize,
+    points: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Point {
+    x: f64,
+    y: f64,
+    on_curve: bool,
+}
+
+impl<'a> TtfFace<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, String> {
+        match data.get(0..4) {
+            Some([0, 1, 0, 0]) | Some(b"true") => {}
+            Some(b"OTTO") => return Err("CFF-based OpenType fonts are not supported".to_owned()),
+            Some(b"ttcf") => return Err("font collections are not supported".to_owned()),
+            _ => return Err("not a TrueType font".to_owned()),
+        }
+        let table_count = read_u16(data, 4)? as usize;
+        let mut tables = HashMap::new();
+        for i in 0..table_count {
+            let record = 12 + i * 16;
+            let tag = data.get(record..record + 4).ok_or(TRUNCATED)?;
+            let offset = read_u32(data, record + 8)? as usize;
+            let length = read_u32(data, record + 12)? as usize;
+            let table = offset
+                .checked_add(length)
+                .and_then(|end| data.get(offset..end))
+                .ok_or_else(|| {
+                    format!("`{}` table is out of bounds", String::from_utf8_lossy(tag))
+                })?;
+            tables.insert(tag, table);
+        }
+        let table = |tag: &[u8; 4]| {
+            tables
+                .get(&tag[..])
+                .copied()
+                .ok_or_else(|| format!("missing `{}` table", String::from_utf8_lossy(tag)))
+        };
+
+        let head = table(b"head")?;
+        let units_per_em = read_u16(head, 18)?;
+        if !(16..=16_384).contains(&units_per_em) {
+            return Err(format!("invalid unitsPerEm {units_per_em}"));
+        }
+        let hhea = table(b"hhea")?;
+        let num_h_metrics = read_u16(hhea, 34)?;
+        if num_h_metrics == 0 {
+            return Err("font has no horizontal metrics".to_owned());
+        }
+
+        Ok(Self {
+            units_per_em: units_per_em as f64,
+            ascent: read_i16(hhea, 4)? as f64,
+            descent: read_i16(hhea, 6)? as f64,
+            line_gap: read_i16(hhea, 8)? as f64,
+            long_loca: read_i16(head, 50)? != 0,
+            num_glyphs: read_u16(table(b"maxp")?, 4)?,
+            num_h_metrics,
+            loca: table(b"loca")?,
+            glyf: table(b"glyf")?,
+            hmtx: table(b"hmtx")?,
+            cmap: parse_cmap(table(b"cmap")?)?,
+            kerning: tables
+                .get(&b"kern"[..])
+                .map_or(Ok(HashMap::new()), |kern| parse_kern(kern))?,
+        })
+    }
+
+    fn scale(&self, px: f64) -> f64 {
+        px / self.units_per_em
+    }
+
+    /// Distance from the top of a line to its baseline, in pixels.
+    pub fn ascent(&self, px: f64) -> f64 {
+        self.ascent * self.scale(px)
+    }
+
+    /// Baseline-to-baseline distance, in pixels.
+    pub fn line_height(&self, px: f64) -> f64 {
+        (self.ascent - self.descent + self.line_gap) * self.scale(px)
+    }
+
+    /// Glyph for `ch`, or 0 (the missing glyph) when the font does not map it.
+    pub fn glyph_index(&self, ch: char) -> u16 {
+        let code = ch as u32;
+        let glyph = match self.cmap {
+            CharMap::Segments(table) => lookup_segments(table, code),
+            CharMap::Groups(table) => lookup_groups(table, code),
+        };
+        glyph.filter(|glyph| *glyph < self.num_glyphs).unwrap_or(0)
+    }
+
+    /// Pair adjustment between two glyphs, in pixels.
+    pub fn kerning(&self, left: u16, right: u16, px: f64) -> f64 {
+        self.kerning
+            .get(&(left, right))
+            .map_or(0.0, |amount| *amount as f64 * self.scale(px))
+    }
+
+    pub fn advance(&self, glyph: u16, px: f64) -> Result<f64, String> {
+        let metric = glyph.min(self.num_h_metrics - 1) as usize;
+        Ok(read_u16(self.hmtx, metric * 4)? as f64 * self.scale(px))
+    }
+
+    pub fn rasterize(&self, glyph: u16, px: f64) -> Result<GlyphBitmap, String> {
+        if !(px.is_finite() && px > 0.0) {
+            return Err(format!("invalid pixel size {px}"));
+        }
+        let scale = self.scale(px);
+        let advance = self.advance(glyph, px)?;
+        let mut contours = Vec::new();
+        let mut budget = OutlineBudget::default();
+        let identity = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+        self.outline(glyph, identity, 0, &mut budget, &mut contours)
+            .map_err(|err| format!("glyph {glyph}: {err}"))?;
+
+        let mut lines = Vec::new();
+        for contour in &contours {
+            let scaled: Vec<Point> = contour
+                .iter()
+                .map(|p| Point {
+                    x: p.x * scale,
+                    y: p.y * scale,
+                    on_curve: p.on_curve,
+                })
+                .collect();
+            flatten_contour(&scaled, &mut lines);
+        }
+        if lines.is_empty() {
+            return Ok(GlyphBitmap {
+                width: 0,
+                height: 0,
+                left: 0,
+                top: 0,
+                advance,
+                coverage: Vec::new(),
+            });
+        }
+
+        let points = lines.iter().flat_map(|(a, b)| [*a, *b]);
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+        for (x, y) in points {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        let (left, top) = (min_x.floor(), max_y.ceil());
+        let width = (max_x.ceil() - left).max(1.0);
+        let height = (top - min_y.floor()).max(1.0);
+        if width > MAX_GLYPH_EXTENT as f64 || height > MAX_GLYPH_EXTENT as f64 {
+            return Err(format!("glyph {glyph} is too large to rasterize"));
+        }
+
+        let mut raster = Raster::new(width as usize, height as usize);
+        for ((x0, y0), (x1, y1)) in lines {
+            raster.line((x0 - left, top - y0), (x1 - left, top - y1));
+        }
+        Ok(GlyphBitmap {
+            width: width as u32,
+            height: height as u32,
+            left: left as i32,
+            top: top as i32,
+            advance,
+            coverage: raster.coverage(),
+        })
+    }
+
+    /// Appends the glyph's contours in font units, transformed by `[a, b, c, d, dx, dy]`.
+    fn outline(
+        &self,
+        glyph: u16,
+        transform: [f64; 6],
+        depth: u32,
+        budget: &mut OutlineBudget,
+        out: &mut Vec<Vec<Point>>,
+    ) -> Result<(), String> {
+        if depth > MAX_COMPONENT_DEPTH {
+            return Err("composite glyphs are nested too deeply".to_owned());
+        }
+        let data = self.glyph_data(glyph)?;
+        if data.is_empty() {
+            return Ok(());
+        }
+        let contour_count = read_i16(data, 0)?;
+        if contour_count < 0 {
+            return self.composite_outline(data, transform, depth, budget, out);
+        }
+
+        let contour_count = contour_count as usize;
+        let mut ends = Vec::with_capacity(contour_count);
+        for i in 0..contour_count {
+            ends.push(read_u16(data, 10 + i * 2)? as usize);
+        }
+        let point_count = ends.last().map_or(0, |end| end + 1);
+        budget.points += point_count;
+        if budget.points > MAX_GLYPH_POINTS {
+            return Err(format!("outline has more than {MAX_GLYPH_POINTS} points"));
+        }
+        let instructions = 10 + contour_count * 2;
+        let mut cursor = instructions + 2 + read_u16(data, instructions)? as usize;
+
+        let mut flags = Vec::with_capacity(point_count);
+        while flags.len() < point_count {
+            let flag = read_u8(data, cursor)?;
+            cursor += 1;
+            let repeat = if flag & 0x08 != 0 {
+                cursor += 1;
+                read_u8(data, cursor - 1)? as usize
+            } else {
+                0
+            };
+            flags.extend(std::iter::repeat_n(flag, repeat + 1));
+        }
+        flags.truncate(point_count);
+
+        let mut coords = [vec![0.0; point_count], vec![0.0; point_count]];
+        // x uses flag bits 1 (short) and 4 (same/positive); y uses bits 2 and 5.
+        for (axis, (short, same)) in [(0x02, 0x10), (0x04, 0x20)].into_iter().enumerate() {
+            let mut value = 0i64;
+            for (i, flag) in flags.iter().enumerate() {
+                if flag & short != 0 {
+                    let delta = read_u8(data, cursor)? as i64;
+                    cursor += 1;
+                    value += if flag & same != 0 { delta } else { -delta };
+                } else if flag & same == 0 {
+                    value += read_i16(data, cursor)? as i64;
+                    cursor += 2;
+                }
+                coords[axis][i] = value as f64;
+            }
+        }
+
+        let [a, b, c, d, dx, dy] = transform;
+        let mut start = 0;
+        for end in ends {
+            if end < start || end >= point_count {
+                return Err(format!("glyph {glyph} has invalid contour ends"));
+            }
+            out.push(
+                (start..=end)
+                    .map(|i| {
+                        let (x, y) = (coords[0][i], coords[1][i]);
+                        Point {
+                            x: a * x + c * y + dx,
+                            y: b * x + d * y + dy,
+                            on_curve: flags[i] & 0x01 != 0,
+                        }
+                    })
+                    .collect(),
+            );
+            start = end + 1;
+        }
+        Ok(())
+    }
+
+    fn composite_outline(
+        &self,
+        data: &[u8],
+        transform: [f64; 6],
+        depth: u32,
+        budget: &mut OutlineBudget,
+        out: &mut Vec<Vec<Point>>,
+    ) -> Result<(), String> {
+        const ARGS_ARE_WORDS: u16 = 0x0001;
+        const ARGS_ARE_XY: u16 = 0x0002;
+        const HAS_SCALE: u16 = 0x0008;
+        const MORE_COMPONENTS: u16 = 0x0020;
+        const HAS_XY_SCALE: u16 = 0x0040;
+        const HAS_2X2: u16 = 0x0080;
+        let f2dot14 = |offset| read_i16(data, offset).map(|v| v as f64 / 16_384.0);
+
+        let mut cursor = 10;
+        loop {
+            budget.components += 1;
+            if budget.components > MAX_GLYPH_COMPONENTS {
+                return Err(format!("more than {MAX_GLYPH_COMPONENTS} components"));
+            }
+            let flags = read_u16(data, cursor)?;
+            let component = read_u16(data, cursor + 2)?;
+            cursor += 4;
+            let (arg1, arg2) = if flags & ARGS_ARE_WORDS != 0 {
+                cursor += 4;
+                (
+                    read_i16(data, cursor - 4)? as f64,
+                    read_i16(data, cursor - 2)? as f64,
+                )
+            } else {
+                cursor += 2;
+                (
+                    read_u8(data, cursor - 2)? as i8 as f64,
+                    read_u8(data, cursor - 1)? as i8 as f64,
+                )
+            };
+            // Point-matched placement is rare in practice; such components stay unshifted.
+            let (dx, dy) = if flags & ARGS_ARE_XY != 0 {
+                (arg1, arg2)
+            } else {
+                (0.0, 0.0)
+            };
+            let [a, b, c, d] = if flags & HAS_SCALE != 0 {
+                cursor += 2;
+                let s = f2dot14(cursor - 2)?;
+                [s, 0.0, 0.0, s]
+            } else if flags & HAS_XY_SCALE != 0 {
+                cursor += 4;
+                [f2dot14(cursor - 4)?, 0.0, 0.0, f2dot14(cursor - 2)?]
+            } else if flags & HAS_2X2 != 0 {
+                cursor += 8;
+                [
+                    f2dot14(cursor - 8)?,
+                    f2dot14(cursor - 6)?,
+                    f2dot14(cursor - 4)?,
+                    f2dot14(cursor - 2)?,
+                ]
+            } else {
+                [1.0, 0.0, 0.0, 1.0]
+            };
+
+            let [pa, pb, pc, pd, px, py] = transform;
+            let combined = [
+                pa * a + pc * b,
+                pb * a + pd * b,
+                pa * c + pc * d,
+                pb * c + pd * d,
+                pa * dx + pc * dy + px,
+                pb * dx + pd * dy + py,
+            ];
+            self.outline(component, combined, depth + 1, budget, out)?;
+            if flags & MORE_COMPONENTS == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    fn glyph_data(&self, glyph: u16) -> Result<&'a [u8], String> {
+        if glyph >= self.num_glyphs {
+            return Err(format!("glyph {glyph} is out of range"));
+        }
+        let i = glyph as usize;
+        let (start, end) = if self.long_loca {
+            (
+                read_u32(self.loca, i * 4)? as usize,
+                read_u32(self.loca, i * 4 + 4)? as usize,
+            )
+        } else {
+            (
+                read_u16(self.loca, i * 2)? as usize * 2,
+                read_u16(self.loca, i * 2 + 2)? as usize * 2,
+            )
+        };
+        self.glyf
+            .get(start..end.max(start))
+            .ok_or_else(|| format!("glyph {glyph} is out of bounds"))
+    }
+}
+
+const TRUNCATED: &str = "font data is truncated";
+
+fn read_u8(data: &[u8], offset: usize) -> Result<u8, String> {
+    data.get(offset)
+        .copied()
+        .ok_or_else(|| TRUNCATED.to_owned())
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+    match data.get(offset..offset + 2) {
+        Some(bytes) => Ok(u16::from_be_bytes([bytes[0], bytes[1]])),
+        None => Err(TRUNCATED.to_owned()),
+    }
+}
+
+fn read_i16(data: &[u8], offset: usize) -> Result<i16, String> {
+    read_u16(data, offset).map(|value| value as i16)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    match data.get(offset..offset + 4) {
+        Some(bytes) => Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        None => Err(TRUNCATED.to_owned()),
+    }
+}
+
+/// Picks a Unicode subtable, preferring full-range format 12 over BMP-only format 4.
+fn parse_cmap(cmap: &[u8]) -> Result<CharMap<'_>, String> {
+    let mut best = None;
+    for i in 0..read_u16(cmap, 2)? as usize {
+        let record = 4 + i * 8;
+        let platform = read_u16(cmap, record)?;
+        let encoding = read_u16(cmap, record + 2)?;
+        let unicode = platform == 0 || (platform == 3 && matches!(encoding, 1 | 10));
+        if !unicode {
+            continue;
+        }
+        let offset = read_u32(cmap, record + 4)? as usize;
+        let table = cmap.get(offset..).ok_or(TRUNCATED)?;
+        match read_u16(table, 0)? {
+            12 => best = Some(CharMap::Groups(table)),
+            4 if best.is_none() => best = Some(CharMap::Segments(table)),
+            _ => {}
+        }
+    }
+    best.ok_or_else(|| "font has no supported Unicode cmap".to_owned())
+}
+
+fn lookup_segments(table: &[u8], code: u32) -> Option<u16> {
+    let code = u16::try_from(code).ok()?;
+    let seg_x2 = read_u16(table, 6).ok()? as usize;
+    let (ends, starts) = (14, 16 + seg_x2);
+    let (deltas, range_offsets) = (16 + seg_x2 * 2, 16 + seg_x2 * 3);
+    for seg in (0..seg_x2).step_by(2) {
+        if read_u16(table, ends + seg).ok()? < code {
+            continue;
+        }
+        let start = read_u16(table, starts + seg).ok()?;
+        if start > code {
+            return None;
+        }
+        let delta = read_u16(table, deltas + seg).ok()?;
+        let range_offset = read_u16(table, range_offsets + seg).ok()? as usize;
+        if range_offset == 0 {
+            return Some(code.wrapping_add(delta));
+        }
+        let at = range_offsets + seg + range_offset + (code - start) as usize * 2;
+        let glyph = read_u16(table, at).ok()?;
+        return (glyph != 0).then(|| glyph.wrapping_add(delta));
+    }
+    None
+}
+
+fn lookup_groups(table: &[u8], code: u32) -> Option<u16> {
+    let groups = read_u32(table, 12).ok()? as usize;
+    for i in 0..groups {
+        let group = 16 + i * 12;
+        let start = read_u32(table, group).ok()?;
+        let end = read_u32(table, group + 4).ok()?;
+        if (start..=end).contains(&code) {
+            let glyph = read_u32(table, group + 8).ok()?.checked_add(code - start)?;
+            return u16::try_from(glyph).ok();
+        }
+    }
+    None
+}
+
+/// Horizontal format 0 pairs of a Windows-style `kern` table; other layouts are ignored.
+fn parse_kern(kern: &[u8]) -> Result<HashMap<(u16, u16), i16>, String> {
+    let mut pairs = HashMap::new();
+    if read_u16(kern, 0)? != 0 {
+        return Ok(pairs);
+    }
+    let mut offset = 4;
+    for _ in 0..read_u16(kern, 2)? {
+        let length = read_u16(kern, offset + 2)? as usize;
+        let coverage = read_u16(kern, offset + 4)?;
+        // Format 0 in the high byte, horizontal, neither minimum nor cross-stream values.
+        if coverage >> 8 == 0 && coverage & 0x07 == 0x01 {
+            for i in 0..read_u16(kern, offset + 6)? as usize {
+                let pair = offset + 14 + i * 6;
+                pairs.insert(
+                    (read_u16(kern, pair)?, read_u16(kern, pair + 2)?),
+                    read_i16(kern, pair + 4)?,
+                );
+            }
+        }
+        offset += length.max(6);
+    }
+    Ok(pairs)
+}
+
+/// A flattened outline edge in pixel space, from one point to the next.
+type Segment = ((f64, f64), (f64, f64));
+
+/// Turns a quadratic TrueType contour into line segments, inserting the implied on-curve
+/// midpoint between consecutive off-curve points.
+fn flatten_contour(points: &[Point], lines: &mut Vec<Segment>) {
+    let Some(last) = points.last() else {
+        return;
+    };
+    let midpoint = |a: Point, b: Point| Point {
+        x: (a.x + b.x) * 0.5,
+        y: (a.y + b.y) * 0.5,
+        on_curve: true,
+    };
+    let (start, rest): (Point, Vec<Point>) = match points.iter().position(|p| p.on_curve) {
+        Some(i) => (points[i], [&points[i + 1..], &points[..=i]].concat()),
+        None => {
+            let start = midpoint(points[0], *last);
+            (start, points.iter().copied().chain([start]).collect())
+        }
+    };
+
+    let mut current = start;
+    let mut control: Option<Point> = None;
+    for point in rest {
+        let (target, next_control) = match (point.on_curve, control) {
+            (true, _) => (Some(point), None),
+            (false, Some(previous)) => (Some(midpoint(previous, point)), Some(point)),
+            (false, None) => (None, Some(point)),
+        };
+        if let Some(target) = target {
+            match control {
+                Some(c) => flatten_quad(current, c, target, lines),
+                None => lines.push(((current.x, current.y), (target.x, target.y))),
+            }
+            current = target;
+        }
+        control = next_control;
+    }
+}
+
+fn flatten_quad(from: Point, control: Point, to: Point, lines: &mut Vec<Segment>) {
+    // Enough steps to keep the chord within about a tenth of a pixel of the curve.
+    let deviation = (from.x - 2.0 * control.x + to.x).hypot(from.y - 2.0 * control.y + to.y);
+    let steps = (deviation * 2.5).sqrt().ceil().clamp(1.0, 32.0) as usize;
+    let mut previous = (from.x, from.y);
+    for step in 1..=steps {
+        let t = step as f64 / steps as f64;
+        let mt = 1.0 - t;
+        let point = (
+            mt * mt * from.x + 2.0 * mt * t * control.x + t * t * to.x,
+            mt * mt * from.y + 2.0 * mt * t * control.y + t * t * to.y,
+        );
+        lines.push((previous, point));
+        previous = point;
+    }
+}
+
+/// Signed-area accumulation buffer: each line deposits its coverage change per pixel, and a
+/// running sum over the whole buffer yields the non-zero winding coverage.
+struct Raster {
+    width: usize,
+    height: usize,
+    acc: Vec<f64>,
+}
+
+impl Raster {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            acc: vec![0.0; width * height + 2],
+        }
+    }
+
+    fn line(&mut self, from: (f64, f64), to: (f64, f64)) {
+        if from.1 == to.1 {
+            return;
+        }
+        let clamp_x = |x: f64| x.clamp(0.0, self.width as f64);
+        let (dir, top, bottom) = if from.1 < to.1 {
+            (1.0, from, to)
+        } else {
+            (-1.0, to, from)
+        };
+        let dxdy = (bottom.0 - top.0) / (bottom.1 - top.1);
+        let first_row = top.1.max(0.0);
+        let mut x = top.0 + (first_row - top.1) * dxdy;
+        let last_row = bottom.1.ceil().min(self.height as f64) as usize;
+        for row in first_row as usize..last_row {
+            let y = row as f64;
+            let dy = (y + 1.0).min(bottom.1) - y.max(top.1);
+            let next_x = x + dxdy * dy;
+            let d = dy * dir;
+            let (x0, x1) = if x < next_x {
+                (clamp_x(x), clamp_x(next_x))
+            } else {
+                (clamp_x(next_x), clamp_x(x))
+            };
+            let start = row * self.width;
+            let (x0_floor, x1_ceil) = (x0.floor(), x1.ceil());
+            let (x0i, x1i) = (x0_floor as usize, x1_ceil as usize);
+            if x1i <= x0i + 1 {
+                let mid = 0.5 * (x0 + x1) - x0_floor;
+                self.acc[start + x0i] += d - d * mid;
+                self.acc[start + x0i + 1] += d * mid;
+            } else {
+                let s = 1.0 / (x1 - x0);
+                let x0f = x0 - x0_floor;
+                let a0 = 0.5 * s * (1.0 - x0f) * (1.0 - x0f);
+                let x1f = x1 - x1_ceil + 1.0;
+                let am = 0.5 * s * x1f * x1f;
+                self.acc[start + x0i] += d * a0;
+                if x1i == x0i + 2 {
+                    self.acc[start + x0i + 1] += d * (1.0 - a0 - am);
+                } else {
+                    let a1 = s * (1.5 - x0f);
+                    self.acc[start + x0i + 1] += d * (a1 - a0);
+                    for xi in x0i + 2..x1i - 1 {
+                        self.acc[start + xi] += d * s;
+                    }
+                    let a2 = a1 + (x1i - x0i - 3) as f64 * s;
+                    self.acc[start + x1i - 1] += d * (1.0 - a2 - am);
+                }
+                self.acc[start + x1i] += d * am;
+            }
+            x = next_x;
+        }
+    }
+
+    fn coverage(&self) -> Vec<u8> {
+        let mut sum = 0.0;
+        self.acc[..self.width * self.height]
+            .iter()
+            .map(|delta| {
+                sum += delta;
+                (sum.abs().min(1.0) * 255.0).round() as u8
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TtfFace;
+
+    use super::{MAX_GLYPH_COMPONENTS, MAX_GLYPH_POINTS};
+
+    fn be16(v: i32) -> Vec<u8> {
+        (v as u16).to_be_bytes().to_vec()
+    }
+
+    /// A composite glyph placing `component` at the origin `count` times.
+    fn wide_composite(component: u16, count: usize) -> Vec<u8> {
+        let mut glyph = [be16(-1), be16(0), be16(0), be16(0), be16(0)].concat();
+        for i in 0..count {
+            let more = if i + 1 < count { 0x0020 } else { 0 };
+            glyph.extend(
+                [
+                    be16(0x0003 | more),
+                    be16(component as i32),
+                    be16(0),
+                    be16(0),
+                ]
+                .concat(),
+            );
+        }
+        glyph
+    }
+
+    /// A font with 1000 units per em whose glyph 1 is a 500-unit square mapped to 'A', plus a
+    /// composite glyph 2 ('B') reusing it shifted right, and an A-A kerning pair of -100.
+    fn square_font() -> Vec<u8> {
+        font_with_glyphs(&[])
+    }
+
+    /// `square_font` with `extra` glyph records appended from glyph 3 on.
+    fn font_with_glyphs(extra: &[Vec<u8>]) -> Vec<u8> {
+        let num_glyphs = 3 + extra.len() as i32;
+
+        let mut head = vec![0u8; 54];
+        head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+        let mut hhea = vec![0u8; 36];
+        hhea[4..6].copy_from_slice(&800i16.to_be_bytes());
+        hhea[6..8].copy_from_slice(&(-200i16).to_be_bytes());
+        hhea[34..36].copy_from_slice(&3u16.to_be_bytes());
+        let mut maxp = vec![0u8; 6];
+        maxp[4..6].copy_from_slice(&be16(num_glyphs));
+        let hmtx = [be16(500), be16(0), be16(600), be16(0), be16(700), be16(0)].concat();
+
+        // Simple square: one contour, four on-curve points with word coordinates.
+        let square = [
+            be16(1),
+            be16(0),
+            be16(0),
+            be16(500),
+            be16(500),
+            be16(3),
+            be16(0),
+            vec![0x01; 4],
+            [be16(0), be16(500), be16(0), be16(-500)].concat(),
+            [be16(0), be16(0), be16(500), be16(0)].concat(),
+        ]
+        .concat();
+        // Composite: glyph 1 offset by (500, 0) with word arguments.
+        let composite = [
+            be16(-1),
+            be16(500),
+            be16(0),
+            be16(1000),
+            be16(500),
+            be16(0x0003),
+            be16(1),
+            be16(500),
+            be16(0),
+        ]
+        .concat();
+        let mut glyf = Vec::new();
+        let mut loca = be16(0);
+        for glyph in [vec![], square, composite].iter().chain(extra) {
+            glyf.extend_from_slice(glyph);
+            glyf.resize(glyf.len().next_multiple_of(2), 0);
+            loca.extend(be16(glyf.len() as i32 / 2));
+        }
+
+        // Format 4 with a segment for 'A'..'B' and the closing 0xFFFF segment.
+        let cmap = [
+            be16(0),
+            be16(1),
+            be16(3),
+            be16(1),
+            12u32.to_be_bytes().to_vec(),
+            be16(4),
+            be16(32),
+            be16(0),
+            be16(4),
+            be16(0),
+            be16(0),
+            be16(0),
+            be16(0x42),
+            be16(0xffff),
+            be16(0),
+            be16(0x41),
+            be16(0xffff),
+            be16(1 - 0x41),
+            be16(1),
+            be16(0),
+            be16(0),
+        ]
+        .concat();
+        let kern = [
+            be16(0),
+            be16(1),
+            be16(0),
+            be16(20),
+            be16(0x0001),
+            be16(1),
+            be16(0),
+            be16(0),
+            be16(0),
+            be16(1),
+            be16(1),
+            be16(-100),
+        ]
+        .concat();
+
+        let tables: [(&[u8; 4], Vec<u8>); 8] = [
+            (b"cmap", cmap),
+            (b"glyf", glyf),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", hmtx),
+            (b"kern", kern),
+            (b"loca", loca),
+            (b"maxp", maxp),
+        ];
+        let mut font = [vec![0, 1, 0, 0], be16(tables.len() as i32), vec![0; 6]].concat();
+        let mut offset = 12 + tables.len() * 16;
+        for (tag, data) in &tables {
+            font.extend_from_slice(*tag);
+            font.extend_from_slice(&[0; 4]);
+            font.extend_from_slice(&(offset as u32).to_be_bytes());
+            font.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            offset += data.len();
+        }
+        for (_, data) in tables {
+            font.extend(data);
+        }
+        font
+    }
+
+    #[test]
+    fn rasterizes_simple_and_composite_glyphs() {
+        let bytes = square_font();
+        let face = TtfFace::parse(&bytes).expect("font should parse");
+        assert_eq!((face.glyph_index('A'), face.glyph_index('B')), (1, 2));
+        assert_eq!(face.glyph_index('C'), 0);
+        assert_eq!(face.ascent(20.0), 16.0);
+        assert_eq!(face.line_height(20.0), 20.0);
+        assert_eq!(face.kerning(1, 1, 20.0), -2.0);
+        assert_eq!(face.kerning(1, 2, 20.0), 0.0);
+
+        let square = face.rasterize(1, 20.0).expect("square should rasterize");
+        assert_eq!(
+            (square.width, square.height, square.left, square.top),
+            (10, 10, 0, 10)
+        );
+        assert_eq!(square.advance, 12.0);
+        assert!(square.coverage.iter().all(|c| *c == 255));
+
+        let shifted = face.rasterize(2, 20.0).expect("composite should rasterize");
+        assert_eq!((shifted.width, shifted.left), (10, 10));
+        assert_eq!(shifted.coverage, square.coverage);
+
+        // Half a pixel past the grid: the right column and top row are half covered.
+        let offset = face.rasterize(1, 21.0).expect("square should rasterize");
+        assert_eq!((offset.width, offset.height), (11, 11));
+        let (edge, top, inside) = (offset.coverage[21], offset.coverage[0], offset.coverage[20]);
+        assert!(
+            (127..=129).contains(&edge) && (127..=129).contains(&top),
+            "{edge} {top}"
+        );
+        assert_eq!(inside, 255);
+    }
+
+    #[test]
+    fn rejects_unsupported_and_truncated_fonts() {
+        assert!(TtfFace::parse(b"OTTO\0\0\0\0").unwrap_err().contains("CFF"));
+        assert!(TtfFace::parse(b"not a font").is_err());
+        let bytes = square_font();
+        assert!(TtfFace::parse(&bytes[..bytes.len() - 40]).is_err());
+    }
+
+    #[test]
+    fn caps_the_work_wide_nested_composites_expand_to() {
+        // Glyphs 3 to 9 each place the next one two hundred times and glyph 10 places the
+        // square: within the depth limit, yet 200^8 placements if nothing else stops it.
+        let chain = (4..=10)
+            .map(|next| wide_composite(next, 200))
+            .chain([wide_composite(1, 200)])
+            .collect::<Vec<_>>();
+        let bytes = font_with_glyphs(&chain);
+        let face = TtfFace::parse(&bytes).expect("font should parse");
+        let err = face.rasterize(3, 20.0).unwrap_err();
+        assert!(
+            err.contains(&format!("more than {MAX_GLYPH_COMPONENTS} components")),
+            "{err}"
+        );
+
+        // Few components, but each repeats a glyph with many points.
+        let points = 100;
+        let dense = [
+            be16(1),
+            be16(0),
+            be16(0),
+            be16(0),
+            be16(0),
+            be16(points - 1),
+            be16(0),
+            // On-curve, short positive x and y deltas, repeated for every point.
+            vec![0x3f, points as u8 - 1],
+            vec![1; points as usize * 2],
+        ]
+        .concat();
+        let count = MAX_GLYPH_POINTS / points as usize + 1;
+        let bytes = font_with_glyphs(&[dense, wide_composite(3, count)]);
+        let face = TtfFace::parse(&bytes).expect("font should parse");
+        face.rasterize(3, 20.0).expect("dense glyph");
+        let err = face.rasterize(4, 20.0).unwrap_err();
+        assert!(
+            err.contains(&format!("more than {MAX_GLYPH_POINTS} points")),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn corrupt_fonts_fail_without_panicking() {
+        let bytes = square_font();
+        let exercise = |data: &[u8]| {
+            if let Ok(face) = TtfFace::parse(data) {
+                for ch in ['A', 'B', 'C', '\u{10000}'] {
+                    let glyph = face.glyph_index(ch);
+                    let _ = face.advance(glyph, 20.0);
+                    let _ = face.rasterize(glyph, 20.0);
+                    let _ = face.kerning(glyph, glyph, 20.0);
+                }
+                assert!(face.rasterize(1, f64::NAN).is_err());
+            }
+        };
+        for len in 0..bytes.len() {
+            exercise(&bytes[..len]);
+        }
+        for i in 0..bytes.len() {
+            for value in [0x00, 0x7f, 0x80, 0xff] {
+                let mut corrupt = bytes.clone();
+                corrupt[i] = value;
+                exercise(&corrupt);
+            }
+        }
+    }
+}
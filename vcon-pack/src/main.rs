@@ -6,7 +6,7 @@ use std::path::{Component, Path, PathBuf};
 use anyhow::{anyhow, bail, Context, Result};
 use clap::{Parser, Subcommand};
 use vcon_engine::sandbox::{scan_entrypoint_source, validate_manifest_permissions};
-use vcon_engine::Manifest;
+use vcon_engine::{literal_clip_references, Manifest};
 
 const BUNDLE_MAGIC: &[u8] = b"VCONPKG";
 const BUNDLE_VERSION: u8 = 1;
@@ -190,7 +190,7 @@ fn validate_manifest_and_policy(manifest: &Manifest, cartridge_dir: &Path) -> Re
         let abs = cartridge_dir.join(&file.path);
         let source = fs::read_to_string(&abs)
             .with_context(|| format!("failed reading python source {}", abs.display()))?;
-        let violations = scan_entrypoint_source(&source);
+        let violations = scan_entrypoint_source(&source, &abs);
         if !violations.is_empty() {
             let msg = violations
                 .iter()
@@ -201,6 +201,23 @@ fn validate_manifest_and_policy(manifest: &Manifest, cartridge_dir: &Path) -> Re
         }
     }
 
+    let entrypoint_source = fs::read_to_string(&entrypoint_path)
+        .with_context(|| format!("failed reading entrypoint {}", entrypoint_path.display()))?;
+    let mut clip_ids = HashSet::new();
+    for entry in fs::read_dir(&assets_path)
+        .with_context(|| format!("failed listing assets {}", assets_path.display()))?
+    {
+        let path = entry
+            .with_context(|| format!("failed listing assets {}", assets_path.display()))?
+            .path();
+        if path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("wav") {
+            if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                clip_ids.insert(stem.to_owned());
+            }
+        }
+    }
+    validate_audio_clip_references(manifest, &entrypoint_source, &clip_ids)?;
+
     Ok(())
 }
 
@@ -252,7 +269,7 @@ fn validate_manifest_and_policy_from_files(
     for file in python_files {
         let source = std::str::from_utf8(&file.bytes)
             .with_context(|| format!("python file {} must be UTF-8", file.path))?;
-        let violations = scan_entrypoint_source(source);
+        let violations = scan_entrypoint_source(source, Path::new(&file.path));
         if !violations.is_empty() {
             let msg = violations
                 .iter()
@@ -263,6 +280,48 @@ fn validate_manifest_and_policy_from_files(
         }
     }
 
+    let entrypoint = files
+        .iter()
+        .find(|file| file.path == manifest.entrypoint)
+        .ok_or_else(|| {
+            anyhow!(
+                "entrypoint file not found in bundle: {}",
+                manifest.entrypoint
+            )
+        })?;
+    let entrypoint_source = std::str::from_utf8(&entrypoint.bytes)
+        .with_context(|| format!("entrypoint {} must be UTF-8", entrypoint.path))?;
+    let clip_ids = files
+        .iter()
+        .filter_map(|file| file.path.strip_prefix(&assets_prefix))
+        .filter(|name| !name.contains('/'))
+        .filter_map(|name| name.strip_suffix(".wav"))
+        .map(str::to_owned)
+        .collect::<HashSet<_>>();
+    validate_audio_clip_references(manifest, entrypoint_source, &clip_ids)?;
+
+    Ok(())
+}
+
+/// Clip ids passed as string literals to the audio API in the entrypoint must have a
+/// matching `<assets_path>/<id>.wav`; ids computed at runtime are checked by the runtime.
+fn validate_audio_clip_references(
+    manifest: &Manifest,
+    entrypoint_source: &str,
+    clip_ids: &HashSet<String>,
+) -> Result<()> {
+    let missing = literal_clip_references(entrypoint_source)
+        .into_iter()
+        .filter(|id| !clip_ids.contains(id))
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        bail!(
+            "audio validation failed in {}: clip ids with no matching .wav asset in {}: {}",
+            manifest.entrypoint,
+            manifest.assets_path,
+            missing.join(", ")
+        );
+    }
     Ok(())
 }
 
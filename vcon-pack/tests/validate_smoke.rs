@@ -223,3 +223,77 @@ fn build_output_is_deterministic() {
 
     let _ = std::fs::remove_dir_all(out_dir);
 }
+
+#[test]
+fn validate_reports_literal_clip_ids_without_assets() {
+    let root = unique_temp_dir("missing-clip");
+    let src = root.join("src");
+    let assets = root.join("assets");
+    std::fs::create_dir_all(&src).expect("src should be created");
+    std::fs::create_dir_all(&assets).expect("assets should be created");
+
+    write_valid_manifest(&root);
+    std::fs::write(
+        src.join("main.py"),
+        "import vcon\n\nvcon.audio.play_sfx(\"jump\")\nvcon.audio.play_music(\"theme\")\n",
+    )
+    .expect("entrypoint write should succeed");
+    std::fs::copy(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../cartridges/audio-smoke/assets/ping.wav"),
+        assets.join("theme.wav"),
+    )
+    .expect("clip asset should be copied");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vcon-pack"))
+        .arg("validate")
+        .arg("--cartridge")
+        .arg(&root)
+        .output()
+        .expect("vcon-pack should execute");
+
+    assert!(!output.status.success(), "validate must fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no matching .wav asset in assets: jump"),
+        "unexpected stderr: {stderr}"
+    );
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn build_and_validate_accept_audio_cartridge_bundle() {
+    let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let cartridge = workspace.join("cartridges/audio-smoke");
+    let out_dir = unique_temp_dir("audio-bundle");
+    let bundle_path = out_dir.join("audio.vcon");
+    std::fs::create_dir_all(&out_dir).expect("out dir should exist");
+
+    let build = Command::new(env!("CARGO_BIN_EXE_vcon-pack"))
+        .arg("build")
+        .arg("--cartridge")
+        .arg(&cartridge)
+        .arg("--output")
+        .arg(&bundle_path)
+        .output()
+        .expect("build should execute");
+    assert!(
+        build.status.success(),
+        "build must succeed: {}",
+        String::from_utf8_lossy(&build.stderr)
+    );
+
+    let validate = Command::new(env!("CARGO_BIN_EXE_vcon-pack"))
+        .arg("validate")
+        .arg("--cartridge")
+        .arg(&bundle_path)
+        .output()
+        .expect("validate should execute");
+    assert!(
+        validate.status.success(),
+        "bundle validate must succeed: {}",
+        String::from_utf8_lossy(&validate.stderr)
+    );
+
+    let _ = std::fs::remove_dir_all(out_dir);
+}
@@ -1,3 +1,93 @@
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use vcon_engine::audio::{MIX_CHANNELS, MIX_SAMPLE_RATE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioBackendRequest {
+    Auto,
+    Simulated,
+    RingBuffer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActiveAudioBackend {
+    Simulated,
+    RingBuffer,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioBackendSelection {
+    pub requested: AudioBackendRequest,
+    pub active: ActiveAudioBackend,
+    pub fallback_reason: Option<String>,
+}
+
+pub fn select_audio_backend(requested: AudioBackendRequest) -> AudioBackendSelection {
+    select_audio_backend_with_probe(requested, probe_audio_output().map(|_| ()))
+}
+
+fn select_audio_backend_with_probe(
+    requested: AudioBackendRequest,
+    output_probe: std::result::Result<(), String>,
+) -> AudioBackendSelection {
+    match requested {
+        AudioBackendRequest::Simulated => AudioBackendSelection {
+            requested,
+            active: ActiveAudioBackend::Simulated,
+            fallback_reason: None,
+        },
+        AudioBackendRequest::RingBuffer => match output_probe {
+            Ok(()) => AudioBackendSelection {
+                requested,
+                active: ActiveAudioBackend::RingBuffer,
+                fallback_reason: None,
+            },
+            Err(reason) => AudioBackendSelection {
+                requested,
+                active: ActiveAudioBackend::Simulated,
+                fallback_reason: Some(reason),
+            },
+        },
+        AudioBackendRequest::Auto => match output_probe {
+            Ok(()) => AudioBackendSelection {
+                requested,
+                active: ActiveAudioBackend::RingBuffer,
+                fallback_reason: None,
+            },
+            Err(reason) => AudioBackendSelection {
+                requested,
+                active: ActiveAudioBackend::Simulated,
+                fallback_reason: Some(format!("auto fallback: {reason}")),
+            },
+        },
+    }
+}
+
+/// Opens the device for `backend`. A ring-buffer device that cannot start falls back to the
+/// simulated device with a diagnostic, mirroring `RenderExecutor::new`.
+pub fn create_audio_device(backend: ActiveAudioBackend) -> Box<dyn AudioDevice> {
+    match backend {
+        ActiveAudioBackend::Simulated => Box::new(SimulatedAudioDevice::new()),
+        ActiveAudioBackend::RingBuffer => match RingBufferAudioDevice::open_system_output() {
+            Ok(device) => Box::new(device),
+            Err(err) => {
+                eprintln!(
+                    "Audio backend fallback: ring-buffer output unavailable ({err:#}); using simulated-device"
+                );
+                Box::new(SimulatedAudioDevice::new())
+            }
+        },
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AudioBackendHealth {
     pub initialized: bool,
@@ -5,6 +95,23 @@ pub struct AudioBackendHealth {
     pub underruns: u64,
     pub overruns: u64,
     pub dropped_buffers: u64,
+    /// Voices evicted by the mixer's stealing policy; filled in by the runtime from the mixer.
+    pub voices_stolen: u64,
+}
+
+/// Output device fed once per fixed step with that step's interleaved stereo mix.
+pub trait AudioDevice: std::fmt::Debug {
+    fn backend_name(&self) -> &'static str;
+
+    fn submit(&mut self, samples: &[f32], dt_fixed: f64, active_voice_count: usize);
+
+    fn health(&self) -> AudioBackendHealth;
+
+    /// Reports a failure that happened after the device started; the runtime then swaps in
+    /// the simulated device.
+    fn take_failure(&mut self) -> Option<String> {
+        None
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -70,21 +177,304 @@ impl SimulatedAudioDevice {
             self.dropped_buffers += dropped as u64;
         }
     }
+}
 
-    pub fn health(&self) -> AudioBackendHealth {
+impl AudioDevice for SimulatedAudioDevice {
+    fn backend_name(&self) -> &'static str {
+        Self::BACKEND_NAME
+    }
+
+    fn submit(&mut self, _samples: &[f32], dt_fixed: f64, active_voice_count: usize) {
+        self.process_frame(dt_fixed, active_voice_count);
+    }
+
+    fn health(&self) -> AudioBackendHealth {
         AudioBackendHealth {
             initialized: self.initialized,
             queued_buffers: self.queued_buffers,
             underruns: self.underruns,
             overruns: self.overruns,
             dropped_buffers: self.dropped_buffers,
+            voices_stolen: 0,
+        }
+    }
+}
+
+const RING_BUFFER_FRAMES: usize = 480;
+const RING_MAX_QUEUED_BUFFERS: usize = 6;
+/// Buffer the host player is asked to keep, matching the ring so output latency stays bounded.
+const OUTPUT_LATENCY_MSEC: usize =
+    RING_BUFFER_FRAMES * RING_MAX_QUEUED_BUFFERS * 1000 / MIX_SAMPLE_RATE as usize;
+
+/// Bounded sample queue between the fixed-step mixer and the output thread. When the
+/// producer runs ahead the oldest samples are dropped; when the consumer has to take a
+/// short buffer it is padded with silence and an underrun is counted.
+#[derive(Debug)]
+struct AudioRing {
+    samples: VecDeque<f32>,
+    capacity: usize,
+    underruns: u64,
+    overruns: u64,
+    dropped_buffers: u64,
+}
+
+impl AudioRing {
+    fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            underruns: 0,
+            overruns: 0,
+            dropped_buffers: 0,
+        }
+    }
+
+    fn push(&mut self, input: &[f32]) {
+        self.samples.extend(input.iter().copied());
+        if self.samples.len() > self.capacity {
+            let excess = self.samples.len() - self.capacity;
+            self.samples.drain(..excess);
+            self.overruns += 1;
+            self.dropped_buffers += excess.div_ceil(RING_BUFFER_FRAMES * MIX_CHANNELS) as u64;
+        }
+    }
+
+    /// Fills `out` when a whole buffer is queued. Otherwise returns false, unless `due` says
+    /// the output cannot wait any longer, in which case the short buffer is padded.
+    fn pull(&mut self, out: &mut [f32], due: bool) -> bool {
+        let available = self.samples.len().min(out.len());
+        if available < out.len() && !due {
+            return false;
+        }
+        for (slot, sample) in out.iter_mut().zip(self.samples.drain(..available)) {
+            *slot = sample;
+        }
+        if available < out.len() {
+            out[available..].fill(0.0);
+            self.underruns += 1;
+        }
+        true
+    }
+
+    fn queued_buffers(&self) -> u32 {
+        (self.samples.len() / (RING_BUFFER_FRAMES * MIX_CHANNELS)) as u32
+    }
+}
+
+/// Streams the mix to a host audio player through `AudioRing`. The output thread writes only
+/// whole mixed buffers until the player, judged by the frames handed over since playback
+/// started, is about to run dry; only then does it pad with silence. Nothing is written
+/// before the first mix arrives, so silence never piles up in the pipe ahead of real audio.
+#[derive(Debug)]
+pub struct RingBufferAudioDevice {
+    ring: Arc<Mutex<AudioRing>>,
+    failure: Arc<Mutex<Option<String>>>,
+    stop: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+    child: Option<Child>,
+}
+
+impl RingBufferAudioDevice {
+    pub const BACKEND_NAME: &'static str = "ring-buffer";
+
+    pub fn open_system_output() -> Result<Self> {
+        let output = probe_audio_output().map_err(|reason| anyhow!(reason))?;
+        let mut child = Command::new(&output.program)
+            .args(&output.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("failed to start {}", output.program.display()))?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("audio player stdin unavailable"))?;
+
+        let mut device = Self::with_sink(Box::new(stdin));
+        device.child = Some(child);
+        Ok(device)
+    }
+
+    pub fn with_sink(mut sink: Box<dyn Write + Send>) -> Self {
+        let ring = Arc::new(Mutex::new(AudioRing::new(
+            RING_BUFFER_FRAMES * MIX_CHANNELS * RING_MAX_QUEUED_BUFFERS,
+        )));
+        let failure = Arc::new(Mutex::new(None));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let worker = {
+            let ring = Arc::clone(&ring);
+            let failure = Arc::clone(&failure);
+            let stop = Arc::clone(&stop);
+            std::thread::spawn(move || {
+                let mut buffer = vec![0.0_f32; RING_BUFFER_FRAMES * MIX_CHANNELS];
+                let mut bytes = Vec::with_capacity(buffer.len() * 4);
+                let rate = MIX_SAMPLE_RATE as f64;
+                let period = Duration::from_secs_f64(RING_BUFFER_FRAMES as f64 / rate);
+                // When playback started and how many frames the player has been handed since.
+                let mut playback: Option<(Instant, u64)> = None;
+                while !stop.load(Ordering::Relaxed) {
+                    let due = playback.as_mut().is_some_and(|(start, written)| {
+                        let played = (start.elapsed().as_secs_f64() * rate) as u64;
+                        if played > *written {
+                            // The player already ran dry; rebase so the gap is not made up with
+                            // a burst of silence.
+                            *start =
+                                Instant::now() - Duration::from_secs_f64(*written as f64 / rate);
+                        }
+                        written.saturating_sub(played) < RING_BUFFER_FRAMES as u64
+                    });
+                    let pulled = ring
+                        .lock()
+                        .is_ok_and(|mut ring| ring.pull(&mut buffer, due));
+                    if !pulled {
+                        std::thread::sleep(period / 10);
+                        continue;
+                    }
+                    let (_, written) = playback.get_or_insert_with(|| (Instant::now(), 0));
+                    *written += RING_BUFFER_FRAMES as u64;
+                    bytes.clear();
+                    for sample in &buffer {
+                        bytes.extend_from_slice(&sample.to_le_bytes());
+                    }
+                    if let Err(err) = sink.write_all(&bytes).and_then(|_| sink.flush()) {
+                        if let Ok(mut failure) = failure.lock() {
+                            *failure = Some(format!("audio output write failed: {err}"));
+                        }
+                        break;
+                    }
+                }
+            })
+        };
+
+        Self {
+            ring,
+            failure,
+            stop,
+            worker: Some(worker),
+            child: None,
+        }
+    }
+}
+
+impl AudioDevice for RingBufferAudioDevice {
+    fn backend_name(&self) -> &'static str {
+        Self::BACKEND_NAME
+    }
+
+    fn submit(&mut self, samples: &[f32], _dt_fixed: f64, _active_voice_count: usize) {
+        if let Ok(mut ring) = self.ring.lock() {
+            ring.push(samples);
+        }
+    }
+
+    fn health(&self) -> AudioBackendHealth {
+        let failed = self.failure.lock().map_or(true, |f| f.is_some());
+        match self.ring.lock() {
+            Ok(ring) => AudioBackendHealth {
+                initialized: !failed,
+                queued_buffers: ring.queued_buffers(),
+                underruns: ring.underruns,
+                overruns: ring.overruns,
+                dropped_buffers: ring.dropped_buffers,
+                voices_stolen: 0,
+            },
+            Err(_) => AudioBackendHealth {
+                initialized: false,
+                queued_buffers: 0,
+                underruns: 0,
+                overruns: 0,
+                dropped_buffers: 0,
+                voices_stolen: 0,
+            },
+        }
+    }
+
+    fn take_failure(&mut self) -> Option<String> {
+        self.failure.lock().ok()?.take()
+    }
+}
+
+impl Drop for RingBufferAudioDevice {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Kill the player first so a worker blocked on a full pipe wakes with an error.
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.kill();
+        }
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        if let Some(mut child) = self.child.take() {
+            let _ = child.wait();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioOutputCommand {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+}
+
+/// Looks for a host player that accepts raw 48 kHz stereo f32 PCM on stdin.
+pub fn probe_audio_output() -> std::result::Result<AudioOutputCommand, String> {
+    let rate = MIX_SAMPLE_RATE.to_string();
+    let channels = MIX_CHANNELS.to_string();
+    let candidates: [(&str, Vec<String>); 2] = [
+        (
+            "pacat",
+            vec![
+                "--raw".to_owned(),
+                "--format=float32le".to_owned(),
+                format!("--channels={channels}"),
+                format!("--rate={rate}"),
+                format!("--latency-msec={OUTPUT_LATENCY_MSEC}"),
+            ],
+        ),
+        (
+            "aplay",
+            vec![
+                "-q".to_owned(),
+                "-t".to_owned(),
+                "raw".to_owned(),
+                "-f".to_owned(),
+                "FLOAT_LE".to_owned(),
+                "-c".to_owned(),
+                channels.clone(),
+                "-r".to_owned(),
+                rate.clone(),
+                "-B".to_owned(),
+                (OUTPUT_LATENCY_MSEC * 1000).to_string(),
+                "-".to_owned(),
+            ],
+        ),
+    ];
+
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    for (name, args) in candidates {
+        if let Some(program) = std::env::split_paths(&path)
+            .map(|dir| dir.join(name))
+            .find(|candidate| candidate.is_file())
+        {
+            return Ok(AudioOutputCommand { program, args });
         }
     }
+    Err("no audio output found (need `pacat` or `aplay` on PATH)".to_owned())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::SimulatedAudioDevice;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use super::{
+        select_audio_backend, select_audio_backend_with_probe, ActiveAudioBackend,
+        AudioBackendRequest, AudioDevice, AudioRing, RingBufferAudioDevice, SimulatedAudioDevice,
+        RING_BUFFER_FRAMES,
+    };
 
     #[test]
     fn tracks_overrun_and_underrun_metrics() {
@@ -104,4 +494,114 @@ mod tests {
         assert!(b.overruns > 0);
         assert!(b.dropped_buffers > 0);
     }
+
+    #[test]
+    fn simulated_request_stays_simulated() {
+        let selection = select_audio_backend(AudioBackendRequest::Simulated);
+        assert_eq!(selection.active, ActiveAudioBackend::Simulated);
+        assert!(selection.fallback_reason.is_none());
+    }
+
+    #[test]
+    fn auto_request_falls_back_to_simulated_when_output_probe_fails() {
+        let selection =
+            select_audio_backend_with_probe(AudioBackendRequest::Auto, Err("no player".into()));
+        assert_eq!(selection.active, ActiveAudioBackend::Simulated);
+        assert_eq!(
+            selection.fallback_reason.as_deref(),
+            Some("auto fallback: no player")
+        );
+
+        let selection = select_audio_backend_with_probe(AudioBackendRequest::RingBuffer, Ok(()));
+        assert_eq!(selection.active, ActiveAudioBackend::RingBuffer);
+    }
+
+    #[test]
+    fn ring_drops_oldest_on_overrun_and_pads_underrun_with_silence() {
+        let buffer = RING_BUFFER_FRAMES * 2;
+        let mut ring = AudioRing::new(buffer * 2);
+        ring.push(&vec![0.25; buffer]);
+        ring.push(&vec![0.5; buffer * 2]);
+        assert_eq!(ring.overruns, 1);
+        assert_eq!(ring.dropped_buffers, 1);
+        assert_eq!(ring.queued_buffers(), 2);
+
+        // A short buffer waits until the output is due, then is padded with silence.
+        let mut out = vec![1.0; buffer * 3];
+        assert!(!ring.pull(&mut out, false));
+        assert_eq!(ring.underruns, 0);
+        assert!(ring.pull(&mut out, true));
+        assert!(out[..buffer * 2].iter().all(|s| *s == 0.5));
+        assert!(out[buffer * 2..].iter().all(|s| *s == 0.0));
+        assert_eq!(ring.underruns, 1);
+    }
+
+    #[derive(Clone, Default)]
+    struct SharedSink(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedSink {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            // Never blocks like a full pipe would, but yields so a spinning writer stays cheap.
+            std::thread::sleep(Duration::from_micros(200));
+            self.0.lock().expect("sink lock").extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn ring_buffer_device_streams_submitted_samples_to_sink() {
+        let sink = SharedSink::default();
+        let mut device = RingBufferAudioDevice::with_sink(Box::new(sink.clone()));
+        device.submit(&[0.5; RING_BUFFER_FRAMES * 2], 1.0 / 100.0, 1);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let wrote_mix = || {
+            sink.0
+                .lock()
+                .expect("sink lock")
+                .chunks_exact(4)
+                .any(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]) == 0.5)
+        };
+        while !wrote_mix() {
+            assert!(Instant::now() < deadline, "mix never reached the sink");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(device.backend_name(), "ring-buffer");
+        assert!(device.health().initialized);
+        assert!(device.take_failure().is_none());
+    }
+
+    #[test]
+    fn ring_buffer_device_paces_a_sink_that_never_blocks() {
+        let sink = SharedSink::default();
+        let written_frames = || sink.0.lock().expect("sink lock").len() as u64 / 8;
+        let mut device = RingBufferAudioDevice::with_sink(Box::new(sink.clone()));
+
+        // Nothing is written, and nothing counts as an underrun, before the first mix.
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(written_frames(), 0);
+        assert_eq!(device.health().underruns, 0);
+
+        let start = Instant::now();
+        device.submit(&[0.5; RING_BUFFER_FRAMES * 2 * 2], 1.0 / 50.0, 1);
+        std::thread::sleep(Duration::from_millis(100));
+        let elapsed = start.elapsed().as_secs_f64();
+
+        // Silence after the mix runs out is paced by real time, one buffer at a time.
+        let buffer = RING_BUFFER_FRAMES as u64;
+        let budget = 2 * buffer + (elapsed * 48_000.0) as u64 + 2 * buffer;
+        assert!(written_frames() >= 2 * buffer);
+        assert!(
+            written_frames() <= budget,
+            "{} > {budget}",
+            written_frames()
+        );
+        let underruns = device.health().underruns;
+        assert!(underruns <= (elapsed * 100.0) as u64 + 2, "{underruns}");
+        assert!(device.take_failure().is_none());
+    }
 }
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 use clap::{Parser, ValueEnum};
-use vcon_engine::boot_cartridge;
+use vcon_engine::{boot_cartridge, DisplayConfig};
 
 mod audio_backend;
 mod gamepad;
@@ -26,6 +26,13 @@ enum RenderBackendArg {
     Wgpu,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum AudioBackendArg {
+    Auto,
+    Simulated,
+    RingBuffer,
+}
+
 #[derive(Debug, Parser)]
 #[command(name = "vcon-runtime", about = "Virtual Console runtime host")]
 struct Args {
@@ -49,8 +56,12 @@ struct Args {
     input_seed: u64,
     #[arg(long, value_enum, default_value_t = RenderBackendArg::Auto)]
     render_backend: RenderBackendArg,
+    #[arg(long, value_enum, default_value_t = AudioBackendArg::Simulated)]
+    audio_backend: AudioBackendArg,
     #[arg(long)]
     dump_frame: Option<PathBuf>,
+    #[arg(long)]
+    dump_audio: Option<PathBuf>,
     #[arg(long, default_value_t = false)]
     windowed: bool,
     #[arg(long, default_value_t = 60)]
@@ -75,6 +86,22 @@ fn main() -> Result<()> {
     println!("Save namespace: {}", report.save_namespace.root.display());
     println!("Save quota: {} MB", report.save_namespace.quota_mb);
 
+    // A cartridge `[display]` table fixes the render resolution; `--width/--height` then only
+    // size the window it is upscaled into.
+    let display = report.manifest.display.unwrap_or(DisplayConfig {
+        width: args.width,
+        height: args.height,
+        filter: Default::default(),
+    });
+    if report.manifest.display.is_some() {
+        println!(
+            "Display: {}x{} (filter: {})",
+            display.width,
+            display.height,
+            display.filter.as_str()
+        );
+    }
+
     let mut none_provider = python_host::NoneInputProvider;
     let mut scripted_provider = python_host::ScriptedInputProvider::with_seed(args.input_seed);
     let mut gamepad_provider = gamepad::GamepadInputProvider::new();
@@ -92,12 +119,19 @@ fn main() -> Result<()> {
         RenderBackendArg::Wgpu => render_backend::RenderBackendRequest::Wgpu,
     };
     let backend_selection = render_backend::select_render_backend(backend_request);
+    let audio_request = match args.audio_backend {
+        AudioBackendArg::Auto => audio_backend::AudioBackendRequest::Auto,
+        AudioBackendArg::Simulated => audio_backend::AudioBackendRequest::Simulated,
+        AudioBackendArg::RingBuffer => audio_backend::AudioBackendRequest::RingBuffer,
+    };
+    let audio_selection = audio_backend::select_audio_backend(audio_request);
 
     let runtime_report = if args.windowed {
         let (mut window_input, mut window_observer) = window_runtime::create_window_runtime(
             &args.window_title,
             args.width,
             args.height,
+            display,
             args.windowed_target_fps,
         )?;
         python_host::run_cartridge_with_loop(
@@ -108,14 +142,16 @@ fn main() -> Result<()> {
                 max_frames: args.windowed_max_frames,
             },
             args.dt_fixed,
-            args.width,
-            args.height,
+            display.width,
+            display.height,
             &mut window_input,
             &report.save_namespace.root,
             report.save_namespace.quota_mb,
             Some(&args.cartridge.join(&report.manifest.assets_path)),
             args.dump_frame.as_deref(),
+            args.dump_audio.as_deref(),
             backend_selection.active,
+            audio_selection.active,
             Some(&mut window_observer),
         )?
     } else {
@@ -125,14 +161,16 @@ fn main() -> Result<()> {
             &args.sdk_root,
             args.frames,
             args.dt_fixed,
-            args.width,
-            args.height,
+            display.width,
+            display.height,
             input_provider,
             &report.save_namespace.root,
             report.save_namespace.quota_mb,
             Some(&args.cartridge.join(&report.manifest.assets_path)),
             args.dump_frame.as_deref(),
+            args.dump_audio.as_deref(),
             backend_selection.active,
+            audio_selection.active,
         )?
     };
 
@@ -144,8 +182,10 @@ fn main() -> Result<()> {
         runtime_report.on_update_calls, runtime_report.on_render_calls
     );
     println!(
-        "Event callbacks invoked: on_event={} (physics events: {})",
-        runtime_report.on_event_calls, runtime_report.physics_events_dispatched
+        "Event callbacks invoked: on_event={} (physics events: {}, audio events: {})",
+        runtime_report.on_event_calls,
+        runtime_report.physics_events_dispatched,
+        runtime_report.audio_events_dispatched
     );
     println!(
         "Draw commands submitted: {}",
@@ -165,12 +205,23 @@ fn main() -> Result<()> {
         runtime_report.physics_backend.as_str()
     );
     println!(
-        "Audio backend: {} (underruns={} overruns={} dropped_buffers={})",
+        "Audio backend: {} (requested={:?} underruns={} overruns={} dropped_buffers={})",
         runtime_report.audio_backend,
+        audio_selection.requested,
         runtime_report.audio_underruns,
         runtime_report.audio_overruns,
         runtime_report.audio_dropped_buffers
     );
+    println!(
+        "Audio mix: frames={} voices_stolen={}",
+        runtime_report.audio_frames_mixed, runtime_report.audio_voices_stolen
+    );
+    if !runtime_report.audio_unknown_clips.is_empty() {
+        println!(
+            "Audio unknown clip ids: {}",
+            runtime_report.audio_unknown_clips.join(", ")
+        );
+    }
     println!(
         "Render timing (us): cpu_render_total={} present_total={} pacing_anomalies={}",
         runtime_report.render_cpu_micros_total,
@@ -180,12 +231,18 @@ fn main() -> Result<()> {
     if let Some(reason) = &backend_selection.fallback_reason {
         println!("Render backend fallback: {reason}");
     }
+    if let Some(reason) = &audio_selection.fallback_reason {
+        println!("Audio backend fallback: {reason}");
+    }
     if runtime_report.on_shutdown_called {
         println!("Invoked lifecycle callback: on_shutdown() [python]");
     }
     if let Some(path) = args.dump_frame {
         println!("Dumped final frame to {}", path.display());
     }
+    if let Some(path) = args.dump_audio {
+        println!("Dumped audio mix to {}", path.display());
+    }
 
     Ok(())
 }
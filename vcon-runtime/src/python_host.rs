@@ -6,14 +6,20 @@ use std::time::Instant;
 
 use anyhow::{anyhow, Context, Result};
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyList};
+use pyo3::types::{PyCFunction, PyDict, PyList};
+use vcon_engine::audio::{DEFAULT_MUSIC_PRIORITY, DEFAULT_SFX_PRIORITY};
 use vcon_engine::{
-    scripted_input_frame_seeded, ActiveVoice, AssetStore, AudioMixer, DrawCommand,
-    FrameCommandBuffer, InputFrame, NodeId, PhysicsBackend, PhysicsBody2D, PhysicsBodyKind,
-    PhysicsVec2, PhysicsWorld, RenderStats, SceneGraph,
+    scripted_input_frame_seeded, ActiveVoice, AdsrEnvelope, AssetStore, AudioBus, AudioBusState,
+    AudioClipStore, AudioMixer, BlendMode, CollisionShape, ContactPhase, DrawCommand,
+    DuckingConfig, Font, FrameCommandBuffer, InputFrame, NodeId, PhysicsBackend, PhysicsBody2D,
+    PhysicsBodyKind, PhysicsVec2, PhysicsWorld, PlayRequest, RenderStats, SceneGraph, SynthTone,
+    SynthWaveform, TextAlign, TextLayout, Tilemap, TilemapStore, VoiceParams, VoiceStealPolicy,
+    WavFileSink, MAX_RENDER_TARGET_BYTES,
 };
 
-use crate::audio_backend::{AudioBackendHealth, SimulatedAudioDevice};
+use crate::audio_backend::{
+    create_audio_device, ActiveAudioBackend, AudioBackendHealth, AudioDevice, SimulatedAudioDevice,
+};
 use crate::render_backend::{ActiveRenderBackend, RenderExecutor};
 
 static NEXT_MODULE_ID: AtomicUsize = AtomicUsize::new(0);
@@ -25,6 +31,7 @@ pub struct RuntimeInvocationReport {
     pub on_render_calls: u32,
     pub on_event_calls: u32,
     pub physics_events_dispatched: u32,
+    pub audio_events_dispatched: u32,
     pub draw_commands_submitted: u32,
     pub draw_commands_rendered: u32,
     pub draw_commands_unsupported: u32,
@@ -34,6 +41,10 @@ pub struct RuntimeInvocationReport {
     pub audio_underruns: u64,
     pub audio_overruns: u64,
     pub audio_dropped_buffers: u64,
+    pub audio_voices_stolen: u64,
+    pub audio_frames_mixed: u64,
+    /// Clip ids requested by the cartridge with no matching asset, in first-seen order.
+    pub audio_unknown_clips: Vec<String>,
     pub render_cpu_micros_total: u64,
     pub present_micros_total: u64,
     pub frame_pacing_anomalies: u32,
@@ -92,9 +103,19 @@ struct PhysicsBodySpec {
     y: f64,
     velocity_x: f64,
     velocity_y: f64,
-    radius: f64,
-    dynamic: bool,
+    rotation: f64,
+    angular_velocity: f64,
+    shape: CollisionShape,
+    kind: PhysicsBodyKind,
     restitution: f64,
+    density: f64,
+    mass: Option<f64>,
+    friction: f64,
+    linear_damping: f64,
+    angular_damping: f64,
+    category: u32,
+    mask: u32,
+    sensor: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -109,6 +130,8 @@ struct RuntimePhysics {
     world: PhysicsWorld,
     names_to_nodes: HashMap<String, NodeId>,
     nodes_to_names: HashMap<NodeId, String>,
+    /// Nodes whose body was removed; they keep their name until the step that ends their contacts.
+    retired_nodes: Vec<NodeId>,
 }
 
 impl Default for RuntimePhysics {
@@ -123,14 +146,35 @@ impl Default for RuntimePhysics {
             world: PhysicsWorld::with_backend(backend),
             names_to_nodes: HashMap::new(),
             nodes_to_names: HashMap::new(),
+            retired_nodes: Vec::new(),
         }
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 struct RuntimeAudio {
     mixer: AudioMixer,
-    device: SimulatedAudioDevice,
+    device: Box<dyn AudioDevice>,
+    sink: Option<WavFileSink>,
+    unknown_clips: Vec<String>,
+}
+
+impl RuntimeAudio {
+    fn new(backend: ActiveAudioBackend) -> Self {
+        Self {
+            mixer: AudioMixer::default(),
+            device: create_audio_device(backend),
+            sink: None,
+            unknown_clips: Vec::new(),
+        }
+    }
+
+    fn health(&self) -> AudioBackendHealth {
+        AudioBackendHealth {
+            voices_stolen: self.mixer.voices_stolen(),
+            ..self.device.health()
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -138,6 +182,25 @@ struct AudioPlayRequestSpec {
     clip_id: String,
     volume: f32,
     looped: bool,
+    priority: u8,
+    bus: AudioBus,
+    fade_in: f32,
+    synth: Option<SynthTone>,
+}
+
+#[derive(Debug, Clone)]
+struct AudioVoiceFadeSpec {
+    voice_id: u64,
+    volume: f32,
+    seconds: f32,
+    stop: bool,
+}
+
+#[derive(Debug, Clone)]
+struct AudioBusUpdateSpec {
+    bus: AudioBus,
+    volume: Option<f32>,
+    muted: Option<bool>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -145,6 +208,29 @@ struct AudioRuntimeCommands {
     play_requests: Vec<AudioPlayRequestSpec>,
     stop_voice_ids: Vec<u64>,
     stop_all: bool,
+    voice_updates: Vec<(u64, VoiceParams)>,
+    voice_fades: Vec<AudioVoiceFadeSpec>,
+    voice_limit: Option<(usize, VoiceStealPolicy)>,
+    bus_updates: Vec<AudioBusUpdateSpec>,
+    /// `Some(None)` disables ducking; `None` leaves the current setting untouched.
+    ducking: Option<Option<DuckingConfig>>,
+}
+
+#[derive(Debug, Clone)]
+struct TilemapCreateSpec {
+    name: String,
+    tileset: String,
+    columns: u32,
+    rows: u32,
+    tile_size: (u32, u32),
+    cells: Vec<i32>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct TilemapRuntimeCommands {
+    creates: Vec<TilemapCreateSpec>,
+    /// `(name, x, y, tile)` edits, applied after this frame's creates.
+    updates: Vec<(String, u32, u32, i32)>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -161,7 +247,9 @@ pub fn run_cartridge(
     save_quota_mb: u32,
     asset_dir: Option<&Path>,
     dump_frame_path: Option<&Path>,
+    dump_audio_path: Option<&Path>,
     render_backend: ActiveRenderBackend,
+    audio_backend: ActiveAudioBackend,
 ) -> Result<RuntimeInvocationReport> {
     run_cartridge_with_loop(
         entrypoint_path,
@@ -176,7 +264,9 @@ pub fn run_cartridge(
         save_quota_mb,
         asset_dir,
         dump_frame_path,
+        dump_audio_path,
         render_backend,
+        audio_backend,
         None,
     )
 }
@@ -195,7 +285,9 @@ pub fn run_cartridge_with_loop(
     save_quota_mb: u32,
     asset_dir: Option<&Path>,
     dump_frame_path: Option<&Path>,
+    dump_audio_path: Option<&Path>,
     render_backend: ActiveRenderBackend,
+    audio_backend: ActiveAudioBackend,
     mut frame_observer: Option<&mut dyn FrameObserver>,
 ) -> Result<RuntimeInvocationReport> {
     let source = fs::read_to_string(entrypoint_path).with_context(|| {
@@ -210,6 +302,9 @@ pub fn run_cartridge_with_loop(
     } else {
         None
     };
+    let no_clips = AudioClipStore::default();
+    let clips = assets.as_ref().map_or(&no_clips, AssetStore::audio_clips);
+    let mut tilemaps = TilemapStore::default();
 
     Python::with_gil(|py| {
         extend_sys_path(py, entrypoint_path, cartridge_root, sdk_root)?;
@@ -218,7 +313,7 @@ pub fn run_cartridge_with_loop(
         configure_physics_api(py)?;
         configure_audio_api(py)?;
         let mut executor = RenderExecutor::new(render_backend, width, height);
-        configure_graphics_api(py, width, height, executor.backend())?;
+        configure_graphics_api(py, width, height, executor.backend(), assets.as_ref())?;
 
         let module_name = format!(
             "cartridge_entry_{}",
@@ -240,6 +335,7 @@ pub fn run_cartridge_with_loop(
         let mut on_render_calls = 0;
         let mut on_event_calls = 0;
         let mut physics_events_dispatched = 0;
+        let mut audio_events_dispatched = 0;
         let mut draw_commands_submitted = 0;
         let mut draw_commands_rendered = 0;
         let mut draw_commands_unsupported = 0;
@@ -252,7 +348,11 @@ pub fn run_cartridge_with_loop(
             None
         };
         let mut physics = RuntimePhysics::default();
-        let mut audio = RuntimeAudio::default();
+        let mut audio = RuntimeAudio::new(audio_backend);
+        if dump_audio_path.is_some() {
+            audio.sink = Some(WavFileSink::new(audio.mixer.sample_rate()));
+        }
+        let mut audio_frames_mixed = 0_u64;
 
         let mut frame_idx = 0_u32;
         loop {
@@ -276,24 +376,54 @@ pub fn run_cartridge_with_loop(
             publish_physics_runtime_state(py, &physics)?;
             physics_events_dispatched += collisions.len() as u32;
             for event in collisions {
-                call_cartridge1_event(&cartridge, "on_event", &event)?;
+                let event_type = format!("physics.contact_{}", event.phase.as_str());
+                call_cartridge1_event(&cartridge, "on_event", &event_type, &event)?;
                 on_event_calls += 1;
+                // Deprecated alias of `physics.contact_begin`, kept for one more release.
+                if event.phase == ContactPhase::Begin {
+                    call_cartridge1_event(&cartridge, "on_event", "physics.collision", &event)?;
+                    on_event_calls += 1;
+                }
             }
 
             let audio_commands = read_audio_runtime_commands(py)?;
-            apply_audio_runtime_commands(&mut audio, &audio_commands);
-            let active_voices = audio.mixer.flush_queue().to_vec();
-            audio.device.process_frame(dt_fixed, active_voices.len());
-            publish_audio_runtime_state(py, &active_voices, &audio.device.health())?;
+            apply_audio_runtime_commands(&mut audio, &audio_commands, clips);
+            audio.mixer.flush_queue();
+            let mix = audio.mixer.mix_step(dt_fixed, clips);
+            audio_frames_mixed += (mix.len() / 2) as u64;
+            if let Some(sink) = audio.sink.as_mut() {
+                sink.push(&mix);
+            }
+            let finished_voices = audio.mixer.drain_finished();
+            let active_voices = audio.mixer.active_voices().to_vec();
+            audio.device.submit(&mix, dt_fixed, active_voices.len());
+            if let Some(reason) = audio.device.take_failure() {
+                eprintln!("Audio backend fallback: {reason}; using simulated-device");
+                audio.device = Box::new(SimulatedAudioDevice::new());
+            }
+            publish_audio_runtime_state(
+                py,
+                &active_voices,
+                &audio.health(),
+                audio.mixer.bus_states(),
+            )?;
+            audio_events_dispatched += finished_voices.len() as u32;
+            for voice in &finished_voices {
+                call_cartridge1_audio_finished(&cartridge, "on_event", voice)?;
+                on_event_calls += 1;
+            }
 
             begin_render_frame(py)?;
             call_cartridge1_f64(&cartridge, "on_render", 1.0)?;
             on_render_calls += 1;
             let frame_commands = drain_and_validate_render_commands(py)?;
             draw_commands_submitted += frame_commands.commands.len() as u32;
+            let tilemap_commands = read_tilemap_runtime_commands(py)?;
+            apply_tilemap_commands(&mut tilemaps, tilemap_commands, assets.as_ref())?;
 
             let render_start = Instant::now();
-            let frame_stats: RenderStats = executor.render_frame(&frame_commands, assets.as_ref());
+            let frame_stats: RenderStats =
+                executor.render_frame(&frame_commands, assets.as_ref(), &tilemaps);
             let render_micros = render_start.elapsed().as_micros() as u64;
             render_cpu_micros_total = render_cpu_micros_total.saturating_add(render_micros);
             draw_commands_rendered += frame_stats.commands_executed as u32;
@@ -327,10 +457,13 @@ pub fn run_cartridge_with_loop(
         if let Some(path) = dump_frame_path {
             executor.dump_ppm(path)?;
         }
+        if let (Some(path), Some(sink)) = (dump_audio_path, audio.sink.as_ref()) {
+            sink.write_wav(path)?;
+        }
 
         call_cartridge0(&cartridge, "on_shutdown")?;
         let on_shutdown_called = true;
-        let audio_health = audio.device.health();
+        let audio_health = audio.health();
 
         Ok(RuntimeInvocationReport {
             on_boot_called,
@@ -338,15 +471,19 @@ pub fn run_cartridge_with_loop(
             on_render_calls,
             on_event_calls,
             physics_events_dispatched,
+            audio_events_dispatched,
             draw_commands_submitted,
             draw_commands_rendered,
             draw_commands_unsupported,
             render_backend: executor.backend(),
             physics_backend: physics.world.backend(),
-            audio_backend: SimulatedAudioDevice::BACKEND_NAME.to_owned(),
+            audio_backend: audio.device.backend_name().to_owned(),
             audio_underruns: audio_health.underruns,
             audio_overruns: audio_health.overruns,
             audio_dropped_buffers: audio_health.dropped_buffers,
+            audio_voices_stolen: audio_health.voices_stolen,
+            audio_frames_mixed,
+            audio_unknown_clips: audio.unknown_clips,
             render_cpu_micros_total,
             present_micros_total,
             frame_pacing_anomalies,
@@ -372,10 +509,10 @@ fn configure_physics_api(py: Python<'_>) -> Result<()> {
         .import_bound("vcon.physics")
         .context("failed to import vcon.physics")?;
     physics_mod
-        .getattr("_set_runtime_state")
-        .context("vcon.physics._set_runtime_state not found")?
-        .call1(((0.0_f64, 0.0_f64), Vec::<&str>::new()))
-        .context("vcon.physics._set_runtime_state() failed")?;
+        .getattr("_reset_runtime_state")
+        .context("vcon.physics._reset_runtime_state not found")?
+        .call0()
+        .context("vcon.physics._reset_runtime_state() failed")?;
     Ok(())
 }
 
@@ -400,6 +537,9 @@ fn configure_audio_api(py: Python<'_>) -> Result<()> {
     health
         .set_item("dropped_buffers", 0_u64)
         .context("audio dropped_buffers health set failed")?;
+    health
+        .set_item("voices_stolen", 0_u64)
+        .context("audio voices_stolen health set failed")?;
 
     audio_mod
         .getattr("_set_runtime_state")
@@ -414,14 +554,49 @@ fn configure_graphics_api(
     width: u32,
     height: u32,
     render_backend: ActiveRenderBackend,
+    assets: Option<&AssetStore>,
 ) -> Result<()> {
     let graphics_mod = py
         .import_bound("vcon.graphics")
         .context("failed to import vcon.graphics")?;
+    // `measure_text` calls back into the engine so its metrics and wrapping are the renderer's.
+    // Fonts are keyed by id, with the builtin face under `None`.
+    let mut fonts = HashMap::from([(None, Font::builtin().clone())]);
+    for id in assets.map(AssetStore::font_ids).unwrap_or_default() {
+        if let Some(font) = assets.and_then(|store| store.font(id)) {
+            fonts.insert(Some(id.to_owned()), font.clone());
+        }
+    }
+    let measure_text = PyCFunction::new_closure_bound(py, None, None, move |args, _kwargs| {
+        let (value, size, font, max_width, line_spacing): (
+            String,
+            f64,
+            Option<String>,
+            Option<f64>,
+            f64,
+        ) = args.extract()?;
+        let layout = TextLayout {
+            max_width,
+            line_spacing,
+            ..TextLayout::default()
+        };
+        PyResult::Ok(
+            fonts
+                .get(&font)
+                .map(|font| font.measure(&value, size, &layout)),
+        )
+    })
+    .context("measure_text callback creation failed")?;
     graphics_mod
         .getattr("_set_runtime_state")
         .context("vcon.graphics._set_runtime_state not found")?
-        .call1((width, height, render_backend.as_str()))
+        .call1((
+            width,
+            height,
+            render_backend.as_str(),
+            measure_text,
+            MAX_RENDER_TARGET_BYTES,
+        ))
         .context("vcon.graphics._set_runtime_state() failed")?;
     Ok(())
 }
@@ -489,24 +664,87 @@ fn read_physics_sync_state(py: Python<'_>) -> Result<PhysicsSyncInput> {
                 .ok_or_else(|| anyhow!("physics body missing `vy`"))?
                 .extract::<f64>()
                 .map_err(|_| anyhow!("physics body `vy` must be number"))?,
-            radius: body
-                .get_item("radius")
-                .context("physics body radius lookup failed")?
-                .ok_or_else(|| anyhow!("physics body missing `radius`"))?
+            rotation: body
+                .get_item("rotation")
+                .context("physics body rotation lookup failed")?
+                .ok_or_else(|| anyhow!("physics body missing `rotation`"))?
                 .extract::<f64>()
-                .map_err(|_| anyhow!("physics body `radius` must be number"))?,
-            dynamic: body
-                .get_item("dynamic")
-                .context("physics body dynamic lookup failed")?
-                .ok_or_else(|| anyhow!("physics body missing `dynamic`"))?
-                .extract::<bool>()
-                .map_err(|_| anyhow!("physics body `dynamic` must be bool"))?,
+                .map_err(|_| anyhow!("physics body `rotation` must be number"))?,
+            angular_velocity: body
+                .get_item("angular_velocity")
+                .context("physics body angular_velocity lookup failed")?
+                .ok_or_else(|| anyhow!("physics body missing `angular_velocity`"))?
+                .extract::<f64>()
+                .map_err(|_| anyhow!("physics body `angular_velocity` must be number"))?,
+            shape: extract_collision_shape(
+                &body
+                    .get_item("shape")
+                    .context("physics body shape lookup failed")?
+                    .ok_or_else(|| anyhow!("physics body missing `shape`"))?
+                    .downcast_into::<PyDict>()
+                    .map_err(|_| anyhow!("physics body `shape` must be dict"))?,
+            )?,
+            kind: extract_physics_body_kind(
+                &body
+                    .get_item("kind")
+                    .context("physics body kind lookup failed")?
+                    .ok_or_else(|| anyhow!("physics body missing `kind`"))?,
+            )?,
             restitution: body
                 .get_item("restitution")
                 .context("physics body restitution lookup failed")?
                 .ok_or_else(|| anyhow!("physics body missing `restitution`"))?
                 .extract::<f64>()
                 .map_err(|_| anyhow!("physics body `restitution` must be number"))?,
+            density: body
+                .get_item("density")
+                .context("physics body density lookup failed")?
+                .ok_or_else(|| anyhow!("physics body missing `density`"))?
+                .extract::<f64>()
+                .map_err(|_| anyhow!("physics body `density` must be number"))?,
+            mass: body
+                .get_item("mass")
+                .context("physics body mass lookup failed")?
+                .filter(|mass| !mass.is_none())
+                .map(|mass| mass.extract::<f64>())
+                .transpose()
+                .map_err(|_| anyhow!("physics body `mass` must be number or None"))?,
+            friction: body
+                .get_item("friction")
+                .context("physics body friction lookup failed")?
+                .ok_or_else(|| anyhow!("physics body missing `friction`"))?
+                .extract::<f64>()
+                .map_err(|_| anyhow!("physics body `friction` must be number"))?,
+            linear_damping: body
+                .get_item("linear_damping")
+                .context("physics body linear_damping lookup failed")?
+                .ok_or_else(|| anyhow!("physics body missing `linear_damping`"))?
+                .extract::<f64>()
+                .map_err(|_| anyhow!("physics body `linear_damping` must be number"))?,
+            angular_damping: body
+                .get_item("angular_damping")
+                .context("physics body angular_damping lookup failed")?
+                .ok_or_else(|| anyhow!("physics body missing `angular_damping`"))?
+                .extract::<f64>()
+                .map_err(|_| anyhow!("physics body `angular_damping` must be number"))?,
+            category: body
+                .get_item("category")
+                .context("physics body category lookup failed")?
+                .ok_or_else(|| anyhow!("physics body missing `category`"))?
+                .extract::<u32>()
+                .map_err(|_| anyhow!("physics body `category` must be 32-bit unsigned int"))?,
+            mask: body
+                .get_item("mask")
+                .context("physics body mask lookup failed")?
+                .ok_or_else(|| anyhow!("physics body missing `mask`"))?
+                .extract::<u32>()
+                .map_err(|_| anyhow!("physics body `mask` must be 32-bit unsigned int"))?,
+            sensor: body
+                .get_item("sensor")
+                .context("physics body sensor lookup failed")?
+                .ok_or_else(|| anyhow!("physics body missing `sensor`"))?
+                .extract::<bool>()
+                .map_err(|_| anyhow!("physics body `sensor` must be bool"))?,
         });
     }
 
@@ -516,6 +754,82 @@ fn read_physics_sync_state(py: Python<'_>) -> Result<PhysicsSyncInput> {
     })
 }
 
+fn extract_physics_body_kind(value: &Bound<'_, PyAny>) -> Result<PhysicsBodyKind> {
+    let name = value
+        .extract::<String>()
+        .map_err(|_| anyhow!("physics body `kind` must be string"))?;
+    PhysicsBodyKind::parse(&name).ok_or_else(|| {
+        anyhow!("physics body kind `{name}` must be one of static, kinematic, dynamic")
+    })
+}
+
+/// Reads an SDK shape dict; sizes are full widths and heights, the engine stores half extents.
+fn extract_collision_shape(shape: &Bound<'_, PyDict>) -> Result<CollisionShape> {
+    let kind = extract_str(shape, "type")?;
+    match kind.as_str() {
+        "circle" => Ok(CollisionShape::Circle {
+            radius: extract_f64(shape, "radius")?,
+        }),
+        "box" => Ok(CollisionShape::Box {
+            half_width: extract_f64(shape, "width")? * 0.5,
+            half_height: extract_f64(shape, "height")? * 0.5,
+        }),
+        "polygon" => Ok(CollisionShape::Polygon {
+            points: extract_points(shape, "points")?
+                .into_iter()
+                .map(|[x, y]| PhysicsVec2::new(x, y))
+                .collect(),
+        }),
+        "capsule" => {
+            let radius = extract_f64(shape, "radius")?;
+            Ok(CollisionShape::Capsule {
+                half_length: extract_f64(shape, "height")? * 0.5 - radius,
+                radius,
+            })
+        }
+        other => Err(anyhow!("unsupported physics shape `{other}`")),
+    }
+}
+
+fn collision_shape_to_py<'py>(
+    py: Python<'py>,
+    shape: &CollisionShape,
+) -> Result<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    let set = |key: &str, value: PyObject| {
+        dict.set_item(key, value)
+            .with_context(|| format!("set physics shape `{key}` failed"))
+    };
+    match shape {
+        CollisionShape::Circle { radius } => {
+            set("type", "circle".into_py(py))?;
+            set("radius", radius.into_py(py))?;
+        }
+        CollisionShape::Box {
+            half_width,
+            half_height,
+        } => {
+            set("type", "box".into_py(py))?;
+            set("width", (half_width * 2.0).into_py(py))?;
+            set("height", (half_height * 2.0).into_py(py))?;
+        }
+        CollisionShape::Polygon { points } => {
+            set("type", "polygon".into_py(py))?;
+            let points = points.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>();
+            set("points", points.into_py(py))?;
+        }
+        CollisionShape::Capsule {
+            half_length,
+            radius,
+        } => {
+            set("type", "capsule".into_py(py))?;
+            set("height", ((half_length + radius) * 2.0).into_py(py))?;
+            set("radius", radius.into_py(py))?;
+        }
+    }
+    Ok(dict)
+}
+
 fn synchronize_physics(state: &mut RuntimePhysics, input: &PhysicsSyncInput) -> Result<()> {
     state.world.set_gravity(input.gravity);
 
@@ -537,22 +851,27 @@ fn synchronize_physics(state: &mut RuntimePhysics, input: &PhysicsSyncInput) ->
 
         state
             .scene
-            .set_node_transform(node, body.x, body.y, 0.0, 1.0, 1.0)
+            .set_node_transform(node, body.x, body.y, body.rotation, 1.0, 1.0)
             .with_context(|| format!("failed to set transform for physics body `{}`", body.name))?;
         state
             .scene
             .set_physics_body(
                 node,
                 PhysicsBody2D {
-                    kind: if body.dynamic {
-                        PhysicsBodyKind::Dynamic
-                    } else {
-                        PhysicsBodyKind::Static
-                    },
-                    radius: body.radius,
+                    kind: body.kind,
+                    shape: body.shape.clone(),
                     velocity_x: body.velocity_x,
                     velocity_y: body.velocity_y,
+                    angular_velocity: body.angular_velocity,
                     restitution: body.restitution,
+                    density: body.density,
+                    mass: body.mass,
+                    friction: body.friction,
+                    linear_damping: body.linear_damping,
+                    angular_damping: body.angular_damping,
+                    category: body.category,
+                    mask: body.mask,
+                    sensor: body.sensor,
                 },
             )
             .with_context(|| format!("failed to set body for physics body `{}`", body.name))?;
@@ -574,7 +893,7 @@ fn synchronize_physics(state: &mut RuntimePhysics, input: &PhysicsSyncInput) ->
 
     for (name, node) in stale {
         state.names_to_nodes.remove(&name);
-        state.nodes_to_names.remove(&node);
+        state.retired_nodes.push(node);
         let _ = state.scene.clear_physics_body(node);
     }
 
@@ -586,14 +905,27 @@ fn step_physics(state: &mut RuntimePhysics, dt_fixed: f64) -> Vec<PyPhysicsEvent
     let events = state.world.step(dt_fixed);
     state.world.apply_to_scene(&mut state.scene);
 
-    events
+    let events = events
         .into_iter()
         .filter_map(|event| {
             let a = state.nodes_to_names.get(&event.a)?.clone();
             let b = state.nodes_to_names.get(&event.b)?.clone();
-            Some(PyPhysicsEvent { a, b })
+            Some(PyPhysicsEvent {
+                a,
+                b,
+                phase: event.phase,
+                sensor: event.sensor,
+                normal: (event.normal.x, event.normal.y),
+                point: (event.point.x, event.point.y),
+                depth: event.depth,
+                impulse: event.impulse,
+            })
         })
-        .collect()
+        .collect();
+    for node in state.retired_nodes.drain(..) {
+        state.nodes_to_names.remove(&node);
+    }
+    events
 }
 
 fn publish_physics_runtime_state(py: Python<'_>, state: &RuntimePhysics) -> Result<()> {
@@ -620,8 +952,12 @@ fn publish_physics_runtime_state(py: Python<'_>, state: &RuntimePhysics) -> Resu
             .context("set physics body vx failed")?;
         item.set_item("vy", body.velocity_y)
             .context("set physics body vy failed")?;
-        item.set_item("radius", body.radius)
-            .context("set physics body radius failed")?;
+        item.set_item("rotation", node.transform.rotation)
+            .context("set physics body rotation failed")?;
+        item.set_item("angular_velocity", body.angular_velocity)
+            .context("set physics body angular_velocity failed")?;
+        item.set_item("shape", collision_shape_to_py(py, &body.shape)?)
+            .context("set physics body shape failed")?;
         bodies.append(item).context("append physics body failed")?;
     }
 
@@ -634,6 +970,104 @@ fn publish_physics_runtime_state(py: Python<'_>, state: &RuntimePhysics) -> Resu
     Ok(())
 }
 
+fn read_tilemap_runtime_commands(py: Python<'_>) -> Result<TilemapRuntimeCommands> {
+    let graphics_mod = py
+        .import_bound("vcon.graphics")
+        .context("failed to import vcon.graphics")?;
+    let exported = graphics_mod
+        .getattr("_export_runtime_state")
+        .context("vcon.graphics._export_runtime_state not found")?
+        .call0()
+        .context("vcon.graphics._export_runtime_state() failed")?;
+    let dict = exported
+        .downcast_into::<PyDict>()
+        .map_err(|_| anyhow!("vcon.graphics._export_runtime_state() must return dict"))?;
+
+    let mut out = TilemapRuntimeCommands::default();
+    if let Some(creates) = dict
+        .get_item("tilemap_creates")
+        .context("tilemap_creates lookup failed")?
+    {
+        let creates = creates
+            .downcast_into::<PyList>()
+            .map_err(|_| anyhow!("tilemap_creates must be list"))?;
+        for item in creates.iter() {
+            let spec = item
+                .downcast_into::<PyDict>()
+                .map_err(|_| anyhow!("tilemap create entry must be dict"))?;
+            let field = |key: &str| {
+                spec.get_item(key)
+                    .context("tilemap create lookup failed")?
+                    .ok_or_else(|| anyhow!("tilemap create missing `{key}`"))
+            };
+            out.creates.push(TilemapCreateSpec {
+                name: field("name")?
+                    .extract()
+                    .map_err(|_| anyhow!("tilemap `name` must be string"))?,
+                tileset: field("tileset")?
+                    .extract()
+                    .map_err(|_| anyhow!("tilemap `tileset` must be string"))?,
+                columns: field("columns")?
+                    .extract()
+                    .map_err(|_| anyhow!("tilemap `columns` must be a non-negative int"))?,
+                rows: field("rows")?
+                    .extract()
+                    .map_err(|_| anyhow!("tilemap `rows` must be a non-negative int"))?,
+                tile_size: field("tile_size")?
+                    .extract()
+                    .map_err(|_| anyhow!("tilemap `tile_size` must be (w, h) ints"))?,
+                cells: field("cells")?
+                    .extract()
+                    .map_err(|_| anyhow!("tilemap `cells` must be a list of ints"))?,
+            });
+        }
+    }
+    if let Some(updates) = dict
+        .get_item("tile_updates")
+        .context("tile_updates lookup failed")?
+    {
+        out.updates = updates
+            .extract()
+            .map_err(|_| anyhow!("tile_updates must be a list of (name, x, y, tile)"))?;
+    }
+    Ok(out)
+}
+
+fn apply_tilemap_commands(
+    tilemaps: &mut TilemapStore,
+    commands: TilemapRuntimeCommands,
+    assets: Option<&AssetStore>,
+) -> Result<()> {
+    let no_assets = AssetStore::default();
+    let assets = assets.unwrap_or(&no_assets);
+    for spec in commands.creates {
+        let tile_count = assets
+            .tileset_tile_count(&spec.tileset, spec.tile_size)
+            .with_context(|| format!("tilemap `{}`", spec.name))?;
+        let map = Tilemap::new(
+            spec.tileset,
+            spec.columns,
+            spec.rows,
+            spec.tile_size,
+            spec.cells,
+        )
+        .with_context(|| format!("tilemap `{}`", spec.name))?;
+        tilemaps
+            .insert(spec.name.clone(), map, tile_count)
+            .with_context(|| format!("tilemap `{}`", spec.name))?;
+    }
+    for (name, x, y, tile) in commands.updates {
+        let tile_count = match tilemaps.get(&name) {
+            Some(map) => assets.tileset_tile_count(map.tileset(), map.tile_size())?,
+            None => 0,
+        };
+        tilemaps
+            .set_tile(&name, x, y, tile, tile_count)
+            .with_context(|| format!("tilemap_set on `{name}`"))?;
+    }
+    Ok(())
+}
+
 fn read_audio_runtime_commands(py: Python<'_>) -> Result<AudioRuntimeCommands> {
     let audio_mod = py
         .import_bound("vcon.audio")
@@ -678,10 +1112,41 @@ fn read_audio_runtime_commands(py: Python<'_>) -> Result<AudioRuntimeCommands> {
                 .context("audio looped lookup failed")?
                 .and_then(|value| value.extract::<bool>().ok())
                 .unwrap_or(false);
+            let priority = req
+                .get_item("priority")
+                .context("audio priority lookup failed")?
+                .map(|value| value.extract::<u8>())
+                .transpose()
+                .map_err(|_| anyhow!("audio priority must be integer in 0..=255"))?
+                .unwrap_or(if looped {
+                    DEFAULT_MUSIC_PRIORITY
+                } else {
+                    DEFAULT_SFX_PRIORITY
+                });
+            let bus = match req.get_item("bus").context("audio bus lookup failed")? {
+                Some(value) => extract_audio_bus(&value)?,
+                None if looped => AudioBus::Music,
+                None => AudioBus::Sfx,
+            };
+            let fade_in = req
+                .get_item("fade_in")
+                .context("audio fade_in lookup failed")?
+                .map(|value| value.extract::<f32>())
+                .transpose()
+                .map_err(|_| anyhow!("audio fade_in must be number"))?
+                .unwrap_or(0.0);
+            let synth = match req.get_item("synth").context("audio synth lookup failed")? {
+                Some(value) if !value.is_none() => Some(extract_synth_tone(&value)?),
+                _ => None,
+            };
             out.play_requests.push(AudioPlayRequestSpec {
                 clip_id,
                 volume,
                 looped,
+                priority,
+                bus,
+                fade_in,
+                synth,
             });
         }
     }
@@ -701,32 +1166,286 @@ fn read_audio_runtime_commands(py: Python<'_>) -> Result<AudioRuntimeCommands> {
         }
     }
 
+    if let Some(voice_updates) = dict
+        .get_item("voice_updates")
+        .context("audio voice_updates lookup failed")?
+    {
+        let voice_updates = voice_updates
+            .downcast_into::<PyList>()
+            .map_err(|_| anyhow!("audio voice_updates must be list"))?;
+        for item in voice_updates.iter() {
+            let update = item
+                .downcast_into::<PyDict>()
+                .map_err(|_| anyhow!("audio voice update entry must be dict"))?;
+            let voice_id = update
+                .get_item("voice_id")
+                .context("audio voice update id lookup failed")?
+                .ok_or_else(|| anyhow!("audio voice update missing `voice_id`"))?
+                .extract::<u64>()
+                .map_err(|_| anyhow!("audio voice update id must be integer"))?;
+            let mut params = VoiceParams::default();
+            for (key, slot) in [
+                ("volume", &mut params.volume),
+                ("pan", &mut params.pan),
+                ("pitch", &mut params.pitch),
+            ] {
+                *slot = update
+                    .get_item(key)
+                    .with_context(|| format!("audio voice {key} lookup failed"))?
+                    .map(|value| value.extract::<f32>())
+                    .transpose()
+                    .map_err(|_| anyhow!("audio voice {key} must be number"))?;
+            }
+            out.voice_updates.push((voice_id, params));
+        }
+    }
+
+    if let Some(voice_fades) = dict
+        .get_item("voice_fades")
+        .context("audio voice_fades lookup failed")?
+    {
+        let voice_fades = voice_fades
+            .downcast_into::<PyList>()
+            .map_err(|_| anyhow!("audio voice_fades must be list"))?;
+        for item in voice_fades.iter() {
+            let fade = item
+                .downcast_into::<PyDict>()
+                .map_err(|_| anyhow!("audio voice fade entry must be dict"))?;
+            let voice_id = fade
+                .get_item("voice_id")
+                .context("audio voice fade id lookup failed")?
+                .ok_or_else(|| anyhow!("audio voice fade missing `voice_id`"))?
+                .extract::<u64>()
+                .map_err(|_| anyhow!("audio voice fade id must be integer"))?;
+            let volume = fade
+                .get_item("volume")
+                .context("audio voice fade volume lookup failed")?
+                .ok_or_else(|| anyhow!("audio voice fade missing `volume`"))?
+                .extract::<f32>()
+                .map_err(|_| anyhow!("audio voice fade volume must be number"))?;
+            let seconds = fade
+                .get_item("seconds")
+                .context("audio voice fade seconds lookup failed")?
+                .ok_or_else(|| anyhow!("audio voice fade missing `seconds`"))?
+                .extract::<f32>()
+                .map_err(|_| anyhow!("audio voice fade seconds must be number"))?;
+            let stop = fade
+                .get_item("stop")
+                .context("audio voice fade stop lookup failed")?
+                .and_then(|value| value.extract::<bool>().ok())
+                .unwrap_or(false);
+            out.voice_fades.push(AudioVoiceFadeSpec {
+                voice_id,
+                volume,
+                seconds,
+                stop,
+            });
+        }
+    }
+
     out.stop_all = dict
         .get_item("stop_all")
         .context("audio stop_all lookup failed")?
         .and_then(|value| value.extract::<bool>().ok())
         .unwrap_or(false);
 
+    if let Some(limit) = dict
+        .get_item("voice_limit")
+        .context("audio voice_limit lookup failed")?
+    {
+        if !limit.is_none() {
+            let limit = limit
+                .downcast_into::<PyDict>()
+                .map_err(|_| anyhow!("audio voice_limit must be dict"))?;
+            let max_voices = limit
+                .get_item("max_voices")
+                .context("audio max_voices lookup failed")?
+                .ok_or_else(|| anyhow!("audio voice_limit missing `max_voices`"))?
+                .extract::<usize>()
+                .map_err(|_| anyhow!("audio max_voices must be positive integer"))?;
+            let policy_name = limit
+                .get_item("steal_policy")
+                .context("audio steal_policy lookup failed")?
+                .ok_or_else(|| anyhow!("audio voice_limit missing `steal_policy`"))?
+                .extract::<String>()
+                .map_err(|_| anyhow!("audio steal_policy must be string"))?;
+            let policy = VoiceStealPolicy::parse(&policy_name).ok_or_else(|| {
+                anyhow!(
+                    "audio steal_policy `{policy_name}` must be one of oldest, quietest, lowest_priority"
+                )
+            })?;
+            out.voice_limit = Some((max_voices, policy));
+        }
+    }
+
+    if let Some(bus_updates) = dict
+        .get_item("bus_updates")
+        .context("audio bus_updates lookup failed")?
+    {
+        let bus_updates = bus_updates
+            .downcast_into::<PyList>()
+            .map_err(|_| anyhow!("audio bus_updates must be list"))?;
+        for item in bus_updates.iter() {
+            let update = item
+                .downcast_into::<PyDict>()
+                .map_err(|_| anyhow!("audio bus update entry must be dict"))?;
+            let bus = update
+                .get_item("bus")
+                .context("audio bus lookup failed")?
+                .ok_or_else(|| anyhow!("audio bus update missing `bus`"))?;
+            let volume = update
+                .get_item("volume")
+                .context("audio bus volume lookup failed")?
+                .map(|value| value.extract::<f32>())
+                .transpose()
+                .map_err(|_| anyhow!("audio bus volume must be number"))?;
+            let muted = update
+                .get_item("muted")
+                .context("audio bus muted lookup failed")?
+                .map(|value| value.extract::<bool>())
+                .transpose()
+                .map_err(|_| anyhow!("audio bus muted must be bool"))?;
+            out.bus_updates.push(AudioBusUpdateSpec {
+                bus: extract_audio_bus(&bus)?,
+                volume,
+                muted,
+            });
+        }
+    }
+
+    if let Some(ducking) = dict
+        .get_item("ducking")
+        .context("audio ducking lookup failed")?
+    {
+        if !ducking.is_none() {
+            let ducking = ducking
+                .downcast_into::<PyDict>()
+                .map_err(|_| anyhow!("audio ducking must be dict"))?;
+            let enabled = ducking
+                .get_item("enabled")
+                .context("audio ducking enabled lookup failed")?
+                .and_then(|value| value.extract::<bool>().ok())
+                .unwrap_or(true);
+            let gain = ducking
+                .get_item("gain")
+                .context("audio ducking gain lookup failed")?
+                .ok_or_else(|| anyhow!("audio ducking missing `gain`"))?
+                .extract::<f32>()
+                .map_err(|_| anyhow!("audio ducking gain must be number"))?;
+            let fade_seconds = ducking
+                .get_item("fade_seconds")
+                .context("audio ducking fade_seconds lookup failed")?
+                .ok_or_else(|| anyhow!("audio ducking missing `fade_seconds`"))?
+                .extract::<f32>()
+                .map_err(|_| anyhow!("audio ducking fade_seconds must be number"))?;
+            out.ducking = Some(enabled.then_some(DuckingConfig { gain, fade_seconds }));
+        }
+    }
+
     Ok(out)
 }
 
-fn apply_audio_runtime_commands(audio: &mut RuntimeAudio, commands: &AudioRuntimeCommands) {
+fn extract_audio_bus(value: &Bound<'_, PyAny>) -> Result<AudioBus> {
+    let name = value
+        .extract::<String>()
+        .map_err(|_| anyhow!("audio bus must be string"))?;
+    AudioBus::parse(&name)
+        .ok_or_else(|| anyhow!("audio bus `{name}` must be one of music, sfx, ui"))
+}
+
+fn extract_synth_tone(value: &Bound<'_, PyAny>) -> Result<SynthTone> {
+    let spec = value
+        .downcast::<PyDict>()
+        .map_err(|_| anyhow!("audio synth must be dict"))?;
+    let number = |key: &str| -> Result<f32> {
+        spec.get_item(key)
+            .with_context(|| format!("audio synth {key} lookup failed"))?
+            .ok_or_else(|| anyhow!("audio synth missing `{key}`"))?
+            .extract::<f32>()
+            .map_err(|_| anyhow!("audio synth {key} must be number"))
+    };
+    let wave_name = spec
+        .get_item("wave")
+        .context("audio synth wave lookup failed")?
+        .ok_or_else(|| anyhow!("audio synth missing `wave`"))?
+        .extract::<String>()
+        .map_err(|_| anyhow!("audio synth wave must be string"))?;
+    let wave = SynthWaveform::parse(&wave_name).ok_or_else(|| {
+        anyhow!("audio synth wave `{wave_name}` must be one of square, triangle, saw, noise")
+    })?;
+
+    Ok(SynthTone {
+        wave,
+        frequency: number("frequency")?,
+        duration_seconds: number("duration")?,
+        envelope: AdsrEnvelope {
+            attack: number("attack")?,
+            decay: number("decay")?,
+            sustain: number("sustain")?,
+            release: number("release")?,
+        },
+    })
+}
+
+fn apply_audio_runtime_commands(
+    audio: &mut RuntimeAudio,
+    commands: &AudioRuntimeCommands,
+    clips: &AudioClipStore,
+) {
+    if let Some((max_voices, policy)) = commands.voice_limit {
+        audio.mixer.set_voice_limit(max_voices, policy);
+    }
+    for update in &commands.bus_updates {
+        if let Some(volume) = update.volume {
+            audio.mixer.set_bus_volume(update.bus, volume);
+        }
+        if let Some(muted) = update.muted {
+            audio.mixer.set_bus_muted(update.bus, muted);
+        }
+    }
+    if let Some(ducking) = commands.ducking {
+        audio.mixer.set_ducking(ducking);
+    }
     if commands.stop_all {
         audio.mixer.stop_all();
     }
     for voice_id in &commands.stop_voice_ids {
         audio.mixer.stop_voice(*voice_id);
     }
-    for request in &commands.play_requests {
-        if request.looped {
+    for (voice_id, params) in &commands.voice_updates {
+        audio.mixer.set_voice_params(*voice_id, *params);
+    }
+    for fade in &commands.voice_fades {
+        if fade.stop {
             audio
                 .mixer
-                .queue_music(request.clip_id.clone(), request.volume, true);
+                .stop_voice_with_fade(fade.voice_id, fade.seconds);
         } else {
             audio
                 .mixer
-                .queue_sfx(request.clip_id.clone(), request.volume);
+                .fade_voice(fade.voice_id, fade.volume, fade.seconds);
+        }
+    }
+    for request in &commands.play_requests {
+        if request.synth.is_none()
+            && !clips.contains(&request.clip_id)
+            && !audio.unknown_clips.contains(&request.clip_id)
+        {
+            eprintln!(
+                "Audio diagnostic: unknown clip id `{}` (no `{}.wav` in cartridge assets); voice will be silent",
+                request.clip_id, request.clip_id
+            );
+            audio.unknown_clips.push(request.clip_id.clone());
         }
+        audio.mixer.queue(PlayRequest {
+            clip_id: request.clip_id.clone(),
+            volume: request.volume,
+            looped: request.looped,
+            priority: request.priority,
+            bus: request.bus,
+            fade_in_seconds: request.fade_in,
+            synth: request.synth,
+        });
     }
 }
 
@@ -734,6 +1453,7 @@ fn publish_audio_runtime_state(
     py: Python<'_>,
     active_voices: &[ActiveVoice],
     health: &AudioBackendHealth,
+    buses: &[AudioBusState],
 ) -> Result<()> {
     let audio_mod = py
         .import_bound("vcon.audio")
@@ -750,6 +1470,20 @@ fn publish_audio_runtime_state(
             .context("set active audio volume failed")?;
         item.set_item("looped", voice.looped)
             .context("set active audio looped failed")?;
+        item.set_item("priority", voice.priority)
+            .context("set active audio priority failed")?;
+        item.set_item("bus", voice.bus.as_str())
+            .context("set active audio bus failed")?;
+        item.set_item("pan", voice.pan)
+            .context("set active audio pan failed")?;
+        item.set_item("pitch", voice.pitch)
+            .context("set active audio pitch failed")?;
+        item.set_item("fading", voice.fade.is_some())
+            .context("set active audio fading failed")?;
+        item.set_item("cursor", voice.cursor)
+            .context("set active audio cursor failed")?;
+        item.set_item("length_frames", voice.length_frames)
+            .context("set active audio length failed")?;
         active.append(item).context("append active voice failed")?;
     }
 
@@ -769,6 +1503,26 @@ fn publish_audio_runtime_state(
     health_dict
         .set_item("dropped_buffers", health.dropped_buffers)
         .context("set audio health dropped buffers failed")?;
+    health_dict
+        .set_item("voices_stolen", health.voices_stolen)
+        .context("set audio health voices stolen failed")?;
+
+    let bus_dict = PyDict::new_bound(py);
+    for bus in buses {
+        let item = PyDict::new_bound(py);
+        item.set_item("volume", bus.volume)
+            .context("set audio bus volume failed")?;
+        item.set_item("muted", bus.muted)
+            .context("set audio bus muted failed")?;
+        item.set_item("duck_gain", bus.duck_gain)
+            .context("set audio bus duck gain failed")?;
+        bus_dict
+            .set_item(bus.bus.as_str(), item)
+            .context("set audio bus state failed")?;
+    }
+    health_dict
+        .set_item("buses", bus_dict)
+        .context("set audio health buses failed")?;
 
     audio_mod
         .getattr("_set_runtime_state")
@@ -782,6 +1536,12 @@ fn publish_audio_runtime_state(
 struct PyPhysicsEvent {
     a: String,
     b: String,
+    phase: ContactPhase,
+    sensor: bool,
+    normal: (f64, f64),
+    point: (f64, f64),
+    depth: f64,
+    impulse: f64,
 }
 
 fn inject_input_state(py: Python<'_>, frame: &InputFrame) -> Result<()> {
@@ -886,7 +1646,12 @@ fn parse_draw_command(item: &Bound<'_, PyAny>) -> Result<DrawCommand> {
             x: extract_f64(dict, "x")?,
             y: extract_f64(dict, "y")?,
             rotation: extract_f64(dict, "rotation")?,
-            scale: extract_f64(dict, "scale")?,
+            scale_x: extract_f64(dict, "scale_x")?,
+            scale_y: extract_f64(dict, "scale_y")?,
+            flip_x: extract_bool(dict, "flip_x")?,
+            flip_y: extract_bool(dict, "flip_y")?,
+            origin_x: extract_f64(dict, "origin_x")?,
+            origin_y: extract_f64(dict, "origin_y")?,
             color: extract_color(dict, "color")?,
         }),
         "text" => Ok(DrawCommand::Text {
@@ -894,8 +1659,89 @@ fn parse_draw_command(item: &Bound<'_, PyAny>) -> Result<DrawCommand> {
             x: extract_f64(dict, "x")?,
             y: extract_f64(dict, "y")?,
             size: extract_f64(dict, "size")?,
+            font: extract_optional_str(dict, "font")?,
+            max_width: extract_optional_f64(dict, "max_width")?,
+            align: {
+                let align = extract_str(dict, "align")?;
+                TextAlign::parse(&align)
+                    .ok_or_else(|| anyhow!("unsupported text align `{align}`"))?
+            },
+            line_spacing: extract_f64(dict, "line_spacing")?,
+            color: extract_color(dict, "color")?,
+        }),
+        "camera" => Ok(DrawCommand::Camera {
+            x: extract_f64(dict, "x")?,
+            y: extract_f64(dict, "y")?,
+            zoom: extract_f64(dict, "zoom")?,
+            rotation: extract_f64(dict, "rotation")?,
+        }),
+        "push_transform" => Ok(DrawCommand::PushTransform),
+        "pop_transform" => Ok(DrawCommand::PopTransform),
+        "translate" => Ok(DrawCommand::Translate {
+            x: extract_f64(dict, "x")?,
+            y: extract_f64(dict, "y")?,
+        }),
+        "scale" => Ok(DrawCommand::Scale {
+            x: extract_f64(dict, "x")?,
+            y: extract_f64(dict, "y")?,
+        }),
+        "rotate" => Ok(DrawCommand::Rotate {
+            rotation: extract_f64(dict, "rotation")?,
+        }),
+        "polygon" => Ok(DrawCommand::Polygon {
+            points: extract_points(dict, "points")?,
+            color: extract_color(dict, "color")?,
+            filled: extract_bool(dict, "filled")?,
+            thickness: extract_f64(dict, "thickness")?,
+        }),
+        "triangles" => Ok(DrawCommand::Triangles {
+            vertices: extract_points(dict, "vertices")?,
+            colors: extract_colors(dict, "colors")?,
+        }),
+        "textured_quad" => Ok(DrawCommand::TexturedQuad {
+            asset_id: extract_str(dict, "asset_id")?,
+            points: extract_quad(dict, "points")?,
+            uvs: extract_quad(dict, "uvs")?,
+            color: extract_color(dict, "color")?,
+        }),
+        "blend" => {
+            let mode = extract_str(dict, "mode")?;
+            Ok(DrawCommand::Blend {
+                mode: BlendMode::parse(&mode)
+                    .ok_or_else(|| anyhow!("unsupported blend mode `{mode}`"))?,
+            })
+        }
+        "set_palette" => Ok(DrawCommand::SetPalette {
+            colors: extract_colors(dict, "colors")?,
+        }),
+        "reset_palette" => Ok(DrawCommand::ResetPalette),
+        "create_target" => Ok(DrawCommand::CreateTarget {
+            name: extract_str(dict, "name")?,
+            width: extract_u32(dict, "width")?,
+            height: extract_u32(dict, "height")?,
+        }),
+        "set_target" => Ok(DrawCommand::SetTarget {
+            name: extract_optional_str(dict, "name")?,
+        }),
+        "release_target" => Ok(DrawCommand::ReleaseTarget {
+            name: extract_str(dict, "name")?,
+        }),
+        "tilemap" => Ok(DrawCommand::Tilemap {
+            tilemap: extract_str(dict, "tilemap")?,
+            x: extract_f64(dict, "x")?,
+            y: extract_f64(dict, "y")?,
             color: extract_color(dict, "color")?,
         }),
+        "layer" => Ok(DrawCommand::Layer {
+            layer: extract_i32(dict, "layer")?,
+        }),
+        "set_clip" => Ok(DrawCommand::SetClip {
+            x: extract_f64(dict, "x")?,
+            y: extract_f64(dict, "y")?,
+            w: extract_f64(dict, "w")?,
+            h: extract_f64(dict, "h")?,
+        }),
+        "reset_clip" => Ok(DrawCommand::ResetClip),
         _ => Err(anyhow!("unknown draw command kind `{kind}`")),
     }
 }
@@ -908,6 +1754,26 @@ fn extract_str(dict: &Bound<'_, PyDict>, key: &str) -> Result<String> {
         .map_err(|_| anyhow!("draw command key `{key}` must be string"))
 }
 
+fn extract_optional_str(dict: &Bound<'_, PyDict>, key: &str) -> Result<Option<String>> {
+    match dict.get_item(key).context("dict lookup failed")? {
+        Some(value) if !value.is_none() => value
+            .extract::<String>()
+            .map(Some)
+            .map_err(|_| anyhow!("draw command key `{key}` must be string or None")),
+        _ => Ok(None),
+    }
+}
+
+fn extract_optional_f64(dict: &Bound<'_, PyDict>, key: &str) -> Result<Option<f64>> {
+    match dict.get_item(key).context("dict lookup failed")? {
+        Some(value) if !value.is_none() => value
+            .extract::<f64>()
+            .map(Some)
+            .map_err(|_| anyhow!("draw command key `{key}` must be number or None")),
+        _ => Ok(None),
+    }
+}
+
 fn extract_f64(dict: &Bound<'_, PyDict>, key: &str) -> Result<f64> {
     dict.get_item(key)
         .context("dict lookup failed")?
@@ -916,6 +1782,22 @@ fn extract_f64(dict: &Bound<'_, PyDict>, key: &str) -> Result<f64> {
         .map_err(|_| anyhow!("draw command key `{key}` must be number"))
 }
 
+fn extract_u32(dict: &Bound<'_, PyDict>, key: &str) -> Result<u32> {
+    dict.get_item(key)
+        .context("dict lookup failed")?
+        .ok_or_else(|| anyhow!("missing draw command key `{key}`"))?
+        .extract::<u32>()
+        .map_err(|_| anyhow!("draw command key `{key}` must be a non-negative int"))
+}
+
+fn extract_i32(dict: &Bound<'_, PyDict>, key: &str) -> Result<i32> {
+    dict.get_item(key)
+        .context("dict lookup failed")?
+        .ok_or_else(|| anyhow!("missing draw command key `{key}`"))?
+        .extract::<i32>()
+        .map_err(|_| anyhow!("draw command key `{key}` must be 32-bit int"))
+}
+
 fn extract_bool(dict: &Bound<'_, PyDict>, key: &str) -> Result<bool> {
     dict.get_item(key)
         .context("dict lookup failed")?
@@ -935,6 +1817,35 @@ fn extract_color(dict: &Bound<'_, PyDict>, key: &str) -> Result<[u8; 4]> {
     Ok([tuple.0, tuple.1, tuple.2, tuple.3])
 }
 
+fn extract_points(dict: &Bound<'_, PyDict>, key: &str) -> Result<Vec<[f64; 2]>> {
+    let points = dict
+        .get_item(key)
+        .context("dict lookup failed")?
+        .ok_or_else(|| anyhow!("missing draw command key `{key}`"))?
+        .extract::<Vec<(f64, f64)>>()
+        .map_err(|_| anyhow!("draw command key `{key}` must be a list of (x, y) pairs"))?;
+    Ok(points.into_iter().map(|(x, y)| [x, y]).collect())
+}
+
+fn extract_quad(dict: &Bound<'_, PyDict>, key: &str) -> Result<[[f64; 2]; 4]> {
+    extract_points(dict, key)?
+        .try_into()
+        .map_err(|_| anyhow!("draw command key `{key}` must have exactly 4 points"))
+}
+
+fn extract_colors(dict: &Bound<'_, PyDict>, key: &str) -> Result<Vec<[u8; 4]>> {
+    let colors = dict
+        .get_item(key)
+        .context("dict lookup failed")?
+        .ok_or_else(|| anyhow!("missing draw command key `{key}`"))?
+        .extract::<Vec<(u8, u8, u8, u8)>>()
+        .map_err(|_| anyhow!("draw command key `{key}` must be a list of RGBA tuples"))?;
+    Ok(colors
+        .into_iter()
+        .map(|(r, g, b, a)| [r, g, b, a])
+        .collect())
+}
+
 fn load_cartridge<'py>(module: &'py Bound<'py, PyModule>) -> Result<Bound<'py, PyAny>> {
     let cartridge = module
         .getattr("cartridge")
@@ -977,12 +1888,13 @@ fn call_cartridge1_f64(cartridge: &Bound<'_, PyAny>, callback: &str, value: f64)
 fn call_cartridge1_event(
     cartridge: &Bound<'_, PyAny>,
     callback: &str,
+    event_type: &str,
     event: &PyPhysicsEvent,
 ) -> Result<()> {
     let py = cartridge.py();
     let payload = PyDict::new_bound(py);
     payload
-        .set_item("type", "physics.collision")
+        .set_item("type", event_type)
         .context("failed to set event type")?;
     payload
         .set_item("a", event.a.as_str())
@@ -990,6 +1902,44 @@ fn call_cartridge1_event(
     payload
         .set_item("b", event.b.as_str())
         .context("failed to set event b")?;
+    payload
+        .set_item("sensor", event.sensor)
+        .context("failed to set event sensor")?;
+    payload
+        .set_item("normal", event.normal)
+        .context("failed to set event normal")?;
+    payload
+        .set_item("point", event.point)
+        .context("failed to set event point")?;
+    payload
+        .set_item("depth", event.depth)
+        .context("failed to set event depth")?;
+    payload
+        .set_item("impulse", event.impulse)
+        .context("failed to set event impulse")?;
+
+    cartridge
+        .call_method1(callback, (payload,))
+        .with_context(|| format!("cartridge lifecycle method `{callback}(event)` failed"))?;
+    Ok(())
+}
+
+fn call_cartridge1_audio_finished(
+    cartridge: &Bound<'_, PyAny>,
+    callback: &str,
+    voice: &ActiveVoice,
+) -> Result<()> {
+    let py = cartridge.py();
+    let payload = PyDict::new_bound(py);
+    payload
+        .set_item("type", "audio.finished")
+        .context("failed to set event type")?;
+    payload
+        .set_item("voice_id", voice.voice_id)
+        .context("failed to set event voice_id")?;
+    payload
+        .set_item("clip_id", voice.clip_id.as_str())
+        .context("failed to set event clip_id")?;
 
     cartridge
         .call_method1(callback, (payload,))
@@ -1151,7 +2101,9 @@ mod tests {
     use std::time::{SystemTime, UNIX_EPOCH};
 
     use super::{run_cartridge, ScriptedInputProvider};
+    use crate::audio_backend::ActiveAudioBackend;
     use crate::render_backend::ActiveRenderBackend;
+    use vcon_engine::audio::encode_wav_pcm16;
     use vcon_engine::PhysicsBackend;
 
     #[test]
@@ -1177,7 +2129,9 @@ mod tests {
             8,
             Some(&asset_dir),
             None,
+            None,
             ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
         )
         .expect("callbacks should execute");
 
@@ -1224,7 +2178,9 @@ def on_boot():
             8,
             None,
             None,
+            None,
             ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
         );
         let err = result.expect_err("network import should be blocked");
         let msg = format!("{err:#}");
@@ -1266,7 +2222,9 @@ def on_boot():
             8,
             None,
             None,
+            None,
             ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
         );
         let err = result.expect_err("non-sdk import should be blocked");
         let msg = format!("{err:#}");
@@ -1314,7 +2272,9 @@ cartridge = vcon.Cartridge(LocalImportGame())
             8,
             None,
             None,
+            None,
             ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
         )
         .expect("local helper import should succeed");
 
@@ -1362,7 +2322,9 @@ cartridge = vcon.Cartridge(LocalPackageGame())
             8,
             None,
             None,
+            None,
             ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
         )
         .expect("local package import should succeed");
 
@@ -1400,7 +2362,9 @@ class MissingCartridge(vcon.Game):
             8,
             None,
             None,
+            None,
             ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
         );
         let err = result.expect_err("missing cartridge should fail");
         let msg = format!("{err:#}");
@@ -1439,7 +2403,9 @@ builtins.__vcon_original_import__("socket")
             8,
             None,
             None,
+            None,
             ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
         );
         let err = result.expect_err("bypass attempt should fail");
         let msg = format!("{err:#}");
@@ -1478,7 +2444,9 @@ imp("socket")
             8,
             None,
             None,
+            None,
             ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
         );
         let err = result.expect_err("obfuscated bypass should fail");
         let msg = format!("{err:#}");
@@ -1491,6 +2459,838 @@ imp("socket")
         let _ = fs::remove_dir_all(&save_root);
     }
 
+    #[test]
+    fn publishes_audio_bus_state_to_sdk_health() {
+        let (root, entrypoint) = write_temp_entrypoint(
+            r#"
+import vcon
+
+
+class BusGame(vcon.Game):
+    def __init__(self):
+        self.ticks = 0
+
+    def on_boot(self):
+        vcon.audio.set_bus_volume("music", 0.25)
+        vcon.audio.set_bus_muted("ui")
+
+    def on_update(self, dt_fixed):
+        self.ticks += 1
+        if self.ticks == 2:
+            buses = vcon.audio.health()["buses"]
+            assert buses["music"]["volume"] == 0.25, buses
+            assert buses["ui"]["muted"] is True, buses
+            assert buses["sfx"]["volume"] == 1.0, buses
+
+
+cartridge = vcon.Cartridge(BusGame())
+"#,
+        );
+        let save_root = std::env::temp_dir().join("vcon-runtime-save-test-audio-bus");
+        let _ = fs::remove_dir_all(&save_root);
+        let mut provider = ScriptedInputProvider::default();
+
+        let report = run_cartridge(
+            &entrypoint,
+            &root,
+            Path::new("../vcon-sdk"),
+            3,
+            1.0 / 60.0,
+            1280,
+            800,
+            &mut provider,
+            &save_root,
+            8,
+            None,
+            None,
+            None,
+            ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
+        )
+        .expect("bus state should round-trip to the sdk");
+        assert_eq!(report.on_update_calls, 3);
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&save_root);
+    }
+
+    #[test]
+    fn applies_voice_automation_and_crossfades_music() {
+        let (root, entrypoint) = write_temp_entrypoint(
+            r#"
+import vcon
+
+
+class FadeGame(vcon.Game):
+    def __init__(self):
+        self.ticks = 0
+        self.title = None
+
+    def on_boot(self):
+        vcon.audio.play_music("title")
+
+    def on_update(self, dt_fixed):
+        self.ticks += 1
+        voices = vcon.audio.active_voices()
+        if self.ticks == 2:
+            self.title = voices[0]["voice_id"]
+            vcon.audio.set_voice(self.title, pan=-0.5, pitch=2.0)
+            vcon.audio.fade_to(self.title, 0.0, 1.0)
+        if self.ticks == 3:
+            assert voices[0]["pan"] == -0.5, voices
+            assert voices[0]["pitch"] == 2.0, voices
+            assert voices[0]["fading"] is True, voices
+            vcon.audio.crossfade_music("gameplay", 0.05)
+        if self.ticks == 8:
+            assert [v["clip_id"] for v in voices] == ["gameplay"], voices
+            assert voices[0]["volume"] == 1.0, voices
+            assert voices[0]["fading"] is False, voices
+
+
+cartridge = vcon.Cartridge(FadeGame())
+"#,
+        );
+        // Music needs real clips: voices of unknown clips retire on their first mix.
+        let assets_dir = root.join("assets");
+        fs::create_dir_all(&assets_dir).expect("asset dir should be created");
+        let clip = encode_wav_pcm16(&[0.25; 480 * 2], 48_000);
+        for name in ["title.wav", "gameplay.wav"] {
+            fs::write(assets_dir.join(name), &clip).expect("clip should be written");
+        }
+        let save_root = std::env::temp_dir().join("vcon-runtime-save-test-audio-fade");
+        let _ = fs::remove_dir_all(&save_root);
+        let mut provider = ScriptedInputProvider::default();
+
+        let report = run_cartridge(
+            &entrypoint,
+            &root,
+            Path::new("../vcon-sdk"),
+            8,
+            1.0 / 60.0,
+            1280,
+            800,
+            &mut provider,
+            &save_root,
+            8,
+            Some(&assets_dir),
+            None,
+            None,
+            ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
+        )
+        .expect("voice automation should round-trip to the sdk");
+        assert_eq!(report.on_update_calls, 8);
+        assert_eq!(report.audio_events_dispatched, 0);
+        assert!(report.audio_unknown_clips.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&save_root);
+    }
+
+    #[test]
+    fn plays_synth_tone_and_reports_finished_event() {
+        let (root, entrypoint) = write_temp_entrypoint(
+            r#"
+import vcon
+
+
+class ToneGame(vcon.Game):
+    def __init__(self):
+        self.ticks = 0
+
+    def on_boot(self):
+        vcon.audio.tone("square", 440, 0.04, envelope={"release": 0.0}, volume=0.5)
+
+    def on_update(self, dt_fixed):
+        self.ticks += 1
+        if self.ticks == 2:
+            voices = vcon.audio.active_voices()
+            assert [v["clip_id"] for v in voices] == ["synth:square"], voices
+            assert voices[0]["length_frames"] == 1920, voices
+
+    def on_event(self, event):
+        assert event["type"] == "audio.finished", event
+        assert event["clip_id"] == "synth:square", event
+        assert self.ticks == 3, self.ticks
+
+
+cartridge = vcon.Cartridge(ToneGame())
+"#,
+        );
+        let save_root = std::env::temp_dir().join("vcon-runtime-save-test-audio-tone");
+        let _ = fs::remove_dir_all(&save_root);
+        let mut provider = ScriptedInputProvider::default();
+
+        let report = run_cartridge(
+            &entrypoint,
+            &root,
+            Path::new("../vcon-sdk"),
+            5,
+            1.0 / 60.0,
+            1280,
+            800,
+            &mut provider,
+            &save_root,
+            8,
+            None,
+            None,
+            None,
+            ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
+        )
+        .expect("synth tone should play without assets");
+        assert_eq!(report.audio_events_dispatched, 1);
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&save_root);
+    }
+
+    #[test]
+    fn camera_persists_across_frames_and_maps_screen_to_world() {
+        let (root, entrypoint) = write_temp_entrypoint(
+            r#"
+import vcon
+
+
+class CameraGame(vcon.Game):
+    def on_boot(self):
+        vcon.graphics.camera(100, 50, zoom=2.0)
+
+    def on_update(self, dt_fixed):
+        assert vcon.graphics.screen_to_world(640, 400) == (100.0, 50.0)
+        assert vcon.graphics.world_to_screen(110, 50) == (660.0, 400.0)
+
+    def on_render(self, alpha):
+        vcon.graphics.push_transform()
+        vcon.graphics.translate(10, 0)
+        vcon.graphics.rotate(0.5)
+        vcon.graphics.rect(0, 0, 4, 4, (255, 0, 0, 255))
+        vcon.graphics.pop_transform()
+        vcon.graphics.set_layer(2)
+        vcon.graphics.set_clip(0, 0, 64, 16)
+        vcon.graphics.polygon([(0, 0), (8, 0), (4, 6)], (0, 255, 0, 255))
+        vcon.graphics.triangles([(0, 0), (8, 0), (0, 8)], (0, 0, 255, 128))
+        vcon.graphics.reset_clip()
+
+
+cartridge = vcon.Cartridge(CameraGame())
+"#,
+        );
+        let save_root = std::env::temp_dir().join("vcon-runtime-save-test-camera");
+        let _ = fs::remove_dir_all(&save_root);
+        let mut provider = ScriptedInputProvider::default();
+
+        let report = run_cartridge(
+            &entrypoint,
+            &root,
+            Path::new("../vcon-sdk"),
+            3,
+            1.0 / 60.0,
+            1280,
+            800,
+            &mut provider,
+            &save_root,
+            8,
+            None,
+            None,
+            None,
+            ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
+        )
+        .expect("camera commands should render");
+        // Camera re-emitted each frame plus nine draw and state commands.
+        assert_eq!(report.draw_commands_submitted, 33);
+        assert_eq!(report.draw_commands_rendered, 33);
+        assert_eq!(report.draw_commands_unsupported, 0);
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&save_root);
+    }
+
+    #[test]
+    fn tilemaps_sync_edits_and_reject_tiles_beyond_tileset() {
+        let (root, entrypoint) = write_temp_entrypoint(
+            r#"
+import vcon
+
+
+class TilemapGame(vcon.Game):
+    def on_boot(self):
+        vcon.graphics.tilemap_create("tiles", 40, 20, 8, 8, cells=[[0] * 40] * 20)
+        self.frame = 0
+
+    def on_update(self, dt_fixed):
+        self.frame += 1
+        vcon.graphics.tilemap_set(self.frame, 0, -1)
+        assert vcon.graphics.tilemap_get(self.frame, 0) == -1
+        if self.frame == 2:
+            vcon.graphics.tilemap_set(0, 0, 2)
+
+    def on_render(self, alpha):
+        vcon.graphics.tilemap(4, 4)
+
+
+cartridge = vcon.Cartridge(TilemapGame())
+"#,
+        );
+        let assets_dir = root.join("assets");
+        fs::create_dir_all(&assets_dir).expect("asset dir should be created");
+        let mut tileset = b"P6\n16 8\n255\n".to_vec();
+        tileset.extend(std::iter::repeat_n(200u8, 16 * 8 * 3));
+        fs::write(assets_dir.join("tiles.ppm"), tileset).expect("tileset should be written");
+        let save_root = std::env::temp_dir().join("vcon-runtime-save-test-tilemap");
+        let _ = fs::remove_dir_all(&save_root);
+        let mut provider = ScriptedInputProvider::default();
+
+        let err = run_cartridge(
+            &entrypoint,
+            &root,
+            Path::new("../vcon-sdk"),
+            3,
+            1.0 / 60.0,
+            320,
+            200,
+            &mut provider,
+            &save_root,
+            8,
+            Some(&assets_dir),
+            None,
+            None,
+            ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
+        )
+        .expect_err("tile 2 is outside a two-tile tileset");
+        let message = format!("{err:#}");
+        assert!(
+            message.contains("tile 2 is outside a tileset of 2 tiles"),
+            "{message}"
+        );
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&save_root);
+    }
+
+    #[test]
+    fn measure_text_matches_builtin_and_bitmap_font_metrics() {
+        let (root, entrypoint) = write_temp_entrypoint(
+            r#"
+import vcon
+
+
+class FontGame(vcon.Game):
+    def on_update(self, dt_fixed):
+        assert vcon.graphics.measure_text("abc", 16) == (54.0, 16.0)
+        assert vcon.graphics.measure_text("AB", 8, font="tiny") == (14.0, 10.0)
+        wrapped = vcon.graphics.measure_text("aa bb cc", 8, max_width=54, line_spacing=1.5)
+        assert wrapped == (45.0, 20.0)
+        assert vcon.graphics.measure_text("abcdefg", 8, max_width=27) == (27.0, 24.0)
+        try:
+            vcon.graphics.measure_text("x", font="missing")
+        except KeyError:
+            pass
+        else:
+            raise AssertionError("unknown fonts must raise")
+
+    def on_render(self, alpha):
+        vcon.graphics.text("AB", 0, 0, size=8, font="tiny")
+        vcon.graphics.text("AB", 0, 20, size=8)
+        vcon.graphics.text("aa bb cc", 0, 40, size=8, max_width=54, align="center")
+
+
+cartridge = vcon.Cartridge(FontGame())
+"#,
+        );
+        let assets_dir = root.join("assets");
+        fs::create_dir_all(&assets_dir).expect("asset dir should be created");
+        let page = vcon_engine::encode_png_rgba(8, 4, &[255; 8 * 4 * 4]);
+        fs::write(assets_dir.join("tiny_0.png"), page).expect("font page should be written");
+        fs::write(
+            assets_dir.join("tiny.fnt"),
+            "info face=\"tiny\" size=4\ncommon lineHeight=5 base=4 pages=1\n\
+             page id=0 file=\"tiny_0.png\"\n\
+             char id=65 x=0 y=0 width=3 height=4 xoffset=0 yoffset=1 xadvance=4 page=0\n\
+             char id=66 x=4 y=0 width=3 height=4 xoffset=0 yoffset=1 xadvance=4 page=0\n\
+             kerning first=65 second=66 amount=-1\n",
+        )
+        .expect("font descriptor should be written");
+        let save_root = std::env::temp_dir().join("vcon-runtime-save-test-fonts");
+        let _ = fs::remove_dir_all(&save_root);
+        let mut provider = ScriptedInputProvider::default();
+
+        let report = run_cartridge(
+            &entrypoint,
+            &root,
+            Path::new("../vcon-sdk"),
+            2,
+            1.0 / 60.0,
+            64,
+            64,
+            &mut provider,
+            &save_root,
+            8,
+            Some(&assets_dir),
+            None,
+            None,
+            ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
+        )
+        .expect("font text should render");
+        assert_eq!(report.draw_commands_rendered, 6);
+        assert_eq!(report.draw_commands_unsupported, 0);
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&save_root);
+    }
+
+    #[test]
+    fn render_targets_persist_from_boot_and_draw_as_sprites() {
+        let (root, entrypoint) = write_temp_entrypoint(
+            r#"
+import vcon
+
+
+class TargetGame(vcon.Game):
+    def on_boot(self):
+        self.minimap = vcon.graphics.create_target("minimap", 32, 16)
+        assert self.minimap == "target:minimap"
+        try:
+            vcon.graphics.create_target("huge", 4096, 2048)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError("targets past the budget must raise")
+
+    def on_render(self, alpha):
+        vcon.graphics.set_target("minimap")
+        vcon.graphics.clear((0, 64, 0, 255))
+        vcon.graphics.set_target()
+        vcon.graphics.sprite(self.minimap, 8, 8)
+
+
+cartridge = vcon.Cartridge(TargetGame())
+"#,
+        );
+        let save_root = std::env::temp_dir().join("vcon-runtime-save-test-targets");
+        let _ = fs::remove_dir_all(&save_root);
+        let mut provider = ScriptedInputProvider::default();
+
+        let report = run_cartridge(
+            &entrypoint,
+            &root,
+            Path::new("../vcon-sdk"),
+            2,
+            1.0 / 60.0,
+            64,
+            64,
+            &mut provider,
+            &save_root,
+            8,
+            None,
+            None,
+            None,
+            ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
+        )
+        .expect("render target commands should render");
+        // The SDK re-declares the target each frame ahead of the four cartridge commands.
+        assert_eq!(report.draw_commands_rendered, 10);
+        assert_eq!(report.draw_commands_unsupported, 0);
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&save_root);
+    }
+
+    #[test]
+    fn palette_mode_cycles_and_accepts_index_colors() {
+        let (root, entrypoint) = write_temp_entrypoint(
+            r#"
+import vcon
+
+
+class PaletteGame(vcon.Game):
+    def on_boot(self):
+        vcon.graphics.set_palette([(0, 0, 0), (255, 0, 0), (0, 255, 0), (0, 0, 255, 255)])
+        try:
+            vcon.graphics.set_palette([])
+        except ValueError:
+            pass
+        else:
+            raise AssertionError("empty palettes must raise")
+        self.frame = 0
+
+    def on_render(self, alpha):
+        self.frame += 1
+        if self.frame == 3:
+            vcon.graphics.clear((0, 0, 0, 255))
+            vcon.graphics.rect(4, 4, 8, 8, (255, 0, 0, 255))
+            return
+        vcon.graphics.palette_cycle(1, 3)
+        expected = [(0, 0, 255, 255), (0, 255, 0, 255)][self.frame - 1]
+        assert vcon.graphics.palette()[1] == expected, vcon.graphics.palette()
+        vcon.graphics.clear(0)
+        vcon.graphics.rect(4, 4, 8, 8, 1)
+        if self.frame == 2:
+            # Too late for this frame's draws; the SDK resends it before the next frame.
+            vcon.graphics.reset_palette()
+
+
+cartridge = vcon.Cartridge(PaletteGame())
+"#,
+        );
+        let save_root = std::env::temp_dir().join("vcon-runtime-save-test-palette");
+        let _ = fs::remove_dir_all(&save_root);
+        let mut provider = ScriptedInputProvider::default();
+
+        let report = run_cartridge(
+            &entrypoint,
+            &root,
+            Path::new("../vcon-sdk"),
+            3,
+            1.0 / 60.0,
+            64,
+            64,
+            &mut provider,
+            &save_root,
+            8,
+            None,
+            None,
+            None,
+            ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
+        )
+        .expect("palette commands should render");
+        // Each palette frame re-sends the palette, then cycles it, clears and draws; the last
+        // frame starts with the resent reset and draws in RGBA.
+        assert_eq!(report.draw_commands_rendered, 11);
+        assert_eq!(report.draw_commands_unsupported, 1);
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&save_root);
+    }
+
+    #[test]
+    fn physics_shapes_round_trip_and_rest_on_static_boxes() {
+        let (root, entrypoint) = write_temp_entrypoint(
+            r#"
+import vcon
+
+
+class ShapesGame(vcon.Game):
+    def on_boot(self):
+        vcon.physics.set_gravity(0.0, 600.0)
+        vcon.physics.upsert_body(
+            "ground", 100.0, 200.0, shape=vcon.physics.box(400, 20), dynamic=False, restitution=0.0
+        )
+        vcon.physics.upsert_body(
+            "crate", 40.0, 100.0, shape=vcon.physics.box(20, 20), restitution=0.0
+        )
+        vcon.physics.upsert_body(
+            "pill", 100.0, 100.0, shape=vcon.physics.capsule(30, 5), restitution=0.0
+        )
+        vcon.physics.upsert_body(
+            "wedge",
+            160.0,
+            100.0,
+            shape=vcon.physics.polygon([(0, -10), (10, 10), (-10, 10)]),
+            restitution=0.0,
+        )
+        assert vcon.physics.body("ground")["dynamic"] is False
+        assert vcon.physics.body("crate")["dynamic"] is True
+        self.frames = 0
+        self.landing = None
+
+    def on_event(self, event):
+        if event["type"] == "physics.contact_begin" and (event["a"], event["b"]) == (
+            "crate",
+            "ground",
+        ):
+            self.landing = event
+
+    def on_update(self, dt_fixed):
+        self.frames += 1
+        # Bodies follow their upserted state, so carry the simulated state forward.
+        for name in ("crate", "pill", "wedge"):
+            body = vcon.physics.body(name)
+            vcon.physics.upsert_body(
+                name,
+                body["x"],
+                body["y"],
+                body["vx"],
+                body["vy"],
+                shape=body["shape"],
+                rotation=body["rotation"],
+                restitution=0.0,
+            )
+
+    def on_render(self, alpha):
+        if self.frames < 90:
+            return
+        ground_top = 190.0
+        crate = vcon.physics.body("crate")
+        pill = vcon.physics.body("pill")
+        wedge = vcon.physics.body("wedge")
+        assert crate["shape"] == {"type": "box", "width": 20.0, "height": 20.0}, crate
+        assert abs(crate["y"] - (ground_top - 10.0)) < 0.5, crate
+        assert pill["shape"]["type"] == "capsule" and pill["radius"] == 5.0, pill
+        assert abs(pill["y"] - (ground_top - 15.0)) < 0.5, pill
+        assert abs(wedge["y"] - (ground_top - 10.0)) < 0.5, wedge
+        assert vcon.physics.body("ground")["y"] == 200.0
+        assert self.landing["normal"] == (0.0, 1.0), self.landing
+        assert abs(self.landing["point"][1] - ground_top) < 10.0, self.landing
+        # The crate first touches the ground while moving, before the solver has pushed on it.
+        assert self.landing["depth"] >= 0.0 and self.landing["impulse"] == 0.0, self.landing
+
+
+cartridge = vcon.Cartridge(ShapesGame())
+"#,
+        );
+        let save_root = std::env::temp_dir().join("vcon-runtime-save-test-shapes");
+        let _ = fs::remove_dir_all(&save_root);
+        let mut provider = ScriptedInputProvider::default();
+
+        let report = run_cartridge(
+            &entrypoint,
+            &root,
+            Path::new("../vcon-sdk"),
+            95,
+            1.0 / 60.0,
+            64,
+            64,
+            &mut provider,
+            &save_root,
+            8,
+            None,
+            None,
+            None,
+            ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
+        )
+        .expect("shaped bodies should simulate");
+        assert_eq!(report.on_render_calls, 95);
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&save_root);
+    }
+
+    #[test]
+    fn sensors_and_masks_deliver_contact_begin_and_end() {
+        let (root, entrypoint) = write_temp_entrypoint(
+            r#"
+import vcon
+
+
+class TriggerGame(vcon.Game):
+    def on_boot(self):
+        vcon.physics.upsert_body("zone", 100.0, 0.0, radius=20.0, dynamic=False, sensor=True)
+        vcon.physics.upsert_body(
+            "wall", 200.0, 0.0, shape=vcon.physics.box(10, 100), dynamic=False, category=0b10
+        )
+        vcon.physics.upsert_body("player", 40.0, 0.0, vx=300.0, radius=5.0, mask=0b01)
+        self.frames = 0
+        self.events = []
+
+    def on_update(self, dt_fixed):
+        self.frames += 1
+        player = vcon.physics.body("player")
+        vcon.physics.upsert_body(
+            "player", player["x"], player["y"], player["vx"], player["vy"], radius=5.0, mask=0b01
+        )
+
+    def on_event(self, event):
+        self.events.append((event["type"], event["a"], event["b"], event["sensor"]))
+
+    def on_render(self, alpha):
+        if self.frames < 60:
+            return
+        assert self.events == [
+            ("physics.contact_begin", "player", "zone", True),
+            ("physics.collision", "player", "zone", True),
+            ("physics.contact_end", "player", "zone", True),
+        ], self.events
+        player = vcon.physics.body("player")
+        assert abs(player["x"] - 340.0) < 1e-6 and player["vx"] == 300.0, player
+
+
+cartridge = vcon.Cartridge(TriggerGame())
+"#,
+        );
+        let save_root = std::env::temp_dir().join("vcon-runtime-save-test-contacts");
+        let _ = fs::remove_dir_all(&save_root);
+        let mut provider = ScriptedInputProvider::default();
+
+        let report = run_cartridge(
+            &entrypoint,
+            &root,
+            Path::new("../vcon-sdk"),
+            60,
+            1.0 / 60.0,
+            64,
+            64,
+            &mut provider,
+            &save_root,
+            8,
+            None,
+            None,
+            None,
+            ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
+        )
+        .expect("sensor and masked bodies should simulate");
+        assert_eq!(report.physics_events_dispatched, 2);
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&save_root);
+    }
+
+    #[test]
+    fn removed_bodies_end_their_contacts_by_name() {
+        let (root, entrypoint) = write_temp_entrypoint(
+            r#"
+import vcon
+
+
+class PickupGame(vcon.Game):
+    def on_boot(self):
+        self.frames = 0
+        self.events = []
+
+    def on_update(self, dt_fixed):
+        self.frames += 1
+        if self.frames == 1:
+            vcon.physics.upsert_body("player", 0.0, 0.0, radius=8.0, dynamic=False, sensor=True)
+            vcon.physics.upsert_body("coin", 4.0, 0.0, radius=4.0, dynamic=False)
+        elif self.frames == 3:
+            vcon.physics.remove_body("coin")
+
+    def on_event(self, event):
+        self.events.append((self.frames, event["type"], event["a"], event["b"]))
+
+    def on_render(self, alpha):
+        if self.frames < 5:
+            return
+        assert self.events == [
+            (1, "physics.contact_begin", "coin", "player"),
+            (1, "physics.collision", "coin", "player"),
+            (3, "physics.contact_end", "coin", "player"),
+        ], self.events
+
+
+cartridge = vcon.Cartridge(PickupGame())
+"#,
+        );
+        let save_root = std::env::temp_dir().join("vcon-runtime-save-test-removed-contacts");
+        let _ = fs::remove_dir_all(&save_root);
+        let mut provider = ScriptedInputProvider::default();
+
+        let report = run_cartridge(
+            &entrypoint,
+            &root,
+            Path::new("../vcon-sdk"),
+            5,
+            1.0 / 60.0,
+            64,
+            64,
+            &mut provider,
+            &save_root,
+            8,
+            None,
+            None,
+            None,
+            ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
+        )
+        .expect("removed bodies should end their contacts");
+        assert_eq!(report.physics_events_dispatched, 2);
+        assert_eq!(report.on_event_calls, 3);
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&save_root);
+    }
+
+    #[test]
+    fn kinematic_bodies_spin_and_damped_bodies_slow() {
+        let (root, entrypoint) = write_temp_entrypoint(
+            r#"
+import vcon
+
+
+class MotionGame(vcon.Game):
+    def on_boot(self):
+        vcon.physics.set_gravity(0.0, 600.0)
+        self.frames = 0
+
+    def on_update(self, dt_fixed):
+        self.frames += 1
+        mover = vcon.physics.body("mover") or {
+            "x": 0.0, "y": 0.0, "vx": 60.0, "vy": 0.0, "rotation": 0.0, "angular_velocity": 1.0
+        }
+        vcon.physics.upsert_body(
+            "mover",
+            mover["x"],
+            mover["y"],
+            mover["vx"],
+            mover["vy"],
+            shape=vcon.physics.box(40, 8),
+            rotation=mover["rotation"],
+            angular_velocity=mover["angular_velocity"],
+            kind="kinematic",
+        )
+        drifter = vcon.physics.body("drifter") or {"x": 0.0, "y": 300.0, "vx": 100.0, "vy": 0.0}
+        vcon.physics.upsert_body(
+            "drifter",
+            drifter["x"],
+            drifter["y"],
+            drifter["vx"],
+            drifter["vy"],
+            radius=4.0,
+            mass=2.0,
+            linear_damping=1.0,
+        )
+
+    def on_render(self, alpha):
+        if self.frames < 60:
+            return
+        mover = vcon.physics.body("mover")
+        # Kinematic bodies ignore gravity and keep their velocity and spin.
+        assert abs(mover["x"] - 60.0) < 1e-6 and mover["y"] == 0.0, mover
+        assert abs(mover["rotation"] - 1.0) < 1e-6, mover
+        assert mover["angular_velocity"] == 1.0, mover
+        # Each step scales velocity by 1 / (1 + dt * damping).
+        expected = 100.0 * (1.0 / (1.0 + 1.0 / 60.0)) ** 60
+        assert abs(vcon.physics.body("drifter")["vx"] - expected) < 1e-6
+
+
+cartridge = vcon.Cartridge(MotionGame())
+"#,
+        );
+        let save_root = std::env::temp_dir().join("vcon-runtime-save-test-motion");
+        let _ = fs::remove_dir_all(&save_root);
+        let mut provider = ScriptedInputProvider::default();
+
+        let report = run_cartridge(
+            &entrypoint,
+            &root,
+            Path::new("../vcon-sdk"),
+            60,
+            1.0 / 60.0,
+            64,
+            64,
+            &mut provider,
+            &save_root,
+            8,
+            None,
+            None,
+            None,
+            ActiveRenderBackend::Software,
+            ActiveAudioBackend::Simulated,
+        )
+        .expect("kinematic bodies should simulate");
+        assert_eq!(report.on_render_calls, 60);
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&save_root);
+    }
+
     fn write_temp_entrypoint(source: &str) -> (PathBuf, PathBuf) {
         write_temp_entrypoint_with_files(source, &[])
     }
@@ -1,7 +1,7 @@
 use std::io::Write;
 
 use anyhow::{bail, Result};
-use vcon_engine::{AssetStore, FrameCommandBuffer, RenderStats, SoftwareFrame};
+use vcon_engine::{AssetStore, FrameCommandBuffer, RenderStats, SoftwareFrame, TilemapStore};
 
 use crate::wgpu_presenter::{probe_wgpu_support, WgpuPresenter};
 
@@ -137,8 +137,11 @@ impl RenderExecutor {
         &mut self,
         commands: &FrameCommandBuffer,
         assets: Option<&AssetStore>,
+        tilemaps: &TilemapStore,
     ) -> RenderStats {
-        let stats = self.surface.apply_with_assets(commands, assets);
+        let stats = self
+            .surface
+            .apply_with_resources(commands, assets, Some(tilemaps));
 
         if let Some(gpu) = self.gpu_post.as_mut() {
             if let Err(err) = gpu.process(self.surface.pixels()) {
@@ -5,7 +5,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context, Result};
-use vcon_engine::InputFrame;
+use vcon_engine::{DisplayConfig, DisplayFilter, InputFrame};
 use winit::dpi::LogicalSize;
 use winit::event::{ElementState, Event, WindowEvent};
 use winit::event_loop::EventLoop;
@@ -107,10 +107,13 @@ pub struct WindowFrameObserver {
     state: Rc<RefCell<WindowRuntimeState>>,
 }
 
+/// Opens a `width` x `height` window that shows frames of the `display` resolution, upscaled by the
+/// largest whole multiple that fits and letterboxed with black bars.
 pub fn create_window_runtime(
     title: &str,
     width: u32,
     height: u32,
+    display: DisplayConfig,
     target_fps: u32,
 ) -> Result<(WindowInputProvider, WindowFrameObserver)> {
     let event_loop = EventLoop::new().context("failed to create winit event loop")?;
@@ -121,13 +124,13 @@ pub fn create_window_runtime(
                 WindowAttributes::default()
                     .with_title(title)
                     .with_inner_size(LogicalSize::new(width as f64, height as f64))
-                    .with_resizable(false),
+                    .with_resizable(true),
             )
             .context("failed to create window")?,
     );
 
-    let presenter = WgpuWindowPresenter::new(window.clone(), width, height)
-        .context("wgpu window init failed")?;
+    let presenter =
+        WgpuWindowPresenter::new(window.clone(), display).context("wgpu window init failed")?;
 
     let target_frame_duration = if target_fps == 0 {
         Duration::ZERO
@@ -222,6 +225,37 @@ impl FrameObserver for WindowFrameObserver {
     }
 }
 
+/// Window-pixel rectangle the frame is drawn into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Letterbox {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// Centres the largest whole multiple of `frame` that fits in `window`. A window smaller than the
+/// frame shrinks it to fit instead, keeping the aspect ratio.
+fn letterbox(frame: (u32, u32), window: (u32, u32)) -> Letterbox {
+    let (frame_w, frame_h) = (frame.0.max(1), frame.1.max(1));
+    let whole = (window.0 / frame_w).min(window.1 / frame_h);
+    let (width, height) = if whole >= 1 {
+        (frame_w * whole, frame_h * whole)
+    } else {
+        let fit = (window.0 as f64 / frame_w as f64).min(window.1 as f64 / frame_h as f64);
+        (
+            ((frame_w as f64 * fit).round() as u32).max(1),
+            ((frame_h as f64 * fit).round() as u32).max(1),
+        )
+    };
+    Letterbox {
+        x: window.0.saturating_sub(width) / 2,
+        y: window.1.saturating_sub(height) / 2,
+        width,
+        height,
+    }
+}
+
 struct WgpuWindowPresenter {
     window: Arc<winit::window::Window>,
     surface: wgpu::Surface<'static>,
@@ -229,18 +263,21 @@ struct WgpuWindowPresenter {
     queue: wgpu::Queue,
     config: wgpu::SurfaceConfiguration,
     frame_texture: wgpu::Texture,
+    params_buffer: wgpu::Buffer,
     bind_group: wgpu::BindGroup,
     pipeline: wgpu::RenderPipeline,
     frame_width: u32,
     frame_height: u32,
+    filter: DisplayFilter,
 }
 
 impl WgpuWindowPresenter {
-    fn new(
-        window: Arc<winit::window::Window>,
-        frame_width: u32,
-        frame_height: u32,
-    ) -> Result<Self> {
+    fn new(window: Arc<winit::window::Window>, display: DisplayConfig) -> Result<Self> {
+        let DisplayConfig {
+            width: frame_width,
+            height: frame_height,
+            filter,
+        } = display;
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
         let surface = instance
             .create_surface(window.clone())
@@ -326,6 +363,13 @@ impl WgpuWindowPresenter {
             mipmap_filter: wgpu::FilterMode::Nearest,
             ..wgpu::SamplerDescriptor::default()
         });
+        // Frame height, output scale and filter, padded to a 16-byte uniform.
+        let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("vcon-window-present-params"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
 
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("vcon-window-bind-group-layout"),
@@ -346,6 +390,16 @@ impl WgpuWindowPresenter {
                     ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -361,6 +415,10 @@ impl WgpuWindowPresenter {
                     binding: 1,
                     resource: wgpu::BindingResource::Sampler(&sampler),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: params_buffer.as_entire_binding(),
+                },
             ],
         });
 
@@ -371,6 +429,15 @@ impl WgpuWindowPresenter {
 @group(0) @binding(0) var frame_tex: texture_2d<f32>;
 @group(0) @binding(1) var frame_sampler: sampler;
 
+struct PresentParams {
+    frame_height: f32,
+    scale: f32,
+    // 0 none, 1 scanlines, 2 crt
+    mode: f32,
+    _pad: f32,
+};
+@group(0) @binding(2) var<uniform> params: PresentParams;
+
 struct VsOut {
     @builtin(position) position: vec4<f32>,
     @location(0) uv: vec2<f32>,
@@ -396,7 +463,24 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VsOut {
 
 @fragment
 fn fs_main(in: VsOut) -> @location(0) vec4<f32> {
-    return textureSample(frame_tex, frame_sampler, in.uv);
+    var uv = in.uv;
+    let centered = uv * 2.0 - 1.0;
+    let crt = params.mode > 1.5;
+    if crt {
+        // Bulge the image like curved glass.
+        uv = centered * (1.0 + 0.04 * dot(centered, centered)) * 0.5 + 0.5;
+    }
+    var color = textureSampleLevel(frame_tex, frame_sampler, clamp(uv, vec2<f32>(0.0), vec2<f32>(1.0)), 0.0);
+    // Scanlines darken the lower half of every source row once rows are at least two pixels tall.
+    if params.mode > 0.5 && params.scale >= 2.0 && fract(uv.y * params.frame_height) >= 0.5 {
+        color = vec4<f32>(color.rgb * 0.6, color.a);
+    }
+    if crt {
+        let inside = all(uv >= vec2<f32>(0.0)) && all(uv <= vec2<f32>(1.0));
+        let vignette = 1.0 - 0.15 * dot(centered, centered);
+        color = vec4<f32>(select(vec3<f32>(0.0), color.rgb * vignette, inside), 1.0);
+    }
+    return color;
 }
 "#
                 .into(),
@@ -441,10 +525,12 @@ fn fs_main(in: VsOut) -> @location(0) vec4<f32> {
             queue,
             config,
             frame_texture,
+            params_buffer,
             bind_group,
             pipeline,
             frame_width,
             frame_height,
+            filter,
         })
     }
 
@@ -510,6 +596,24 @@ fn fs_main(in: VsOut) -> @location(0) vec4<f32> {
             }
         };
 
+        let viewport = letterbox(
+            (self.frame_width, self.frame_height),
+            (self.config.width, self.config.height),
+        );
+        let filter = match self.filter {
+            DisplayFilter::None => 0.0_f32,
+            DisplayFilter::Scanlines => 1.0,
+            DisplayFilter::Crt => 2.0,
+        };
+        let params = [
+            self.frame_height as f32,
+            viewport.height as f32 / self.frame_height as f32,
+            filter,
+            0.0,
+        ];
+        let params: Vec<u8> = params.iter().flat_map(|v| v.to_le_bytes()).collect();
+        self.queue.write_buffer(&self.params_buffer, 0, &params);
+
         let view = frame
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
@@ -534,6 +638,14 @@ fn fs_main(in: VsOut) -> @location(0) vec4<f32> {
                 occlusion_query_set: None,
                 timestamp_writes: None,
             });
+            pass.set_viewport(
+                viewport.x as f32,
+                viewport.y as f32,
+                viewport.width as f32,
+                viewport.height as f32,
+                0.0,
+                1.0,
+            );
             pass.set_pipeline(&self.pipeline);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.draw(0..3, 0..1);
@@ -544,3 +656,41 @@ fn fs_main(in: VsOut) -> @location(0) vec4<f32> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{letterbox, Letterbox};
+
+    #[test]
+    fn letterbox_uses_whole_multiples_and_centres_the_frame() {
+        assert_eq!(
+            letterbox((320, 200), (1280, 800)),
+            Letterbox {
+                x: 0,
+                y: 0,
+                width: 1280,
+                height: 800
+            }
+        );
+        // 320x240 fits three times into 1280x800, leaving bars on every side.
+        assert_eq!(
+            letterbox((320, 240), (1280, 800)),
+            Letterbox {
+                x: 160,
+                y: 40,
+                width: 960,
+                height: 720
+            }
+        );
+        // A window smaller than the frame shrinks it instead of cropping.
+        assert_eq!(
+            letterbox((640, 400), (320, 400)),
+            Letterbox {
+                x: 0,
+                y: 100,
+                width: 320,
+                height: 200
+            }
+        );
+    }
+}
@@ -17,6 +17,8 @@ fn runtime_initializes_audio_backend_and_processes_playback_commands() {
         .arg(&sdk_root)
         .arg("--frames")
         .arg("36")
+        .arg("--audio-backend")
+        .arg("simulated")
         .output()
         .expect("runtime should execute");
 
@@ -25,8 +27,128 @@ fn runtime_initializes_audio_backend_and_processes_playback_commands() {
     let stdout = String::from_utf8_lossy(&output.stdout);
     assert!(stdout.contains("Loaded cartridge: Audio Smoke"));
     assert!(stdout.contains("Loop callbacks invoked: on_update=36 on_render=36"));
+    assert!(stdout.contains("audio events: 5"));
+    assert!(stdout.contains("voices_stolen=2"));
+    assert!(
+        !stdout.contains("Audio unknown clip ids"),
+        "smoke cartridge clips should all resolve: {stdout}"
+    );
     assert!(stdout.contains("Audio backend: simulated-device"));
     assert!(stdout.contains("underruns="));
     assert!(stdout.contains("overruns="));
     assert!(stdout.contains("dropped_buffers="));
 }
+
+#[test]
+fn runtime_dumps_mixed_audio_from_wav_assets() {
+    let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let cartridge = workspace.join("cartridges/audio-smoke");
+    let sdk_root = workspace.join("vcon-sdk");
+    let saves_root = std::env::temp_dir().join("vcon-runtime-audio-mix-saves");
+    let dump_audio = std::env::temp_dir().join("vcon-runtime-audio-mix.wav");
+    let _ = std::fs::remove_file(&dump_audio);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vcon-runtime"))
+        .arg("--cartridge")
+        .arg(&cartridge)
+        .arg("--saves-root")
+        .arg(&saves_root)
+        .arg("--sdk-root")
+        .arg(&sdk_root)
+        .arg("--frames")
+        .arg("12")
+        .arg("--dump-audio")
+        .arg(&dump_audio)
+        .output()
+        .expect("runtime should execute");
+
+    assert!(
+        output.status.success(),
+        "runtime must succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Audio mix: frames=9600"));
+    assert!(stdout.contains("Dumped audio mix to"));
+
+    let wav = std::fs::read(&dump_audio).expect("dumped mix should exist");
+    assert!(wav.starts_with(b"RIFF"), "wav header should exist");
+    let samples = wav[44..]
+        .chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+        .collect::<Vec<_>>();
+    assert_eq!(samples.len(), 9600 * 2);
+    assert!(
+        samples.iter().any(|s| s.unsigned_abs() > 1000),
+        "looped music clip should produce audible samples"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn ring_buffer_backend_streams_to_host_player_and_falls_back_without_one() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let cartridge = workspace.join("cartridges/audio-smoke");
+    let sdk_root = workspace.join("vcon-sdk");
+    let saves_root = std::env::temp_dir().join("vcon-runtime-audio-ring-saves");
+    let bin_dir = std::env::temp_dir().join("vcon-runtime-audio-ring-bin");
+    let captured = bin_dir.join("captured.raw");
+    let _ = std::fs::remove_dir_all(&bin_dir);
+    std::fs::create_dir_all(&bin_dir).expect("fake player dir should be created");
+
+    let player = bin_dir.join("aplay");
+    std::fs::write(
+        &player,
+        format!("#!/bin/sh\nexec /bin/cat > '{}'\n", captured.display()),
+    )
+    .expect("fake player should be written");
+    std::fs::set_permissions(&player, std::fs::Permissions::from_mode(0o755))
+        .expect("fake player should be executable");
+
+    let run = |path: &Path| {
+        Command::new(env!("CARGO_BIN_EXE_vcon-runtime"))
+            .env("PATH", path)
+            .arg("--cartridge")
+            .arg(&cartridge)
+            .arg("--saves-root")
+            .arg(&saves_root)
+            .arg("--sdk-root")
+            .arg(&sdk_root)
+            .arg("--frames")
+            .arg("12")
+            .arg("--audio-backend")
+            .arg("ring-buffer")
+            .output()
+            .expect("runtime should execute")
+    };
+
+    let output = run(&bin_dir);
+    assert!(
+        output.status.success(),
+        "runtime must succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Audio backend: ring-buffer (requested=RingBuffer"),
+        "unexpected stdout: {stdout}"
+    );
+    let raw = std::fs::read(&captured).expect("player should receive the stream");
+    assert!(
+        !raw.is_empty() && raw.len() % 8 == 0,
+        "expected f32 stereo frames"
+    );
+
+    let empty_dir = bin_dir.join("empty");
+    std::fs::create_dir_all(&empty_dir).expect("empty PATH dir should be created");
+    let output = run(&empty_dir);
+    assert!(output.status.success(), "fallback run must succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Audio backend: simulated-device (requested=RingBuffer"));
+    assert!(stdout.contains("Audio backend fallback: no audio output found"));
+
+    let _ = std::fs::remove_dir_all(&bin_dir);
+}
@@ -3,7 +3,15 @@ use std::process::Command;
 
 fn run_and_dump(backend: &str, dump_file: &str) -> (String, Vec<u8>) {
     let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
-    let cartridge = workspace.join("cartridges/sample-game");
+    run_cartridge_and_dump(
+        &workspace.join("cartridges/sample-game"),
+        backend,
+        dump_file,
+    )
+}
+
+fn run_cartridge_and_dump(cartridge: &Path, backend: &str, dump_file: &str) -> (String, Vec<u8>) {
+    let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
     let sdk_root = workspace.join("vcon-sdk");
     let saves_root = std::env::temp_dir().join(format!("vcon-runtime-backend-parity-{backend}"));
     let path = std::env::temp_dir().join(dump_file);
@@ -11,7 +19,7 @@ fn run_and_dump(backend: &str, dump_file: &str) -> (String, Vec<u8>) {
 
     let output = Command::new(env!("CARGO_BIN_EXE_vcon-runtime"))
         .arg("--cartridge")
-        .arg(&cartridge)
+        .arg(cartridge)
         .arg("--saves-root")
         .arg(&saves_root)
         .arg("--sdk-root")
@@ -57,3 +65,50 @@ fn software_and_wgpu_dump_frames_match() {
         "software and wgpu backend dumps diverged"
     );
 }
+
+#[test]
+fn blend_modes_match_across_backends() {
+    let cartridge = std::env::temp_dir().join("vcon-runtime-blend-parity");
+    let _ = std::fs::remove_dir_all(&cartridge);
+    std::fs::create_dir_all(cartridge.join("src")).expect("cartridge dir should be created");
+    std::fs::write(
+        cartridge.join("vcon.toml"),
+        "id = \"com.vcon.blend_parity\"\nname = \"Blend Parity\"\nversion = \"0.1.0\"\n\
+         entrypoint = \"src/main.py\"\nsdk_version = \"2\"\nassets_path = \"assets\"\nsave_quota_mb = 1\n",
+    )
+    .expect("manifest should be written");
+    std::fs::write(
+        cartridge.join("src/main.py"),
+        r#"import vcon
+
+
+class BlendGame(vcon.Game):
+    def on_render(self, alpha):
+        vcon.graphics.clear((40, 60, 80, 255))
+        for i, mode in enumerate(("alpha", "add", "multiply", "screen")):
+            vcon.graphics.set_blend(mode)
+            vcon.graphics.circle(60 + i * 60, 100, 40, (200, 120, 40, 160))
+            vcon.graphics.text(mode, 30 + i * 60, 150, size=8, color=(255, 255, 255, 200))
+
+
+cartridge = vcon.Cartridge(BlendGame())
+"#,
+    )
+    .expect("entrypoint should be written");
+
+    let (_software_stdout, software_dump) =
+        run_cartridge_and_dump(&cartridge, "software", "vcon-blend-parity-software.ppm");
+    let (wgpu_stdout, wgpu_dump) =
+        run_cartridge_and_dump(&cartridge, "wgpu", "vcon-blend-parity-wgpu.ppm");
+    let _ = std::fs::remove_dir_all(&cartridge);
+
+    if !wgpu_stdout.contains("Render backend: requested=Wgpu active=wgpu") {
+        eprintln!("Skipping blend parity check: wgpu backend unavailable in environment");
+        return;
+    }
+
+    assert_eq!(
+        software_dump, wgpu_dump,
+        "software and wgpu blend mode dumps diverged"
+    );
+}
@@ -44,3 +44,102 @@ fn steam_deck_profile_frame_pacing_smoke_budget() {
         elapsed.as_secs_f64()
     );
 }
+
+#[test]
+fn physics_world_steps_a_thousand_bodies_within_budget() {
+    use vcon_engine::{
+        CollisionShape, PhysicsBody2D, PhysicsBodyKind, PhysicsVec2, PhysicsWorld, SceneGraph,
+    };
+
+    fn run() -> (Vec<(f64, f64)>, usize, f64) {
+        let mut scene = SceneGraph::new();
+        let root = scene.root();
+        let ground = scene.add_node(root, "ground").expect("ground node");
+        scene
+            .set_node_transform(ground, 640.0, 790.0, 0.0, 1.0, 1.0)
+            .expect("ground transform");
+        scene
+            .set_physics_body(
+                ground,
+                PhysicsBody2D {
+                    kind: PhysicsBodyKind::Static,
+                    shape: CollisionShape::Box {
+                        half_width: 640.0,
+                        half_height: 10.0,
+                    },
+                    velocity_x: 0.0,
+                    velocity_y: 0.0,
+                    restitution: 0.2,
+                    ..PhysicsBody2D::default()
+                },
+            )
+            .expect("ground body");
+
+        // A 40x25 field of bullets, spaced so neighbours start close but apart.
+        let mut bullets = Vec::new();
+        for i in 0..1000 {
+            let node = scene
+                .add_node(root, format!("bullet-{i}"))
+                .expect("bullet node");
+            let x = 20.0 + (i % 40) as f64 * 31.0;
+            let y = 20.0 + (i / 40) as f64 * 24.0;
+            scene
+                .set_node_transform(node, x, y, 0.0, 1.0, 1.0)
+                .expect("bullet transform");
+            scene
+                .set_physics_body(
+                    node,
+                    PhysicsBody2D {
+                        kind: PhysicsBodyKind::Dynamic,
+                        shape: CollisionShape::Circle { radius: 4.0 },
+                        velocity_x: ((i * 7) % 13) as f64 * 10.0 - 60.0,
+                        velocity_y: ((i * 5) % 11) as f64 * 10.0 - 50.0,
+                        restitution: 0.5,
+                        ..PhysicsBody2D::default()
+                    },
+                )
+                .expect("bullet body");
+            bullets.push(node);
+        }
+
+        let mut world = PhysicsWorld::new();
+        world.set_gravity(PhysicsVec2::new(0.0, 300.0));
+
+        let start = Instant::now();
+        let mut events = 0;
+        for _ in 0..120 {
+            world.sync_from_scene(&scene);
+            events += world.step(1.0 / 60.0).len();
+            world.apply_to_scene(&mut scene);
+        }
+        let elapsed = start.elapsed().as_secs_f64();
+
+        let positions = bullets
+            .iter()
+            .map(|id| {
+                let node = scene.node(*id).expect("bullet exists");
+                (node.transform.x, node.transform.y)
+            })
+            .collect();
+        (positions, events, elapsed)
+    }
+
+    let (first, events, elapsed) = run();
+    let (second, replay_events, _) = run();
+
+    assert!(
+        events > 0,
+        "bullets should collide with the ground or each other"
+    );
+    assert_eq!(events, replay_events);
+    assert_eq!(
+        first, second,
+        "broad phase must keep stepping deterministic"
+    );
+
+    // 120 steps at 60 Hz; soft budget so debug CI builds stay green.
+    assert!(
+        elapsed < 6.0,
+        "1000-body physics exceeded performance smoke budget: {elapsed:.3}s"
+    );
+}
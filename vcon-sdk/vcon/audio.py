@@ -3,6 +3,11 @@
 _play_requests = []
 _stop_voice_ids = []
 _stop_all = False
+_voice_updates = []
+_voice_fades = []
+_voice_limit = None
+_bus_updates = []
+_ducking = None
 _runtime_active_voices = {}
 _runtime_health = {
     "initialized": True,
@@ -10,8 +15,26 @@ _runtime_health = {
     "underruns": 0,
     "overruns": 0,
     "dropped_buffers": 0,
+    "voices_stolen": 0,
 }
 
+DEFAULT_SFX_PRIORITY = 64
+DEFAULT_MUSIC_PRIORITY = 192
+STEAL_POLICIES = ("oldest", "quietest", "lowest_priority")
+BUSES = ("music", "sfx", "ui")
+MIN_PITCH = 0.125
+MAX_PITCH = 8.0
+WAVES = ("square", "triangle", "saw", "noise")
+MAX_TONE_FREQUENCY = 20000.0
+DEFAULT_ENVELOPE = {"attack": 0.005, "decay": 0.05, "sustain": 0.7, "release": 0.05}
+
+
+def _default_buses():
+    return {name: {"volume": 1.0, "muted": False, "duck_gain": 1.0} for name in BUSES}
+
+
+_runtime_health["buses"] = _default_buses()
+
 
 def _clamp_volume(volume):
     value = float(volume)
@@ -22,24 +45,174 @@ def _clamp_volume(volume):
     return value
 
 
-def play_sfx(clip_id, volume=1.0):
+def _validate_priority(priority):
+    value = int(priority)
+    if value < 0 or value > 255:
+        raise ValueError("priority must be in 0..=255")
+    return value
+
+
+def _validate_seconds(seconds, name):
+    value = float(seconds)
+    if value < 0.0:
+        raise ValueError(f"{name} must be >= 0")
+    return value
+
+
+def _validate_bus(bus):
+    name = str(bus)
+    if name not in BUSES:
+        raise ValueError(f"bus must be one of {', '.join(BUSES)}")
+    return name
+
+
+def play_sfx(clip_id, volume=1.0, priority=DEFAULT_SFX_PRIORITY, bus="sfx", fade_in=0.0):
     key = str(clip_id)
     if not key:
         raise ValueError("clip_id must be non-empty")
-    _play_requests.append({"clip_id": key, "volume": _clamp_volume(volume), "looped": False})
+    _play_requests.append(
+        {
+            "clip_id": key,
+            "volume": _clamp_volume(volume),
+            "looped": False,
+            "priority": _validate_priority(priority),
+            "bus": _validate_bus(bus),
+            "fade_in": _validate_seconds(fade_in, "fade_in"),
+        }
+    )
 
 
-def play_music(clip_id, volume=1.0, looped=True):
+def play_music(
+    clip_id, volume=1.0, looped=True, priority=DEFAULT_MUSIC_PRIORITY, bus="music", fade_in=0.0
+):
     key = str(clip_id)
     if not key:
         raise ValueError("clip_id must be non-empty")
     _play_requests.append(
-        {"clip_id": key, "volume": _clamp_volume(volume), "looped": bool(looped)}
+        {
+            "clip_id": key,
+            "volume": _clamp_volume(volume),
+            "looped": bool(looped),
+            "priority": _validate_priority(priority),
+            "bus": _validate_bus(bus),
+            "fade_in": _validate_seconds(fade_in, "fade_in"),
+        }
     )
 
 
-def stop_voice(voice_id):
-    _stop_voice_ids.append(int(voice_id))
+def _normalize_envelope(envelope):
+    if envelope is None:
+        return dict(DEFAULT_ENVELOPE)
+    if isinstance(envelope, dict):
+        unknown = set(envelope) - set(DEFAULT_ENVELOPE)
+        if unknown:
+            raise ValueError(f"unknown envelope keys: {', '.join(sorted(unknown))}")
+        values = {**DEFAULT_ENVELOPE, **envelope}
+    else:
+        parts = tuple(envelope)
+        if len(parts) != 4:
+            raise ValueError("envelope must be (attack, decay, sustain, release)")
+        values = dict(zip(("attack", "decay", "sustain", "release"), parts))
+    return {
+        "attack": _validate_seconds(values["attack"], "attack"),
+        "decay": _validate_seconds(values["decay"], "decay"),
+        "sustain": _clamp_volume(values["sustain"]),
+        "release": _validate_seconds(values["release"], "release"),
+    }
+
+
+def tone(
+    wave, freq, duration, envelope=None, volume=1.0, priority=DEFAULT_SFX_PRIORITY, bus="sfx"
+):
+    name = str(wave)
+    if name not in WAVES:
+        raise ValueError(f"wave must be one of {', '.join(WAVES)}")
+    frequency = float(freq)
+    if frequency <= 0.0 or frequency > MAX_TONE_FREQUENCY:
+        raise ValueError(f"freq must be in (0, {MAX_TONE_FREQUENCY:g}]")
+    _play_requests.append(
+        {
+            "clip_id": f"synth:{name}",
+            "volume": _clamp_volume(volume),
+            "looped": False,
+            "priority": _validate_priority(priority),
+            "bus": _validate_bus(bus),
+            "synth": {
+                "wave": name,
+                "frequency": frequency,
+                "duration": _validate_seconds(duration, "duration"),
+                **_normalize_envelope(envelope),
+            },
+        }
+    )
+
+
+def crossfade_music(clip_id, seconds, volume=1.0, looped=True):
+    fade = _validate_seconds(seconds, "seconds")
+    for voice in active_voices():
+        if voice["bus"] == "music":
+            stop_voice(voice["voice_id"], fade_out=fade)
+    play_music(clip_id, volume=volume, looped=looped, fade_in=fade)
+
+
+def set_voice(voice_id, volume=None, pan=None, pitch=None):
+    update = {"voice_id": int(voice_id)}
+    if volume is not None:
+        update["volume"] = _clamp_volume(volume)
+    if pan is not None:
+        update["pan"] = max(-1.0, min(1.0, float(pan)))
+    if pitch is not None:
+        value = float(pitch)
+        if value <= 0.0:
+            raise ValueError("pitch must be > 0")
+        update["pitch"] = max(MIN_PITCH, min(MAX_PITCH, value))
+    _voice_updates.append(update)
+
+
+def fade_to(voice_id, volume, seconds):
+    _voice_fades.append(
+        {
+            "voice_id": int(voice_id),
+            "volume": _clamp_volume(volume),
+            "seconds": _validate_seconds(seconds, "seconds"),
+            "stop": False,
+        }
+    )
+
+
+def set_bus_volume(name, volume):
+    _bus_updates.append({"bus": _validate_bus(name), "volume": _clamp_volume(volume)})
+
+
+def set_bus_muted(name, muted=True):
+    _bus_updates.append({"bus": _validate_bus(name), "muted": bool(muted)})
+
+
+def set_ducking(enabled=True, gain=0.4, fade_seconds=0.15):
+    global _ducking
+    fade = float(fade_seconds)
+    if fade < 0.0:
+        raise ValueError("fade_seconds must be >= 0")
+    _ducking = {"enabled": bool(enabled), "gain": _clamp_volume(gain), "fade_seconds": fade}
+
+
+def set_voice_limit(max_voices, steal_policy="oldest"):
+    global _voice_limit
+    count = int(max_voices)
+    if count < 1:
+        raise ValueError("max_voices must be >= 1")
+    policy = str(steal_policy)
+    if policy not in STEAL_POLICIES:
+        raise ValueError(f"steal_policy must be one of {', '.join(STEAL_POLICIES)}")
+    _voice_limit = {"max_voices": count, "steal_policy": policy}
+
+
+def stop_voice(voice_id, fade_out=0.0):
+    fade = _validate_seconds(fade_out, "fade_out")
+    if fade == 0.0:
+        _stop_voice_ids.append(int(voice_id))
+        return
+    _voice_fades.append({"voice_id": int(voice_id), "volume": 0.0, "seconds": fade, "stop": True})
 
 
 def stop_all():
@@ -52,19 +225,32 @@ def active_voices():
 
 
 def health():
-    return dict(_runtime_health)
+    snapshot = dict(_runtime_health)
+    snapshot["buses"] = {name: dict(state) for name, state in _runtime_health["buses"].items()}
+    return snapshot
 
 
 def _export_runtime_state():
-    global _play_requests, _stop_voice_ids, _stop_all
+    global _play_requests, _stop_voice_ids, _stop_all, _voice_updates, _voice_fades
+    global _voice_limit, _bus_updates, _ducking
     payload = {
         "play_requests": list(_play_requests),
         "stop_voice_ids": list(_stop_voice_ids),
         "stop_all": bool(_stop_all),
+        "voice_updates": list(_voice_updates),
+        "voice_fades": list(_voice_fades),
+        "voice_limit": _voice_limit,
+        "bus_updates": list(_bus_updates),
+        "ducking": _ducking,
     }
     _play_requests = []
     _stop_voice_ids = []
     _stop_all = False
+    _voice_updates = []
+    _voice_fades = []
+    _voice_limit = None
+    _bus_updates = []
+    _ducking = None
     return payload
 
 
@@ -79,6 +265,13 @@ def _set_runtime_state(active_voices, health):
             "clip_id": str(voice["clip_id"]),
             "volume": float(voice["volume"]),
             "looped": bool(voice["looped"]),
+            "priority": int(voice.get("priority", DEFAULT_SFX_PRIORITY)),
+            "bus": str(voice.get("bus", "sfx")),
+            "pan": float(voice.get("pan", 0.0)),
+            "pitch": float(voice.get("pitch", 1.0)),
+            "fading": bool(voice.get("fading", False)),
+            "cursor": float(voice.get("cursor", 0.0)),
+            "length_frames": int(voice.get("length_frames", 0)),
         }
     _runtime_active_voices = next_active
 
@@ -88,4 +281,12 @@ def _set_runtime_state(active_voices, health):
         "underruns": int(health.get("underruns", 0)),
         "overruns": int(health.get("overruns", 0)),
         "dropped_buffers": int(health.get("dropped_buffers", 0)),
+        "voices_stolen": int(health.get("voices_stolen", 0)),
+        "buses": _default_buses(),
     }
+    for name, state in dict(health.get("buses", {})).items():
+        _runtime_health["buses"][str(name)] = {
+            "volume": float(state.get("volume", 1.0)),
+            "muted": bool(state.get("muted", False)),
+            "duck_gain": float(state.get("duck_gain", 1.0)),
+        }
@@ -4,21 +4,72 @@ Cartridges call these functions during `on_render`; runtime drains commands
 for engine-side validation and submission.
 """
 
+import math
+
+RENDER_TARGET_PREFIX = "target:"
+MAX_PALETTE_COLORS = 256
+
 _frame_commands = []
 _surface_width = 1280
 _surface_height = 800
 _render_backend = "software"
+_camera = None
+_tilemaps = {}
+_tilemap_creates = []
+_tile_updates = []
+_measure_text = None
+_targets = {}
+_target_budget = 16 << 20
+_palette = None
+# A reset issued after the frame's first draw is refused, so it is sent again next frame.
+_palette_reset_pending = False
 
 
-def _set_runtime_state(surface_width, surface_height, render_backend="software"):
-    global _surface_width, _surface_height, _render_backend
+def _set_runtime_state(
+    surface_width,
+    surface_height,
+    render_backend="software",
+    measure_text=None,
+    target_budget=16 << 20,
+):
+    global _surface_width, _surface_height, _render_backend, _camera, _measure_text
+    global _target_budget, _palette, _palette_reset_pending
+    _camera = None
+    _palette = None
+    _palette_reset_pending = False
+    _measure_text = measure_text
+    _targets.clear()
+    _target_budget = int(target_budget)
+    _tilemaps.clear()
+    _tilemap_creates.clear()
+    _tile_updates.clear()
     _surface_width = int(surface_width)
     _surface_height = int(surface_height)
     _render_backend = str(render_backend)
 
 
+def _export_runtime_state():
+    payload = {
+        "tilemap_creates": list(_tilemap_creates),
+        "tile_updates": list(_tile_updates),
+    }
+    _tilemap_creates.clear()
+    _tile_updates.clear()
+    return payload
+
+
 def begin_frame():
+    global _palette_reset_pending
     _frame_commands.clear()
+    for name, (width, height) in _targets.items():
+        _emit_create_target(name, width, height)
+    if _palette is not None:
+        _emit_palette()
+    elif _palette_reset_pending:
+        _palette_reset_pending = False
+        _frame_commands.append({"kind": "reset_palette"})
+    if _camera is not None:
+        _emit_camera()
 
 
 def drain_commands():
@@ -27,8 +78,14 @@ def drain_commands():
     return commands
 
 
+def _color(color):
+    if isinstance(color, int):
+        return (color, 0, 0, 255)
+    return tuple(color)
+
+
 def clear(color):
-    _frame_commands.append({"kind": "clear", "color": tuple(color)})
+    _frame_commands.append({"kind": "clear", "color": _color(color)})
 
 
 def line(x1, y1, x2, y2, color, thickness=1.0):
@@ -39,7 +96,7 @@ def line(x1, y1, x2, y2, color, thickness=1.0):
             "y1": float(y1),
             "x2": float(x2),
             "y2": float(y2),
-            "color": tuple(color),
+            "color": _color(color),
             "thickness": float(thickness),
         }
     )
@@ -53,7 +110,7 @@ def rect(x, y, w, h, color, filled=True, thickness=1.0):
             "y": float(y),
             "w": float(w),
             "h": float(h),
-            "color": tuple(color),
+            "color": _color(color),
             "filled": bool(filled),
             "thickness": float(thickness),
         }
@@ -67,14 +124,29 @@ def circle(x, y, r, color, filled=True, thickness=1.0):
             "x": float(x),
             "y": float(y),
             "r": float(r),
-            "color": tuple(color),
+            "color": _color(color),
             "filled": bool(filled),
             "thickness": float(thickness),
         }
     )
 
 
-def sprite(asset_id, x, y, rotation=0.0, scale=1.0, color=(255, 255, 255, 255)):
+def sprite(
+    asset_id,
+    x,
+    y,
+    rotation=0.0,
+    scale=1.0,
+    color=(255, 255, 255, 255),
+    flip_x=False,
+    flip_y=False,
+    origin=(0.0, 0.0),
+):
+    if isinstance(scale, (tuple, list)):
+        scale_x, scale_y = scale
+    else:
+        scale_x = scale_y = scale
+    origin_x, origin_y = origin
     _frame_commands.append(
         {
             "kind": "sprite",
@@ -82,13 +154,74 @@ def sprite(asset_id, x, y, rotation=0.0, scale=1.0, color=(255, 255, 255, 255)):
             "x": float(x),
             "y": float(y),
             "rotation": float(rotation),
-            "scale": float(scale),
+            "scale_x": float(scale_x),
+            "scale_y": float(scale_y),
+            "flip_x": bool(flip_x),
+            "flip_y": bool(flip_y),
+            "origin_x": float(origin_x),
+            "origin_y": float(origin_y),
             "color": tuple(color),
         }
     )
 
 
-def text(value, x, y, size=16.0, color=(255, 255, 255, 255)):
+def polygon(points, color, filled=True, thickness=1.0):
+    _frame_commands.append(
+        {
+            "kind": "polygon",
+            "points": [(float(x), float(y)) for x, y in points],
+            "color": _color(color),
+            "filled": bool(filled),
+            "thickness": float(thickness),
+        }
+    )
+
+
+def triangles(vertices, colors):
+    vertices = [(float(x), float(y)) for x, y in vertices]
+    if isinstance(colors, int) or (
+        len(colors) == 4 and all(isinstance(c, int) for c in colors)
+    ):
+        colors = [colors] * len(vertices)
+    _frame_commands.append(
+        {
+            "kind": "triangles",
+            "vertices": vertices,
+            "colors": [_color(c) for c in colors],
+        }
+    )
+
+
+def textured_quad(
+    asset_id,
+    points,
+    uvs=((0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)),
+    color=(255, 255, 255, 255),
+):
+    _frame_commands.append(
+        {
+            "kind": "textured_quad",
+            "asset_id": str(asset_id),
+            "points": [(float(x), float(y)) for x, y in points],
+            "uvs": [(float(u), float(v)) for u, v in uvs],
+            "color": tuple(color),
+        }
+    )
+
+
+def text(
+    value,
+    x,
+    y,
+    size=16.0,
+    color=(255, 255, 255, 255),
+    font=None,
+    max_width=None,
+    align="left",
+    line_spacing=1.0,
+):
+    if align not in ("left", "center", "right"):
+        raise ValueError(f"unsupported text align {align!r}")
     _frame_commands.append(
         {
             "kind": "text",
@@ -96,11 +229,261 @@ def text(value, x, y, size=16.0, color=(255, 255, 255, 255)):
             "x": float(x),
             "y": float(y),
             "size": float(size),
+            "font": None if font is None else str(font),
+            "max_width": None if max_width is None else float(max_width),
+            "align": align,
+            "line_spacing": float(line_spacing),
+            "color": _color(color),
+        }
+    )
+
+
+def measure_text(value, size=16.0, font=None, max_width=None, line_spacing=1.0):
+    if _measure_text is None:
+        raise RuntimeError("measure_text needs the runtime host")
+    font = None if font is None else str(font)
+    size_px = _measure_text(
+        str(value),
+        float(size),
+        font,
+        None if max_width is None else float(max_width),
+        float(line_spacing),
+    )
+    if size_px is None:
+        raise KeyError(f"unknown font {font!r}")
+    return size_px
+
+
+def tilemap_create(tileset, columns, rows, tile_w, tile_h, cells=None, name="main"):
+    columns = int(columns)
+    rows = int(rows)
+    if cells is None:
+        flat = [-1] * (columns * rows)
+    else:
+        flat = []
+        for cell in cells:
+            if isinstance(cell, (list, tuple)):
+                flat.extend(int(tile) for tile in cell)
+            else:
+                flat.append(int(cell))
+    if len(flat) != columns * rows:
+        raise ValueError(f"tilemap expects {columns * rows} cells, got {len(flat)}")
+    _tilemaps[str(name)] = {"columns": columns, "rows": rows, "cells": flat}
+    _tilemap_creates.append(
+        {
+            "name": str(name),
+            "tileset": str(tileset),
+            "columns": columns,
+            "rows": rows,
+            "tile_size": (int(tile_w), int(tile_h)),
+            "cells": list(flat),
+        }
+    )
+
+
+def tilemap_set(x, y, tile, name="main"):
+    grid = _tilemaps.get(str(name))
+    if grid is None:
+        raise KeyError(f"unknown tilemap {name!r}")
+    x = int(x)
+    y = int(y)
+    if not (0 <= x < grid["columns"] and 0 <= y < grid["rows"]):
+        raise IndexError(f"tile cell ({x}, {y}) is outside tilemap {name!r}")
+    grid["cells"][y * grid["columns"] + x] = int(tile)
+    _tile_updates.append((str(name), x, y, int(tile)))
+
+
+def tilemap_get(x, y, name="main"):
+    grid = _tilemaps.get(str(name))
+    if grid is None:
+        raise KeyError(f"unknown tilemap {name!r}")
+    x = int(x)
+    y = int(y)
+    if not (0 <= x < grid["columns"] and 0 <= y < grid["rows"]):
+        raise IndexError(f"tile cell ({x}, {y}) is outside tilemap {name!r}")
+    return grid["cells"][y * grid["columns"] + x]
+
+
+def tilemap(x=0.0, y=0.0, name="main", color=(255, 255, 255, 255)):
+    _frame_commands.append(
+        {
+            "kind": "tilemap",
+            "tilemap": str(name),
+            "x": float(x),
+            "y": float(y),
             "color": tuple(color),
         }
     )
 
 
+def create_target(name, width, height):
+    name = str(name)
+    width = int(width)
+    height = int(height)
+    if width <= 0 or height <= 0:
+        raise ValueError("render target size must be > 0")
+    used = sum(w * h * 4 for key, (w, h) in _targets.items() if key != name)
+    if used + width * height * 4 > _target_budget:
+        raise ValueError(
+            f"render target {name!r} would exceed the {_target_budget} byte target budget"
+        )
+    _targets[name] = (width, height)
+    _emit_create_target(name, width, height)
+    return RENDER_TARGET_PREFIX + name
+
+
+def _emit_create_target(name, width, height):
+    _frame_commands.append(
+        {"kind": "create_target", "name": name, "width": width, "height": height}
+    )
+
+
+def set_target(name=None):
+    if name is not None and str(name) not in _targets:
+        raise KeyError(f"unknown render target {name!r}")
+    _frame_commands.append(
+        {"kind": "set_target", "name": None if name is None else str(name)}
+    )
+
+
+def release_target(name):
+    name = str(name)
+    if _targets.pop(name, None) is None:
+        raise KeyError(f"unknown render target {name!r}")
+    _frame_commands.append({"kind": "release_target", "name": name})
+
+
+def camera(x, y, zoom=1.0, rotation=0.0):
+    global _camera
+    _camera = (float(x), float(y), float(zoom), float(rotation))
+    _emit_camera()
+
+
+def reset_camera():
+    global _camera
+    _camera = None
+    _frame_commands.append(
+        {
+            "kind": "camera",
+            "x": _surface_width / 2.0,
+            "y": _surface_height / 2.0,
+            "zoom": 1.0,
+            "rotation": 0.0,
+        }
+    )
+
+
+def _emit_camera():
+    x, y, zoom, rotation = _camera
+    _frame_commands.append(
+        {"kind": "camera", "x": x, "y": y, "zoom": zoom, "rotation": rotation}
+    )
+
+
+def push_transform():
+    _frame_commands.append({"kind": "push_transform"})
+
+
+def pop_transform():
+    _frame_commands.append({"kind": "pop_transform"})
+
+
+def translate(x, y):
+    _frame_commands.append({"kind": "translate", "x": float(x), "y": float(y)})
+
+
+def scale(x, y=None):
+    y = x if y is None else y
+    _frame_commands.append({"kind": "scale", "x": float(x), "y": float(y)})
+
+
+def rotate(radians):
+    _frame_commands.append({"kind": "rotate", "rotation": float(radians)})
+
+
+def set_layer(layer):
+    _frame_commands.append({"kind": "layer", "layer": int(layer)})
+
+
+def set_blend(mode):
+    if mode not in ("alpha", "add", "multiply", "screen"):
+        raise ValueError(f"unsupported blend mode {mode!r}")
+    _frame_commands.append({"kind": "blend", "mode": mode})
+
+
+def set_palette(colors):
+    global _palette
+    colors = [tuple(int(c) for c in color) for color in colors]
+    if not 1 <= len(colors) <= MAX_PALETTE_COLORS:
+        raise ValueError(f"palette must have 1..={MAX_PALETTE_COLORS} colors")
+    for color in colors:
+        if len(color) not in (3, 4):
+            raise ValueError(f"palette color {color!r} must be RGB or RGBA")
+    _palette = [color if len(color) == 4 else color + (255,) for color in colors]
+    _emit_palette()
+
+
+def palette_cycle(start, count, steps=1):
+    if _palette is None:
+        raise RuntimeError("palette_cycle requires set_palette first")
+    start = int(start)
+    count = int(count)
+    if start < 0 or count <= 0 or start + count > len(_palette):
+        raise ValueError(f"palette range {start}..{start + count} is outside the palette")
+    shift = int(steps) % count
+    span = _palette[start : start + count]
+    _palette[start : start + count] = span[-shift:] + span[:-shift]
+    _emit_palette()
+
+
+def reset_palette():
+    global _palette, _palette_reset_pending
+    _palette = None
+    _palette_reset_pending = True
+    _frame_commands.append({"kind": "reset_palette"})
+
+
+def palette():
+    return None if _palette is None else list(_palette)
+
+
+def _emit_palette():
+    _frame_commands.append({"kind": "set_palette", "colors": list(_palette)})
+
+
+def set_clip(x, y, w, h):
+    _frame_commands.append(
+        {"kind": "set_clip", "x": float(x), "y": float(y), "w": float(w), "h": float(h)}
+    )
+
+
+def reset_clip():
+    _frame_commands.append({"kind": "reset_clip"})
+
+
+def screen_to_world(x, y):
+    if _camera is None:
+        return (float(x), float(y))
+    cam_x, cam_y, zoom, rotation = _camera
+    dx = (float(x) - _surface_width / 2.0) / zoom
+    dy = (float(y) - _surface_height / 2.0) / zoom
+    cos, sin = math.cos(rotation), math.sin(rotation)
+    return (cam_x + dx * cos - dy * sin, cam_y + dx * sin + dy * cos)
+
+
+def world_to_screen(x, y):
+    if _camera is None:
+        return (float(x), float(y))
+    cam_x, cam_y, zoom, rotation = _camera
+    dx = float(x) - cam_x
+    dy = float(y) - cam_y
+    cos, sin = math.cos(rotation), math.sin(rotation)
+    return (
+        _surface_width / 2.0 + (dx * cos + dy * sin) * zoom,
+        _surface_height / 2.0 + (-dx * sin + dy * cos) * zoom,
+    )
+
+
 def surface_width():
     return _surface_width
 
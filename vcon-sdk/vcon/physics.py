@@ -4,12 +4,46 @@ _gravity = (0.0, 0.0)
 _desired_bodies = {}
 _runtime_bodies = {}
 
+MAX_POLYGON_VERTICES = 8
+ALL_LAYERS = 0xFFFFFFFF
+BODY_KINDS = ("static", "kinematic", "dynamic")
+
 
 def set_gravity(x, y):
     global _gravity
     _gravity = (float(x), float(y))
 
 
+def circle(radius):
+    radius = float(radius)
+    if radius <= 0.0:
+        raise ValueError("radius must be greater than 0")
+    return {"type": "circle", "radius": radius}
+
+
+def box(width, height):
+    width = float(width)
+    height = float(height)
+    if width <= 0.0 or height <= 0.0:
+        raise ValueError("box width and height must be greater than 0")
+    return {"type": "box", "width": width, "height": height}
+
+
+def polygon(points):
+    points = [(float(x), float(y)) for x, y in points]
+    if not 3 <= len(points) <= MAX_POLYGON_VERTICES:
+        raise ValueError(f"polygon must have 3..={MAX_POLYGON_VERTICES} points")
+    return {"type": "polygon", "points": points}
+
+
+def capsule(height, radius):
+    height = float(height)
+    radius = float(radius)
+    if radius <= 0.0 or height < 2.0 * radius:
+        raise ValueError("capsule radius must be > 0 and height at least twice the radius")
+    return {"type": "capsule", "height": height, "radius": radius}
+
+
 def upsert_body(
     name,
     x,
@@ -19,29 +53,79 @@ def upsert_body(
     radius=16.0,
     dynamic=True,
     restitution=0.5,
+    shape=None,
+    rotation=0.0,
+    category=1,
+    mask=ALL_LAYERS,
+    sensor=False,
+    kind=None,
+    angular_velocity=0.0,
+    density=1.0,
+    mass=None,
+    friction=0.2,
+    linear_damping=0.0,
+    angular_damping=0.0,
 ):
     key = str(name)
     if not key:
         raise ValueError("body name must be non-empty")
 
-    radius = float(radius)
-    if radius <= 0.0:
-        raise ValueError("radius must be greater than 0")
+    if shape is None:
+        shape = circle(radius)
+    if not isinstance(shape, dict) or shape.get("type") not in (
+        "circle",
+        "box",
+        "polygon",
+        "capsule",
+    ):
+        raise ValueError("shape must come from circle(), box(), polygon() or capsule()")
 
     restitution = float(restitution)
     if restitution < 0.0 or restitution > 1.0:
         raise ValueError("restitution must be in [0.0, 1.0]")
 
+    category = int(category)
+    mask = int(mask)
+    if not 0 <= category <= ALL_LAYERS or not 0 <= mask <= ALL_LAYERS:
+        raise ValueError("category and mask must be 32-bit layer bits")
+
+    if kind is None:
+        kind = "dynamic" if dynamic else "static"
+    if kind not in BODY_KINDS:
+        raise ValueError("kind must be one of static, kinematic, dynamic")
+
+    density = float(density)
+    if density <= 0.0 or (mass is not None and float(mass) <= 0.0):
+        raise ValueError("density and mass must be greater than 0")
+    friction = float(friction)
+    linear_damping = float(linear_damping)
+    angular_damping = float(angular_damping)
+    if friction < 0.0 or linear_damping < 0.0 or angular_damping < 0.0:
+        raise ValueError("friction and damping must be at least 0")
+
     _desired_bodies[key] = {
         "name": key,
         "x": float(x),
         "y": float(y),
         "vx": float(vx),
         "vy": float(vy),
-        "radius": radius,
-        "dynamic": bool(dynamic),
+        "rotation": float(rotation),
+        "angular_velocity": float(angular_velocity),
+        "shape": dict(shape),
+        "kind": kind,
+        "dynamic": kind == "dynamic",
         "restitution": restitution,
+        "density": density,
+        "mass": None if mass is None else float(mass),
+        "friction": friction,
+        "linear_damping": linear_damping,
+        "angular_damping": angular_damping,
+        "category": category,
+        "mask": mask,
+        "sensor": bool(sensor),
     }
+    if "radius" in shape:
+        _desired_bodies[key]["radius"] = float(shape["radius"])
 
 
 def remove_body(name):
@@ -69,6 +153,13 @@ def _export_runtime_state():
     }
 
 
+def _reset_runtime_state():
+    global _gravity
+    _gravity = (0.0, 0.0)
+    _desired_bodies.clear()
+    _runtime_bodies.clear()
+
+
 def _set_runtime_state(gravity, bodies):
     global _gravity, _runtime_bodies
     gx, gy = gravity
@@ -76,12 +167,17 @@ def _set_runtime_state(gravity, bodies):
     next_runtime = {}
     for body in bodies:
         name = str(body["name"])
+        shape = dict(body["shape"])
         next_runtime[name] = {
             "name": name,
             "x": float(body["x"]),
             "y": float(body["y"]),
             "vx": float(body["vx"]),
             "vy": float(body["vy"]),
-            "radius": float(body["radius"]),
+            "rotation": float(body["rotation"]),
+            "angular_velocity": float(body["angular_velocity"]),
+            "shape": shape,
         }
+        if "radius" in shape:
+            next_runtime[name]["radius"] = float(shape["radius"])
     _runtime_bodies = next_runtime